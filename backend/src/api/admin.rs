@@ -0,0 +1,237 @@
+//! Maintenance endpoints
+//!
+//! Every request must carry `ADMIN_API_KEY` in the `X-Admin-Key` header. When no
+//! key is configured the endpoints are disabled and answer `403`. [`admin_guard`]
+//! enforces this for the whole `/admin` scope and for admin-only routes elsewhere.
+//!
+//! `POST /admin/reconcile` reconciles up to [`MAX_RECONCILE_VAULTS`] vaults on demand,
+//! for when one can't wait for the next reconciliation cycle.
+//!
+//! `GET /admin/audit/{vault_pubkey}/verify` walks a vault's audit trail hash chain
+//! and reports the first entry that was edited, removed or reordered.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::{from_fn, Next};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use shared::{error_codes, ApiResponse, ReconcileVaultsRequest, SetVaultFreezeRequest};
+
+use crate::api::error::{self, ApiError};
+use crate::api::validation;
+use crate::config::Config;
+use crate::services::tvl_recorder;
+use crate::services::{reconcile_vaults, AppState, ReconciliationResult};
+
+pub const ADMIN_KEY_HEADER: &str = "X-Admin-Key";
+
+/// Most vaults one `POST /admin/reconcile` request may name
+pub const MAX_RECONCILE_VAULTS: usize = 100;
+
+/// Outcome of reconciling one vault: its `result`, or the `error` that stopped it
+#[derive(Debug, Serialize)]
+pub struct VaultReconciliation {
+    pub vault_pubkey: String,
+    pub result: Option<ReconciliationResult>,
+    pub error: Option<String>,
+    pub error_code: Option<&'static str>,
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .wrap(from_fn(admin_guard))
+            .route("/tvl/recompute", web::post().to(recompute_tvl))
+            .route("/reconcile", web::post().to(reconcile_selected_vaults))
+            .route("/vault/{vault_pubkey}/freeze", web::post().to(set_vault_freeze))
+            .route("/audit/{vault_pubkey}/verify", web::get().to(verify_audit_chain)),
+    );
+}
+
+/// Middleware rejecting requests that fail [`require_admin`]
+pub async fn admin_guard(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(state) = req.app_data::<web::Data<AppState>>() else {
+        let resp = error::internal(error_codes::INTERNAL_ERROR, "Application state missing");
+        return Ok(req.into_response(resp).map_into_right_body());
+    };
+
+    if let Err(resp) = require_admin(req.request(), &state.config) {
+        return Ok(req.into_response(resp).map_into_right_body());
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+/// Recompute TVL from the `vaults` table, bypassing the cached stats
+async fn recompute_tvl(state: web::Data<AppState>) -> impl Responder {
+    tracing::info!("API: Recompute TVL");
+
+    match tvl_recorder::recompute_tvl(&state).await {
+        Ok(stats) => HttpResponse::Ok().json(ApiResponse::success(stats)),
+        Err(e) => {
+            tracing::error!("Failed to recompute TVL: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+/// Reconcile the requested vaults now, reporting each vault's outcome separately
+async fn reconcile_selected_vaults(
+    state: web::Data<AppState>,
+    req: web::Json<ReconcileVaultsRequest>,
+) -> impl Responder {
+    tracing::warn!("API: Reconcile {} vaults", req.vault_pubkeys.len());
+
+    if req.vault_pubkeys.is_empty() || req.vault_pubkeys.len() > MAX_RECONCILE_VAULTS {
+        return error::bad_request(
+            error_codes::INVALID_REQUEST,
+            format!("vault_pubkeys must name 1 to {} vaults", MAX_RECONCILE_VAULTS),
+        );
+    }
+    if let Err(resp) = req
+        .vault_pubkeys
+        .iter()
+        .try_for_each(|vault_pubkey| validation::pubkey("vault_pubkeys", vault_pubkey))
+    {
+        return resp;
+    }
+
+    let results = reconcile_vaults(&state, &req.vault_pubkeys).await;
+    let reconciliations: Vec<VaultReconciliation> = req
+        .vault_pubkeys
+        .iter()
+        .zip(results)
+        .map(|(vault_pubkey, result)| match result {
+            Ok(result) => VaultReconciliation {
+                vault_pubkey: vault_pubkey.clone(),
+                result: Some(result),
+                error: None,
+                error_code: None,
+            },
+            Err(e) => {
+                tracing::error!("Reconciliation failed for vault {}: {}", vault_pubkey, e);
+                VaultReconciliation {
+                    vault_pubkey: vault_pubkey.clone(),
+                    result: None,
+                    error: Some(e.to_string()),
+                    error_code: Some(e.code()),
+                }
+            }
+        })
+        .collect();
+
+    HttpResponse::Ok().json(ApiResponse::success(reconciliations))
+}
+
+/// Freeze or unfreeze a vault's withdrawals; deposits and reads are unaffected
+async fn set_vault_freeze(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    req: web::Json<SetVaultFreezeRequest>,
+) -> impl Responder {
+    tracing::warn!("API: Set vault {} frozen = {}", vault_pubkey, req.frozen);
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    let status = match state.database.set_vault_frozen(&vault_pubkey, req.frozen).await {
+        Ok(Some(status)) => status,
+        Ok(None) => return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to set vault freeze: {}", e);
+            return e.to_response();
+        }
+    };
+
+    let event_type = if status.frozen { "vault_frozen" } else { "vault_unfrozen" };
+    if let Err(e) = state
+        .database
+        .create_audit_entry(
+            event_type,
+            Some(&status.vault_pubkey),
+            None,
+            None,
+            None,
+            serde_json::json!({ "frozen": status.frozen }),
+            None,
+            None,
+        )
+        .await
+    {
+        tracing::error!("Failed to audit {} for vault {}: {}", event_type, vault_pubkey, e);
+    }
+
+    HttpResponse::Ok().json(ApiResponse::success(status))
+}
+
+/// Verify a vault's audit trail hash chain; a broken chain is reported, not an error
+async fn verify_audit_chain(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    tracing::info!("API: Verify audit chain for vault {}", vault_pubkey);
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match state.database.verify_audit_chain(&vault_pubkey).await {
+        Ok(report) => {
+            if !report.valid {
+                tracing::error!(
+                    "Audit chain for vault {} broken at entry {:?}: {:?}",
+                    vault_pubkey,
+                    report.broken_entry_id,
+                    report.reason
+                );
+            }
+            HttpResponse::Ok().json(ApiResponse::success(report))
+        }
+        Err(e) => {
+            tracing::error!("Failed to verify audit chain: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+/// Check the request's `X-Admin-Key` against the configured admin key
+///
+/// Both keys are hashed first, so the comparison runs over equal-length digests and
+/// timing leaks neither the key's contents nor its length.
+pub fn require_admin(http_req: &HttpRequest, config: &Config) -> validation::ValidationResult {
+    let Some(expected) = config.admin_api_key.as_deref() else {
+        return Err(error::error_response(
+            StatusCode::FORBIDDEN,
+            error_codes::UNAUTHORIZED,
+            "Admin API is disabled",
+        ));
+    };
+
+    let provided = http_req
+        .headers()
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    if !constant_time_eq(&provided, &expected) {
+        return Err(error::error_response(
+            StatusCode::UNAUTHORIZED,
+            error_codes::UNAUTHORIZED,
+            "Missing or invalid admin key",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Compare without short-circuiting so timing doesn't leak how much of the key matched
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
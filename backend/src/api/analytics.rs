@@ -0,0 +1,58 @@
+//! Protocol-wide KPIs for the dashboard
+//!
+//! The overview aggregates over every vault and the last 24h of transactions, so
+//! it is cached for 30s rather than recomputed per request.
+
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Duration, Utc};
+use shared::{AnalyticsOverview, ApiResponse, VaultResponse};
+
+use crate::api::error::ApiError;
+use crate::database::Database;
+use crate::services::AppState;
+
+/// Vaults listed in `top_vaults`
+pub const TOP_VAULTS_LIMIT: i64 = 5;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/analytics").route("/overview", web::get().to(get_overview)));
+}
+
+async fn get_overview(state: web::Data<AppState>) -> impl Responder {
+    if let Some(overview) = state.cache.get_analytics_overview().await {
+        return HttpResponse::Ok().json(ApiResponse::success(overview));
+    }
+
+    match build_overview(&state.database).await {
+        Ok(overview) => {
+            state.cache.set_analytics_overview(overview.clone()).await;
+            HttpResponse::Ok().json(ApiResponse::success(overview))
+        }
+        Err(e) => {
+            tracing::error!("Failed to build analytics overview: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+pub async fn build_overview(database: &Database) -> Result<AnalyticsOverview, sqlx::Error> {
+    let now = Utc::now();
+
+    let tvl = database.get_tvl_stats().await?;
+    let activity = database.get_vault_activity_stats().await?;
+    let volume = database
+        .get_protocol_volume(now - Duration::hours(24), now)
+        .await?;
+    let top_vaults = database.get_top_vaults_by_balance(TOP_VAULTS_LIMIT).await?;
+
+    Ok(AnalyticsOverview {
+        total_vaults: tvl.total_vaults,
+        active_vaults: activity.funded_vaults,
+        total_value_locked: tvl.total_value_locked,
+        deposit_volume_24h: volume.deposit_volume,
+        withdraw_volume_24h: volume.withdraw_volume,
+        avg_utilization_pct: activity.avg_utilization_pct,
+        top_vaults: top_vaults.into_iter().map(VaultResponse::from).collect(),
+        generated_at: now,
+    })
+}
@@ -0,0 +1,182 @@
+//! Signed request sessions
+//!
+//! `POST /auth` issues a session to a wallet that signs
+//! `goquant-vault-session:{owner_pubkey}:{timestamp}` with its key. The returned
+//! `secret` keys an HMAC-SHA256 of the raw request body, which the client sends
+//! hex-encoded in `X-Signature` alongside `X-Session-Id`. With
+//! `REQUIRE_SIGNED_REQUESTS` set, [`require_signed_request`] rejects balance-changing
+//! requests whose signature is missing or doesn't match their body, and requests
+//! for a vault the session's wallet does not own.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use serde::Deserialize;
+use shared::{error_codes, ApiResponse, CreateSessionRequest, VaultError};
+use uuid::Uuid;
+
+use crate::api::admin::constant_time_eq;
+use crate::api::error::{self, ApiError};
+use crate::api::validation;
+use crate::services::{webhook_dispatcher, AppState};
+use crate::websocket::verify_auth_signature;
+
+pub const SESSION_ID_HEADER: &str = "X-Session-Id";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Prefix of the message a wallet signs to open a session
+pub const SESSION_MESSAGE_PREFIX: &str = "goquant-vault-session";
+
+/// How far a session request's `timestamp` may be from the server clock, in seconds
+pub const SESSION_MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/auth", web::post().to(create_session));
+}
+
+/// Message `owner_pubkey` signs to open a session at `timestamp`
+pub fn session_message(owner_pubkey: &str, timestamp: i64) -> String {
+    format!("{}:{}:{}", SESSION_MESSAGE_PREFIX, owner_pubkey, timestamp)
+}
+
+/// Issue a session secret to a wallet that signed a fresh session message
+async fn create_session(
+    state: web::Data<AppState>,
+    req: web::Json<CreateSessionRequest>,
+) -> impl Responder {
+    tracing::info!("API: Create session for {}", req.owner_pubkey);
+
+    if let Err(resp) = validation::pubkey("owner_pubkey", &req.owner_pubkey) {
+        return resp;
+    }
+
+    if (Utc::now().timestamp() - req.timestamp).abs() > SESSION_MAX_CLOCK_SKEW_SECONDS {
+        return unauthorized("Session timestamp is too far from the server clock");
+    }
+
+    let message = session_message(&req.owner_pubkey, req.timestamp);
+    if !verify_auth_signature(&req.owner_pubkey, &req.signature, &message) {
+        return unauthorized("Invalid session signature");
+    }
+
+    // Two v4 UUIDs give 244 random bits, hex-encoded
+    let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+    match state
+        .database
+        .create_session(&req.owner_pubkey, &secret, state.config.session_ttl_seconds)
+        .await
+    {
+        Ok(session) => HttpResponse::Ok().json(ApiResponse::success(session)),
+        Err(e) => {
+            tracing::error!("Failed to create session: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+/// Middleware rejecting requests that fail [`verify_request_signature`]
+///
+/// Passes every request through while `REQUIRE_SIGNED_REQUESTS` is off. The body is
+/// buffered to be checked and then handed back to the handler unchanged.
+pub async fn require_signed_request(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        let resp = error::internal(error_codes::INTERNAL_ERROR, "Application state missing");
+        return Ok(req.into_response(resp).map_into_right_body());
+    };
+
+    if state.config.require_signed_requests {
+        let body = req.extract::<web::Bytes>().await?;
+        req.set_payload(Payload::from(body.clone()));
+
+        if let Err(resp) = verify_request_signature(&state, req.request(), &body).await {
+            return Ok(req.into_response(resp).map_into_right_body());
+        }
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+/// The part of every signed request body the owner check needs
+#[derive(Deserialize)]
+struct SignedVaultRequest {
+    vault_pubkey: String,
+}
+
+/// Check that `X-Session-Id` names a live session, `X-Signature` is the hex
+/// HMAC-SHA256 of `body` under its secret, and the session's wallet owns the
+/// `vault_pubkey` the body names
+pub async fn verify_request_signature(
+    state: &AppState,
+    http_req: &HttpRequest,
+    body: &[u8],
+) -> validation::ValidationResult {
+    let header = |name: &str| {
+        http_req
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+
+    let (Some(session_id), Some(signature)) = (header(SESSION_ID_HEADER), header(SIGNATURE_HEADER))
+    else {
+        return Err(unauthorized("Missing X-Session-Id or X-Signature header"));
+    };
+
+    let Ok(session_id) = Uuid::parse_str(session_id) else {
+        return Err(unauthorized("Invalid or expired session"));
+    };
+
+    let session = match state.database.get_active_session(session_id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => return Err(unauthorized("Invalid or expired session")),
+        Err(e) => {
+            tracing::error!("Failed to load session {}: {}", session_id, e);
+            return Err(e.to_response());
+        }
+    };
+
+    let expected = webhook_dispatcher::sign(&session.secret, body);
+    if !constant_time_eq(expected.as_bytes(), signature.to_ascii_lowercase().as_bytes()) {
+        tracing::warn!("Request signature mismatch for session {}", session_id);
+        return Err(unauthorized("Request signature does not match the body"));
+    }
+
+    let Ok(request) = serde_json::from_slice::<SignedVaultRequest>(body) else {
+        return Err(error::bad_request(
+            error_codes::INVALID_REQUEST,
+            "Signed request body must name a vault_pubkey",
+        ));
+    };
+
+    let vault = match state.database.get_vault(&request.vault_pubkey).await {
+        Ok(Some(vault)) => vault,
+        Ok(None) => return Err(VaultError::VaultNotFound(request.vault_pubkey).to_response()),
+        Err(e) => {
+            tracing::error!("Failed to load vault {}: {}", request.vault_pubkey, e);
+            return Err(e.to_response());
+        }
+    };
+
+    if vault.owner_pubkey != session.owner_pubkey {
+        tracing::warn!(
+            "Session {} of {} used for vault {} owned by {}",
+            session_id,
+            session.owner_pubkey,
+            vault.vault_pubkey,
+            vault.owner_pubkey
+        );
+        return Err(VaultError::Unauthorized.to_response());
+    }
+
+    Ok(())
+}
+
+fn unauthorized(message: &str) -> HttpResponse {
+    error::error_response(StatusCode::UNAUTHORIZED, error_codes::UNAUTHORIZED, message)
+}
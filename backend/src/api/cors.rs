@@ -0,0 +1,25 @@
+//! CORS policy built from [`Config`]
+//!
+//! In dev mode any origin, method and header is allowed. Otherwise only the
+//! configured origins may make cross-origin requests; others are rejected.
+
+use actix_cors::Cors;
+
+use crate::config::Config;
+
+pub fn cors(config: &Config) -> Cors {
+    if config.dev_mode {
+        return Cors::permissive().max_age(3600);
+    }
+
+    let mut cors = Cors::default()
+        .allowed_methods(config.cors_allowed_methods.iter().map(String::as_str))
+        .allowed_headers(config.cors_allowed_headers.iter().map(String::as_str))
+        .max_age(3600);
+
+    for origin in &config.cors_allowed_origins {
+        cors = cors.allowed_origin(origin);
+    }
+
+    cors
+}
@@ -0,0 +1,291 @@
+//! Error responses with machine-readable codes
+//!
+//! Every failed request returns `ApiResponse` with a human-readable `error` and
+//! a stable `error_code` from `shared::error_codes`. Service errors implement
+//! [`ApiError`] so handlers get the code and HTTP status from the error itself.
+//! [`BackendError`] wraps all of them and also implements `ResponseError`, so a
+//! handler returning `Result<HttpResponse, BackendError>` can use `?` throughout.
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use solana_client::client_error::ClientError;
+use shared::{error_codes, ApiResponse};
+
+use crate::services::tvl_recorder::TvlRecorderError;
+use crate::services::{
+    BackendError, BalanceError, BuilderError, CPIError, EventListenerError, MonitorError,
+    ReconcilerError, VaultError,
+};
+
+pub trait ApiError: std::fmt::Display {
+    fn code(&self) -> &'static str;
+    fn status(&self) -> StatusCode;
+
+    fn to_response(&self) -> HttpResponse {
+        error_response(self.status(), self.code(), self.to_string())
+    }
+}
+
+pub fn error_response(status: StatusCode, code: &str, message: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(ApiResponse::<()>::error_with_code(code, message.into()))
+}
+
+pub fn not_found(code: &str, message: impl Into<String>) -> HttpResponse {
+    error_response(StatusCode::NOT_FOUND, code, message)
+}
+
+pub fn bad_request(code: &str, message: impl Into<String>) -> HttpResponse {
+    error_response(StatusCode::BAD_REQUEST, code, message)
+}
+
+pub fn internal(code: &str, message: impl Into<String>) -> HttpResponse {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, code, message)
+}
+
+impl ApiError for VaultError {
+    fn code(&self) -> &'static str {
+        match self {
+            VaultError::InvalidPubkey => error_codes::INVALID_PUBKEY,
+            VaultError::VaultNotFound => error_codes::VAULT_NOT_FOUND,
+            VaultError::InsufficientBalance => error_codes::INSUFFICIENT_BALANCE,
+            VaultError::InsufficientLockedBalance => error_codes::INSUFFICIENT_LOCKED_BALANCE,
+            VaultError::NotImplemented(_) => error_codes::NOT_IMPLEMENTED,
+            VaultError::DeserializationError(_) => error_codes::SERIALIZATION_ERROR,
+            VaultError::DepositNotVerified(_) => error_codes::DEPOSIT_NOT_VERIFIED,
+            VaultError::DailyLimitExceeded { .. } => error_codes::DAILY_LIMIT_EXCEEDED,
+            VaultError::AmountExceedsMax { .. } => error_codes::AMOUNT_EXCEEDS_MAX,
+            VaultError::ProgramNotAuthorized(_) => error_codes::PROGRAM_NOT_AUTHORIZED,
+            VaultError::ProgramPaused => error_codes::PROGRAM_PAUSED,
+            VaultError::VaultFrozen => error_codes::VAULT_FROZEN,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            VaultError::InvalidPubkey
+            | VaultError::InsufficientBalance
+            | VaultError::InsufficientLockedBalance
+            | VaultError::DepositNotVerified(_)
+            | VaultError::DailyLimitExceeded { .. }
+            | VaultError::AmountExceedsMax { .. } => StatusCode::BAD_REQUEST,
+            VaultError::ProgramNotAuthorized(_) | VaultError::VaultFrozen => StatusCode::FORBIDDEN,
+            VaultError::VaultNotFound => StatusCode::NOT_FOUND,
+            VaultError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            VaultError::ProgramPaused => StatusCode::SERVICE_UNAVAILABLE,
+            VaultError::DeserializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl ApiError for BalanceError {
+    fn code(&self) -> &'static str {
+        match self {
+            BalanceError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+            BalanceError::SolanaRpcError(_) => error_codes::SOLANA_RPC_ERROR,
+            BalanceError::InvalidPubkey => error_codes::INVALID_PUBKEY,
+            BalanceError::VaultNotFound => error_codes::VAULT_NOT_FOUND,
+            BalanceError::DeserializationError(_) => error_codes::SERIALIZATION_ERROR,
+            BalanceError::UnsupportedTokenProgram(_) => error_codes::UNSUPPORTED_TOKEN_PROGRAM,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            BalanceError::InvalidPubkey | BalanceError::UnsupportedTokenProgram(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            BalanceError::VaultNotFound => StatusCode::NOT_FOUND,
+            BalanceError::SolanaRpcError(_) => StatusCode::BAD_GATEWAY,
+            BalanceError::DatabaseError(_) | BalanceError::DeserializationError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl ApiError for EventListenerError {
+    fn code(&self) -> &'static str {
+        match self {
+            EventListenerError::RpcError(_) => error_codes::SOLANA_RPC_ERROR,
+            EventListenerError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+            EventListenerError::ParseError(_) => error_codes::SERIALIZATION_ERROR,
+            EventListenerError::VaultNotFound(_) => error_codes::VAULT_NOT_FOUND,
+            EventListenerError::ProcessingError(_) => error_codes::INTERNAL_ERROR,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            EventListenerError::VaultNotFound(_) => StatusCode::NOT_FOUND,
+            EventListenerError::RpcError(_) => StatusCode::BAD_GATEWAY,
+            EventListenerError::DatabaseError(_)
+            | EventListenerError::ParseError(_)
+            | EventListenerError::ProcessingError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl ApiError for TvlRecorderError {
+    fn code(&self) -> &'static str {
+        match self {
+            TvlRecorderError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl ApiError for shared::VaultError {
+    fn code(&self) -> &'static str {
+        shared::VaultError::code(self)
+    }
+
+    fn status(&self) -> StatusCode {
+        use shared::VaultError as E;
+        match self {
+            E::VaultNotFound(_) | E::TransactionNotFound(_) => StatusCode::NOT_FOUND,
+            E::Unauthorized => StatusCode::FORBIDDEN,
+            E::SolanaRpcError(_) => StatusCode::BAD_GATEWAY,
+            E::DatabaseError(_)
+            | E::SerializationError(_)
+            | E::DeserializationError(_)
+            | E::ConfigError(_)
+            | E::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl ApiError for sqlx::Error {
+    fn code(&self) -> &'static str {
+        error_codes::DATABASE_ERROR
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl ApiError for ClientError {
+    fn code(&self) -> &'static str {
+        error_codes::SOLANA_RPC_ERROR
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::BAD_GATEWAY
+    }
+}
+
+impl ApiError for CPIError {
+    fn code(&self) -> &'static str {
+        match self {
+            CPIError::InvalidAmount(_) => error_codes::INVALID_AMOUNT,
+            CPIError::InvalidPubkey(_) => error_codes::INVALID_PUBKEY,
+            CPIError::VaultNotFound(_) => error_codes::VAULT_NOT_FOUND,
+            CPIError::InsufficientBalance { .. } => error_codes::INSUFFICIENT_BALANCE,
+            CPIError::RpcError(_) | CPIError::TransactionFailed(_) => {
+                error_codes::SOLANA_RPC_ERROR
+            }
+            CPIError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+            CPIError::Unauthorized => error_codes::PROGRAM_NOT_AUTHORIZED,
+            CPIError::InstructionBuildError(_) => error_codes::TRANSACTION_BUILD_ERROR,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            CPIError::InvalidAmount(_)
+            | CPIError::InvalidPubkey(_)
+            | CPIError::InsufficientBalance { .. } => StatusCode::BAD_REQUEST,
+            CPIError::VaultNotFound(_) => StatusCode::NOT_FOUND,
+            CPIError::Unauthorized => StatusCode::FORBIDDEN,
+            CPIError::RpcError(_) | CPIError::TransactionFailed(_) => StatusCode::BAD_GATEWAY,
+            CPIError::DatabaseError(_) | CPIError::InstructionBuildError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl ApiError for BuilderError {
+    fn code(&self) -> &'static str {
+        match self {
+            BuilderError::InvalidPubkey => error_codes::INVALID_PUBKEY,
+            BuilderError::BuildFailed(_) => error_codes::TRANSACTION_BUILD_ERROR,
+            BuilderError::BatchTooLarge(_) => error_codes::INVALID_REQUEST,
+            BuilderError::RpcFailed(_) => error_codes::SOLANA_RPC_ERROR,
+            BuilderError::UnsupportedTokenProgram(_) => error_codes::UNSUPPORTED_TOKEN_PROGRAM,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            BuilderError::InvalidPubkey
+            | BuilderError::BatchTooLarge(_)
+            | BuilderError::UnsupportedTokenProgram(_) => StatusCode::BAD_REQUEST,
+            BuilderError::RpcFailed(_) => StatusCode::BAD_GATEWAY,
+            BuilderError::BuildFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl ApiError for ReconcilerError {
+    fn code(&self) -> &'static str {
+        match self {
+            ReconcilerError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl ApiError for MonitorError {
+    fn code(&self) -> &'static str {
+        match self {
+            MonitorError::DatabaseError(_) => error_codes::DATABASE_ERROR,
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl BackendError {
+    fn inner(&self) -> &dyn ApiError {
+        match self {
+            BackendError::Vault(e) => e,
+            BackendError::Balance(e) => e,
+            BackendError::Cpi(e) => e,
+            BackendError::EventListener(e) => e,
+            BackendError::Reconciler(e) => e,
+            BackendError::Monitor(e) => e,
+            BackendError::Builder(e) => e,
+            BackendError::Database(e) => e,
+            BackendError::SolanaRpc(e) => e,
+        }
+    }
+}
+
+impl ApiError for BackendError {
+    fn code(&self) -> &'static str {
+        self.inner().code()
+    }
+
+    fn status(&self) -> StatusCode {
+        self.inner().status()
+    }
+}
+
+impl ResponseError for BackendError {
+    fn status_code(&self) -> StatusCode {
+        ApiError::status(self)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.to_response()
+    }
+}
@@ -1,22 +1,172 @@
-use actix_web::{HttpResponse, Responder};
+//! Health endpoints
+//!
+//! - `GET /health` reports the database, Solana RPC and cache
+//! - `GET /health/live` answers 200 without touching any dependency, for liveness probes
+//! - `GET /health/ready` also requires a recent heartbeat from every background service,
+//!   for readiness probes
+
+use actix_web::{http::StatusCode, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
+use crate::cache::CacheStats;
+use crate::config::Config;
+use crate::services::{AppState, BackgroundService, Heartbeats};
+
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
     pub uptime_seconds: u64,
+    /// `"up"` or `"down"`
+    pub database: String,
+    /// `"up"` or `"down"`
+    pub solana_rpc: String,
+    pub cache: CacheStats,
 }
 
 static START_TIME: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
 
-pub async fn health_check() -> impl Responder {
-    let start_time = START_TIME.get_or_init(|| std::time::Instant::now());
-    let uptime = start_time.elapsed().as_secs();
+impl HealthResponse {
+    /// Build the response from dependency checks; any core dependency down is a 503
+    pub fn from_checks(
+        database_up: bool,
+        solana_rpc_up: bool,
+        cache: CacheStats,
+    ) -> (StatusCode, Self) {
+        let start_time = START_TIME.get_or_init(std::time::Instant::now);
+        let healthy = database_up && solana_rpc_up;
+        let up_down = |up: bool| if up { "up" } else { "down" }.to_string();
+
+        let status = if healthy {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        (
+            status,
+            Self {
+                status: if healthy { "healthy" } else { "unhealthy" }.to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                uptime_seconds: start_time.elapsed().as_secs(),
+                database: up_down(database_up),
+                solana_rpc: up_down(solana_rpc_up),
+                cache,
+            },
+        )
+    }
+}
+
+pub async fn health_check(state: web::Data<AppState>) -> impl Responder {
+    let (database, solana_rpc) =
+        tokio::join!(state.database.ping(), state.solana_client.get_slot());
+
+    if let Err(e) = &database {
+        tracing::warn!("Health check: database down: {}", e);
+    }
+    if let Err(e) = &solana_rpc {
+        tracing::warn!("Health check: Solana RPC down: {}", e);
+    }
+
+    let (status, body) = HealthResponse::from_checks(
+        database.is_ok(),
+        solana_rpc.is_ok(),
+        state.cache.get_stats().await,
+    );
+
+    HttpResponse::build(status).json(body)
+}
+
+/// Liveness probe: the process is up and serving requests
+pub async fn liveness() -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "alive" }))
+}
+
+/// Heartbeat state of one background service
+#[derive(Serialize)]
+pub struct ServiceHeartbeat {
+    pub name: String,
+    /// `"up"` or `"stale"`
+    pub status: String,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub stale_after_seconds: u64,
+}
+
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    pub status: String,
+    /// `"up"` or `"down"`
+    pub database: String,
+    /// `"up"` or `"down"`
+    pub solana_rpc: String,
+    pub services: Vec<ServiceHeartbeat>,
+}
+
+impl ReadinessResponse {
+    /// Build the response from dependency checks and heartbeats; anything down or
+    /// stale is a 503
+    pub fn from_checks(
+        database_up: bool,
+        solana_rpc_up: bool,
+        heartbeats: &Heartbeats,
+        config: &Config,
+    ) -> (StatusCode, Self) {
+        let services: Vec<ServiceHeartbeat> = BackgroundService::ALL
+            .into_iter()
+            .map(|service| {
+                let fresh = heartbeats.is_fresh(service, config);
+                ServiceHeartbeat {
+                    name: service.as_str().to_string(),
+                    status: if fresh { "up" } else { "stale" }.to_string(),
+                    last_heartbeat: heartbeats.last_beat(service),
+                    stale_after_seconds: service.stale_after(config).as_secs(),
+                }
+            })
+            .collect();
+
+        let ready = database_up && solana_rpc_up && services.iter().all(|s| s.status == "up");
+        let up_down = |up: bool| if up { "up" } else { "down" }.to_string();
+
+        let status = if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        (
+            status,
+            Self {
+                status: if ready { "ready" } else { "unhealthy" }.to_string(),
+                database: up_down(database_up),
+                solana_rpc: up_down(solana_rpc_up),
+                services,
+            },
+        )
+    }
+}
+
+/// Readiness probe: database and RPC reachable and no background service stuck
+pub async fn readiness_check(state: web::Data<AppState>) -> impl Responder {
+    let (database, solana_rpc) =
+        tokio::join!(state.database.ping(), state.solana_client.get_slot());
+
+    if let Err(e) = &database {
+        tracing::warn!("Readiness check: database down: {}", e);
+    }
+    if let Err(e) = &solana_rpc {
+        tracing::warn!("Readiness check: Solana RPC down: {}", e);
+    }
+
+    let (status, body) = ReadinessResponse::from_checks(
+        database.is_ok(),
+        solana_rpc.is_ok(),
+        &state.heartbeats,
+        &state.config,
+    );
+    for service in body.services.iter().filter(|s| s.status != "up") {
+        tracing::warn!("Readiness check: {} heartbeat is stale", service.name);
+    }
 
-    HttpResponse::Ok().json(HealthResponse {
-        status: "healthy".to_string(),
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        uptime_seconds: uptime,
-    })
+    HttpResponse::build(status).json(body)
 }
@@ -0,0 +1,138 @@
+//! `Idempotency-Key` support for vault mutation endpoints
+//!
+//! The first request with a key runs normally and its response is stored along
+//! with a hash of the request. Repeats within the TTL get the stored response
+//! back without re-executing; reusing a key for a different request is a 422.
+
+use std::future::Future;
+
+use actix_web::{body::to_bytes, http::StatusCode, HttpRequest, HttpResponse};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use shared::error_codes;
+
+use crate::api::error;
+use crate::services::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Set on responses replayed from a stored key
+pub const IDEMPOTENT_REPLAY_HEADER: &str = "Idempotent-Replayed";
+const MAX_KEY_LEN: usize = 255;
+
+/// Hash of the endpoint and JSON body that a key is bound to
+pub fn request_hash<T: Serialize>(endpoint: &str, body: &T) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(endpoint.as_bytes());
+    hasher.update(serde_json::to_vec(body).unwrap_or_default());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Run `handler` at most once per `Idempotency-Key`; requests without the header run as usual
+pub async fn with_idempotency<T, F, Fut>(
+    state: &AppState,
+    http_req: &HttpRequest,
+    endpoint: &str,
+    body: &T,
+    handler: F,
+) -> HttpResponse
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = HttpResponse>,
+{
+    let key = match http_req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        None => return handler().await,
+        Some(value) => match value.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LEN => key.to_string(),
+            _ => {
+                return error::bad_request(
+                    error_codes::INVALID_REQUEST,
+                    format!(
+                        "{} must be 1-{} visible ASCII characters",
+                        IDEMPOTENCY_KEY_HEADER, MAX_KEY_LEN
+                    ),
+                );
+            }
+        },
+    };
+
+    let hash = request_hash(endpoint, body);
+    let ttl = state.config.idempotency_ttl_seconds;
+
+    match state.database.claim_idempotency_key(&key, &hash, ttl).await {
+        Ok(None) => {}
+        Ok(Some(existing)) if existing.request_hash != hash => {
+            return error::error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                error_codes::IDEMPOTENCY_KEY_REUSED,
+                format!(
+                    "{} was already used for a different request",
+                    IDEMPOTENCY_KEY_HEADER
+                ),
+            );
+        }
+        Ok(Some(existing)) => {
+            return match (existing.status_code, existing.response) {
+                (Some(code), Some(response)) => {
+                    let status = u16::try_from(code)
+                        .ok()
+                        .and_then(|c| StatusCode::from_u16(c).ok())
+                        .unwrap_or(StatusCode::OK);
+                    HttpResponse::build(status)
+                        .insert_header((IDEMPOTENT_REPLAY_HEADER, "true"))
+                        .json(response)
+                }
+                _ => error::error_response(
+                    StatusCode::CONFLICT,
+                    error_codes::IDEMPOTENCY_KEY_IN_PROGRESS,
+                    "A request with this idempotency key is still in progress",
+                ),
+            };
+        }
+        Err(e) => {
+            tracing::error!("Failed to claim idempotency key {}: {}", key, e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
+        }
+    }
+
+    let response = handler().await;
+    let status = response.status();
+
+    // Server errors are not stored so the client can retry with the same key
+    if status.is_server_error() {
+        if let Err(e) = state.database.release_idempotency_key(&key).await {
+            tracing::error!("Failed to release idempotency key {}: {}", key, e);
+        }
+        return response;
+    }
+
+    let (response, body) = response.into_parts();
+    let bytes = match to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let _ = state.database.release_idempotency_key(&key).await;
+            return error::internal(error_codes::INTERNAL_ERROR, "Failed to read response");
+        }
+    };
+
+    match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(json) => {
+            if let Err(e) = state
+                .database
+                .complete_idempotency_key(&key, status.as_u16() as i32, &json)
+                .await
+            {
+                tracing::error!("Failed to store idempotent response for {}: {}", key, e);
+            }
+        }
+        Err(_) => {
+            let _ = state.database.release_idempotency_key(&key).await;
+        }
+    }
+
+    response.set_body(bytes).map_into_boxed_body()
+}
@@ -1,6 +1,17 @@
+pub mod admin;
+pub mod analytics;
+pub mod auth;
+pub mod cors;
+pub mod error;
 pub mod health;
+pub mod idempotency;
+pub mod ndjson;
+pub mod reconciliation;
+pub mod request_id;
 pub mod transaction;
+pub mod validation;
 pub mod vault;
+pub mod webhook;
 
 pub use health::*;
 pub use transaction::*;
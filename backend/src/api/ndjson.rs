@@ -0,0 +1,55 @@
+//! Newline-delimited JSON for large listings
+//!
+//! A client sending `Accept: application/x-ndjson` to `/vault/list` or the
+//! transaction history gets one record per line, written as rows come back
+//! from Postgres instead of after the whole page is collected. Everyone else
+//! keeps the usual `ApiResponse` JSON body.
+
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use serde::Serialize;
+
+use crate::database::RowStream;
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Whether any media type in the `Accept` header is NDJSON
+pub fn accepts_ndjson(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| {
+            accept.split(',').any(|media| {
+                media
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .eq_ignore_ascii_case(NDJSON_CONTENT_TYPE)
+            })
+        })
+}
+
+/// Stream each row, converted by `into`, as one JSON line
+///
+/// Headers are sent before the first row, so a query that fails part way
+/// aborts the body rather than turning into an error response.
+pub fn stream<T, R, F>(rows: RowStream<T>, into: F) -> HttpResponse
+where
+    T: 'static,
+    R: Serialize,
+    F: Fn(T) -> R + 'static,
+{
+    let body = rows.map(move |row| -> Result<Bytes, Box<dyn std::error::Error>> {
+        let row = row.inspect_err(|e| tracing::error!("NDJSON stream aborted: {}", e))?;
+        let mut line = serde_json::to_vec(&into(row))?;
+        line.push(b'\n');
+        Ok(Bytes::from(line))
+    });
+
+    HttpResponse::Ok()
+        .content_type(NDJSON_CONTENT_TYPE)
+        .streaming(body)
+}
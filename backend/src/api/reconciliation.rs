@@ -0,0 +1,29 @@
+//! Balance reconciliation history
+//!
+//! Each reconciler cycle stores its vault, mismatch and error counts, so ops can
+//! watch the discrepancy rate over time instead of grepping logs.
+
+use actix_web::{web, HttpResponse, Responder};
+use shared::{ApiResponse, ReconciliationRunParams};
+
+use crate::api::error::ApiError;
+use crate::services::AppState;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/reconciliation").route("/runs", web::get().to(get_runs)));
+}
+
+async fn get_runs(
+    state: web::Data<AppState>,
+    query: web::Query<ReconciliationRunParams>,
+) -> impl Responder {
+    let limit = query.limit.clamp(1, 100);
+
+    match state.database.get_reconciliation_runs(limit).await {
+        Ok(runs) => HttpResponse::Ok().json(ApiResponse::success(runs)),
+        Err(e) => {
+            tracing::error!("Failed to get reconciliation runs: {}", e);
+            e.to_response()
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Request correlation IDs
+//!
+//! [`assign_request_id`] gives every request an `X-Request-Id`, reusing the
+//! caller's when it is well formed, and echoes it on the response. The root span
+//! built by [`RequestIdRootSpan`] records it as `request_id`, so everything logged
+//! while handling the request (`VaultManager` operations, WebSocket broadcasts)
+//! carries the same ID.
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::HttpMessage;
+use tracing::Span;
+use tracing_actix_web::{DefaultRootSpanBuilder, RootSpanBuilder};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Longest incoming ID that is reused rather than replaced
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation ID of the current request, stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// The caller's ID if it is short printable ASCII, otherwise a new UUID
+    fn from_header(value: Option<&HeaderValue>) -> Self {
+        let incoming = value
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|id| {
+                !id.is_empty()
+                    && id.len() <= MAX_REQUEST_ID_LEN
+                    && id.bytes().all(|b| b.is_ascii_graphic())
+            });
+
+        match incoming {
+            Some(id) => RequestId(id.to_string()),
+            None => RequestId(Uuid::new_v4().to_string()),
+        }
+    }
+}
+
+/// Middleware assigning the request's [`RequestId`] and returning it in `X-Request-Id`
+///
+/// Must wrap [`tracing_actix_web::TracingLogger`] so the ID is in place before
+/// the root span is built.
+pub async fn assign_request_id(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let request_id = RequestId::from_header(req.headers().get(REQUEST_ID_HEADER));
+    let header_value =
+        HeaderValue::from_str(&request_id.0).expect("request IDs are printable ASCII");
+    req.extensions_mut().insert(request_id);
+
+    let mut res = next.call(req).await?;
+    res.headers_mut()
+        .insert(HeaderName::from_static("x-request-id"), header_value);
+    Ok(res)
+}
+
+/// Root span for [`tracing_actix_web::TracingLogger`] keyed by our [`RequestId`]
+///
+/// The default builder records its own freshly generated ID, which would not
+/// match the one returned to the caller.
+pub struct RequestIdRootSpan;
+
+impl RootSpanBuilder for RequestIdRootSpan {
+    fn on_request_start(request: &ServiceRequest) -> Span {
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.0.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let route = request.match_pattern().unwrap_or_default();
+
+        tracing::info_span!(
+            "HTTP request",
+            http.method = %request.method(),
+            http.route = %route,
+            http.target = %request.uri(),
+            request_id = %request_id,
+            http.status_code = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+            exception.message = tracing::field::Empty,
+            exception.details = tracing::field::Empty,
+        )
+    }
+
+    fn on_request_end<B: MessageBody>(
+        span: Span,
+        outcome: &Result<ServiceResponse<B>, actix_web::Error>,
+    ) {
+        DefaultRootSpanBuilder::on_request_end(span, outcome);
+    }
+}
@@ -1,12 +1,22 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use ::base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ::base64::Engine;
 use serde::{Deserialize, Serialize};
+use shared::{base_units_to_usdt, error_codes, format_usdt, ApiResponse, Vault};
 use solana_sdk::{
     pubkey::Pubkey,
     transaction::Transaction,
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 use std::str::FromStr;
 
-use crate::services::{AppState, TransactionBuilder};
+use crate::api::error::{self, ApiError};
+use crate::api::{ndjson, validation};
+use crate::database::TransactionCursor;
+use crate::services::{
+    rpc_retry, AppState, BalanceTracker, BuilderError, TokenProgram, TransactionBuilder,
+    VaultManager,
+};
 
 // ============================================================================
 // Request/Response Types
@@ -29,12 +39,66 @@ pub struct BuildWithdrawTxRequest {
     pub amount: u64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BuildWithdrawAllTxRequest {
+    pub user_pubkey: String,
+    pub vault_pubkey: String,
+    pub vault_token_account: String,
+    pub user_token_account: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BuildBatchLockTxRequest {
+    pub payer_pubkey: String,
+    /// Must be in each vault's on-chain `VaultAuthority` list; co-signs the transaction
+    pub authority_program: String,
+    pub vault_pubkeys: Vec<String>,
+    pub amounts: Vec<u64>,
+    /// Each vault's nonce to sign against; read from the vault accounts when omitted
+    #[serde(default)]
+    pub nonces: Option<Vec<u64>>,
+}
+
+/// Body for `/build/lock` and `/build/unlock`
+#[derive(Debug, Deserialize)]
+pub struct BuildCollateralTxRequest {
+    pub payer_pubkey: String,
+    /// Must be in the vault's on-chain `VaultAuthority` list; co-signs the transaction
+    pub authority_program: String,
+    pub vault_pubkey: String,
+    pub amount: u64,
+    /// Vault nonce to sign against; read from the vault account when omitted
+    #[serde(default)]
+    pub nonce: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BuildAuthorizeProgramTxRequest {
+    /// The vault's owner, who signs and pays for the transaction
+    pub admin_pubkey: String,
+    pub vault_pubkey: String,
+    /// Program to add to the vault's `VaultAuthority` list
+    pub program_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BuildInitializeTxRequest {
     pub user_pubkey: String,
     pub mint_pubkey: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BuildOnboardTxRequest {
+    pub user_pubkey: String,
+    pub mint_pubkey: String,
+    /// Smallest accepted deposit (0 for no floor)
+    #[serde(default)]
+    pub min_deposit: u64,
+    /// Cap on the vault's total balance (0 for unlimited)
+    #[serde(default)]
+    pub max_balance: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UnsignedTransactionResponse {
     /// Base64-encoded serialized transaction (unsigned)
@@ -43,18 +107,47 @@ pub struct UnsignedTransactionResponse {
     pub blockhash: String,
     /// Estimated fee in lamports
     pub estimated_fee: u64,
+    /// Compute unit price in micro-lamports from recent fees on the written accounts;
+    /// `None` when the RPC couldn't be asked
+    pub suggested_priority_fee: Option<u64>,
     /// Accounts that need to sign
     pub signers: Vec<String>,
     /// Message for the user
     pub message: String,
 }
 
+/// One step of a multi-transaction bundle; sign and send in order
+#[derive(Debug, Serialize)]
+pub struct BundledTransaction {
+    /// Base64-encoded serialized transaction (unsigned)
+    pub transaction: String,
+    /// Estimated fee in lamports
+    pub estimated_fee: u64,
+    /// Compute unit price in micro-lamports, as in `UnsignedTransactionResponse`
+    pub suggested_priority_fee: Option<u64>,
+    /// Accounts that need to sign
+    pub signers: Vec<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnboardBundleResponse {
+    pub vault_pubkey: String,
+    pub vault_token_account: String,
+    pub user_token_account: String,
+    /// Shared by every transaction in the bundle
+    pub blockhash: String,
+    pub transactions: Vec<BundledTransaction>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TransactionHistoryResponse {
     pub transactions: Vec<TransactionRecord>,
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
+    /// Pass as `after` to fetch the next page; `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -63,42 +156,40 @@ pub struct TransactionRecord {
     pub vault_pubkey: String,
     pub tx_signature: String,
     pub tx_type: String,
+    /// Base units (6 decimals)
     pub amount: i64,
+    pub amount_usdt: f64,
+    /// `amount` formatted for display, e.g. `"1000.000000 USDT"`
+    pub amount_formatted: String,
     pub status: String,
     pub created_at: String,
 }
 
+impl From<shared::TransactionRecord> for TransactionRecord {
+    fn from(t: shared::TransactionRecord) -> Self {
+        Self {
+            id: t.id,
+            vault_pubkey: t.vault_pubkey,
+            tx_signature: t.tx_signature,
+            tx_type: t.tx_type,
+            amount: t.amount,
+            amount_usdt: base_units_to_usdt(t.amount),
+            amount_formatted: format_usdt(t.amount),
+            status: t.status,
+            created_at: t.created_at.to_rfc3339(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TransactionHistoryQuery {
     pub vault_pubkey: Option<String>,
     pub tx_type: Option<String>,
+    pub status: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
-}
-
-#[derive(Debug, Serialize)]
-struct ApiResponse<T> {
-    success: bool,
-    data: Option<T>,
-    error: Option<String>,
-}
-
-impl<T: Serialize> ApiResponse<T> {
-    fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            error: None,
-        }
-    }
-
-    fn error(message: String) -> ApiResponse<()> {
-        ApiResponse {
-            success: false,
-            data: None,
-            error: Some(message),
-        }
-    }
+    /// `next_cursor` from the previous page; can't be combined with `offset`
+    pub after: Option<String>,
 }
 
 // ============================================================================
@@ -111,7 +202,16 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             // Build unsigned transactions (Frontend will sign these)
             .route("/build/deposit", web::post().to(build_deposit_transaction))
             .route("/build/withdraw", web::post().to(build_withdraw_transaction))
+            .route("/build/withdraw-all", web::post().to(build_withdraw_all_transaction))
+            .route("/build/batch-lock", web::post().to(build_batch_lock_transaction))
+            .route("/build/lock", web::post().to(build_lock_transaction))
+            .route("/build/unlock", web::post().to(build_unlock_transaction))
+            .route(
+                "/build/authorize-program",
+                web::post().to(build_authorize_program_transaction),
+            )
             .route("/build/initialize", web::post().to(build_initialize_transaction))
+            .route("/build/onboard", web::post().to(build_onboard_transaction))
             // Transaction history
             .route("/history", web::get().to(get_transaction_history))
             .route("/history/{vault_pubkey}", web::get().to(get_vault_transactions))
@@ -147,43 +247,77 @@ async fn build_deposit_transaction(
         req.amount
     );
 
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    let max_amount = state.config.max_operation_amount;
+    if let Err(resp) = validation::amount_u64("amount", req.amount)
+        .and_then(|_| validation::max_amount("amount", req.amount, max_amount))
+    {
+        return resp;
+    }
+
     // Validate pubkeys
     let user_pubkey = match Pubkey::from_str(&req.user_pubkey) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid user pubkey".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user pubkey");
         }
     };
 
     let user_token_account = match Pubkey::from_str(&req.user_token_account) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid user token account".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user token account");
         }
     };
 
     let vault_token_account = match Pubkey::from_str(&req.vault_token_account) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid vault token account".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault token account");
+        }
+    };
+
+    // Pre-check the vault's deposit limits so the user isn't asked to sign a failing tx
+    let token_program = match VaultManager::get_vault_by_owner(&state, &req.user_pubkey).await {
+        Ok(Some(vault)) => {
+            if let Err(e) = vault.check_deposit_limits(req.amount as i64) {
+                return e.to_response();
+            }
+            match vault_token_program(&vault) {
+                Ok(token_program) => token_program,
+                Err(resp) => return resp,
+            }
+        }
+        Ok(None) => TokenProgram::default(),
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
         }
     };
 
     // Get recent blockhash
-    let recent_blockhash = match state.solana_client.get_latest_blockhash().await {
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!("Failed to get recent blockhash: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get recent blockhash".to_string()));
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
         }
     };
 
     // Build the unsigned transaction
     let transaction = match TransactionBuilder::build_deposit_tx(
+        token_program,
         &user_pubkey,
         &user_token_account,
         &vault_token_account,
@@ -193,8 +327,10 @@ async fn build_deposit_transaction(
         Ok(tx) => tx,
         Err(e) => {
             tracing::error!("Failed to build deposit transaction: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error(format!("Failed to build transaction: {}", e)));
+            return error::internal(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
         }
     };
 
@@ -203,18 +339,22 @@ async fn build_deposit_transaction(
         Ok(json_str) => base64::encode(json_str.as_bytes()),
         Err(e) => {
             tracing::error!("Failed to serialize transaction: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to serialize transaction".to_string()));
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
         }
     };
 
     // Estimate fee (5000 lamports per signature is a reasonable estimate)
     let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
 
     let response = UnsignedTransactionResponse {
         transaction: serialized,
         blockhash: recent_blockhash.to_string(),
         estimated_fee,
+        suggested_priority_fee,
         signers: vec![req.user_pubkey.clone()],
         message: format!("Deposit {} tokens to vault", req.amount),
     };
@@ -234,70 +374,93 @@ async fn build_withdraw_transaction(
         req.amount
     );
 
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    let max_amount = state.config.max_operation_amount;
+    if let Err(resp) = validation::amount_u64("amount", req.amount)
+        .and_then(|_| validation::max_amount("amount", req.amount, max_amount))
+    {
+        return resp;
+    }
+
     // Validate pubkeys
     let user_pubkey = match Pubkey::from_str(&req.user_pubkey) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid user pubkey".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user pubkey");
         }
     };
 
     let vault_pubkey = match Pubkey::from_str(&req.vault_pubkey) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid vault pubkey".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault pubkey");
         }
     };
 
     let vault_token_account = match Pubkey::from_str(&req.vault_token_account) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid vault token account".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault token account");
         }
     };
 
     let user_token_account = match Pubkey::from_str(&req.user_token_account) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid user token account".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user token account");
         }
     };
 
+    if let Err(e) = VaultManager::ensure_not_frozen(&state, &req.vault_pubkey).await {
+        return e.to_response();
+    }
+
     // Verify vault exists and has sufficient balance
-    match state.database.get_vault(&req.vault_pubkey).await {
+    let token_program = match state.database.get_vault(&req.vault_pubkey).await {
         Ok(Some(vault)) => {
             if vault.available_balance < req.amount as i64 {
-                return HttpResponse::BadRequest()
-                    .json(ApiResponse::<()>::error("Insufficient available balance".to_string()));
+                return error::bad_request(
+                    error_codes::INSUFFICIENT_BALANCE,
+                    "Insufficient available balance",
+                );
+            }
+            match vault_token_program(&vault) {
+                Ok(token_program) => token_program,
+                Err(resp) => return resp,
             }
         }
         Ok(None) => {
-            return HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Vault not found".to_string()));
+            return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found");
         }
         Err(e) => {
             tracing::error!("Database error: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Database error".to_string()));
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
         }
-    }
+    };
 
     // Get recent blockhash
-    let recent_blockhash = match state.solana_client.get_latest_blockhash().await {
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!("Failed to get recent blockhash: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get recent blockhash".to_string()));
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
         }
     };
 
     // Build the unsigned transaction
     let transaction = match TransactionBuilder::build_withdraw_tx(
+        token_program,
         &user_pubkey,
         &vault_pubkey,
         &vault_token_account,
@@ -308,8 +471,10 @@ async fn build_withdraw_transaction(
         Ok(tx) => tx,
         Err(e) => {
             tracing::error!("Failed to build withdraw transaction: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error(format!("Failed to build transaction: {}", e)));
+            return error::internal(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
         }
     };
 
@@ -318,17 +483,21 @@ async fn build_withdraw_transaction(
         Ok(json_str) => base64::encode(json_str.as_bytes()),
         Err(e) => {
             tracing::error!("Failed to serialize transaction: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to serialize transaction".to_string()));
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
         }
     };
 
     let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
 
     let response = UnsignedTransactionResponse {
         transaction: serialized,
         blockhash: recent_blockhash.to_string(),
         estimated_fee,
+        suggested_priority_fee,
         signers: vec![req.user_pubkey.clone()],
         message: format!("Withdraw {} tokens from vault", req.amount),
     };
@@ -337,6 +506,587 @@ async fn build_withdraw_transaction(
     HttpResponse::Ok().json(ApiResponse::success(response))
 }
 
+/// Build an unsigned `withdraw_all` transaction
+async fn build_withdraw_all_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildWithdrawAllTxRequest>,
+) -> impl Responder {
+    tracing::info!("API: Build withdraw-all transaction - user: {}", req.user_pubkey);
+
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    let user_pubkey = match Pubkey::from_str(&req.user_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user pubkey");
+        }
+    };
+
+    let vault_pubkey = match Pubkey::from_str(&req.vault_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault pubkey");
+        }
+    };
+
+    let vault_token_account = match Pubkey::from_str(&req.vault_token_account) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault token account");
+        }
+    };
+
+    let user_token_account = match Pubkey::from_str(&req.user_token_account) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user token account");
+        }
+    };
+
+    if let Err(e) = VaultManager::ensure_not_frozen(&state, &req.vault_pubkey).await {
+        return e.to_response();
+    }
+
+    // Verify the user owns the vault and it has something to withdraw; ownership can move
+    // with `transfer_ownership`, so the vault can't be derived from the user's key
+    let vault = match state.database.get_vault(&req.vault_pubkey).await {
+        Ok(Some(vault)) if vault.owner_pubkey != req.user_pubkey => {
+            return error::error_response(
+                StatusCode::FORBIDDEN,
+                error_codes::UNAUTHORIZED,
+                "Only the vault owner can withdraw from it",
+            );
+        }
+        Ok(Some(vault)) if vault.available_balance > 0 => vault,
+        Ok(Some(_)) => {
+            return error::bad_request(
+                error_codes::INSUFFICIENT_BALANCE,
+                "Insufficient available balance",
+            );
+        }
+        Ok(None) => {
+            return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found");
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
+        }
+    };
+
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            tracing::error!("Failed to get recent blockhash: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
+        }
+    };
+
+    let token_program = match vault_token_program(&vault) {
+        Ok(token_program) => token_program,
+        Err(resp) => return resp,
+    };
+
+    let transaction = match TransactionBuilder::build_withdraw_all_tx(
+        &state.program_id,
+        token_program,
+        &user_pubkey,
+        &vault_pubkey,
+        &vault_token_account,
+        &user_token_account,
+        recent_blockhash,
+    ) {
+        Ok(tx) => tx,
+        Err(e @ BuilderError::UnsupportedTokenProgram(_)) => return e.to_response(),
+        Err(e) => {
+            tracing::error!("Failed to build withdraw-all transaction: {}", e);
+            return error::internal(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
+        }
+    };
+
+    let serialized = match serde_json::to_string(&transaction) {
+        Ok(json_str) => base64::encode(json_str.as_bytes()),
+        Err(e) => {
+            tracing::error!("Failed to serialize transaction: {}", e);
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
+        }
+    };
+
+    let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
+
+    let response = UnsignedTransactionResponse {
+        transaction: serialized,
+        blockhash: recent_blockhash.to_string(),
+        estimated_fee,
+        suggested_priority_fee,
+        signers: vec![req.user_pubkey.clone()],
+        message: format!("Withdraw all {} available tokens from vault", vault.available_balance),
+    };
+
+    tracing::info!("Built unsigned withdraw-all transaction for user {}", req.user_pubkey);
+    HttpResponse::Ok().json(ApiResponse::success(response))
+}
+
+/// Priority fee to suggest for `transaction`; a failed estimate doesn't fail the build
+async fn suggested_priority_fee(state: &AppState, transaction: &Transaction) -> Option<u64> {
+    match TransactionBuilder::estimate_priority_fee(&state.solana_client, transaction).await {
+        Ok(fee) => Some(fee),
+        Err(e) => {
+            tracing::warn!("Failed to estimate priority fee: {}", e);
+            None
+        }
+    }
+}
+
+/// The token program stored for `vault`, which its transfers must be addressed to
+fn vault_token_program(vault: &Vault) -> Result<TokenProgram, HttpResponse> {
+    TokenProgram::from_str(&vault.token_program).map_err(|e| {
+        tracing::error!("Vault {} has an unusable token program: {}", vault.vault_pubkey, e);
+        error::internal(error_codes::UNSUPPORTED_TOKEN_PROGRAM, e.to_string())
+    })
+}
+
+/// Build an unsigned `batch_lock` transaction across several vaults
+async fn build_batch_lock_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildBatchLockTxRequest>,
+) -> impl Responder {
+    tracing::info!(
+        "API: Build batch lock transaction - {} vaults",
+        req.vault_pubkeys.len()
+    );
+
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    let payer_pubkey = match Pubkey::from_str(&req.payer_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid payer pubkey");
+        }
+    };
+
+    let authority_program = match Pubkey::from_str(&req.authority_program) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid authority program");
+        }
+    };
+
+    let vaults = match req
+        .vault_pubkeys
+        .iter()
+        .map(|v| Pubkey::from_str(v))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(vaults) => vaults,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault pubkey");
+        }
+    };
+
+    if let Err(resp) = req
+        .amounts
+        .iter()
+        .try_for_each(|amount| {
+            validation::amount_u64("amounts", *amount).and_then(|_| {
+                validation::max_amount("amounts", *amount, state.config.max_operation_amount)
+            })
+        })
+    {
+        return resp;
+    }
+
+    let nonces = match &req.nonces {
+        Some(nonces) => nonces.clone(),
+        None => {
+            let mut nonces = Vec::with_capacity(vaults.len());
+            for vault in &vaults {
+                match VaultManager::fetch_vault_nonce(&state, vault).await {
+                    Ok(nonce) => nonces.push(nonce),
+                    Err(e) => {
+                        tracing::error!("Failed to fetch nonce for vault {}: {}", vault, e);
+                        return e.to_response();
+                    }
+                }
+            }
+            nonces
+        }
+    };
+
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            tracing::error!("Failed to get recent blockhash: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
+        }
+    };
+
+    let transaction = match TransactionBuilder::build_batch_lock_tx(
+        &state.program_id,
+        &payer_pubkey,
+        &authority_program,
+        &vaults,
+        &req.amounts,
+        &nonces,
+        recent_blockhash,
+    ) {
+        Ok(tx) => tx,
+        Err(e) => {
+            tracing::warn!("Rejected batch lock transaction: {}", e);
+            return error::bad_request(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
+        }
+    };
+
+    let serialized = match serde_json::to_string(&transaction) {
+        Ok(json_str) => base64::encode(json_str.as_bytes()),
+        Err(e) => {
+            tracing::error!("Failed to serialize transaction: {}", e);
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
+        }
+    };
+
+    let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
+
+    let response = UnsignedTransactionResponse {
+        transaction: serialized,
+        blockhash: recent_blockhash.to_string(),
+        estimated_fee,
+        suggested_priority_fee,
+        signers: vec![req.payer_pubkey.clone()],
+        message: format!("Lock collateral in {} vaults", vaults.len()),
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(response))
+}
+
+/// Build an unsigned `lock_collateral` transaction
+async fn build_lock_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildCollateralTxRequest>,
+) -> impl Responder {
+    build_collateral_transaction(state, req, CollateralAction::Lock).await
+}
+
+/// Build an unsigned `unlock_collateral` transaction
+async fn build_unlock_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildCollateralTxRequest>,
+) -> impl Responder {
+    build_collateral_transaction(state, req, CollateralAction::Unlock).await
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CollateralAction {
+    Lock,
+    Unlock,
+}
+
+impl CollateralAction {
+    fn verb(self) -> &'static str {
+        match self {
+            CollateralAction::Lock => "Lock",
+            CollateralAction::Unlock => "Unlock",
+        }
+    }
+}
+
+async fn build_collateral_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildCollateralTxRequest>,
+    action: CollateralAction,
+) -> HttpResponse {
+    tracing::info!(
+        "API: Build {} transaction - vault: {}, amount: {}",
+        action.verb().to_lowercase(),
+        req.vault_pubkey,
+        req.amount
+    );
+
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    let max_amount = state.config.max_operation_amount;
+    if let Err(resp) = validation::amount_u64("amount", req.amount)
+        .and_then(|_| validation::max_amount("amount", req.amount, max_amount))
+    {
+        return resp;
+    }
+
+    let payer_pubkey = match Pubkey::from_str(&req.payer_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid payer pubkey");
+        }
+    };
+
+    let authority_program = match Pubkey::from_str(&req.authority_program) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid authority program");
+        }
+    };
+
+    let vault_pubkey = match Pubkey::from_str(&req.vault_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault pubkey");
+        }
+    };
+
+    // Verify vault exists and has enough to move
+    match state.database.get_vault(&req.vault_pubkey).await {
+        Ok(Some(vault)) => match action {
+            CollateralAction::Lock if vault.available_balance < req.amount as i64 => {
+                return error::bad_request(
+                    error_codes::INSUFFICIENT_BALANCE,
+                    "Insufficient available balance",
+                );
+            }
+            CollateralAction::Unlock if vault.locked_balance < req.amount as i64 => {
+                return error::bad_request(
+                    error_codes::INSUFFICIENT_LOCKED_BALANCE,
+                    "Insufficient locked balance",
+                );
+            }
+            _ => {}
+        },
+        Ok(None) => {
+            return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found");
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
+        }
+    }
+
+    // The program rejects unauthorized callers; don't hand out a tx that can only fail
+    if let Err(e) =
+        VaultManager::ensure_program_authorized(&state, &vault_pubkey, &authority_program).await
+    {
+        tracing::warn!("Rejected {} transaction: {}", action.verb().to_lowercase(), e);
+        return e.to_response();
+    }
+
+    let nonce = match req.nonce {
+        Some(nonce) => nonce,
+        None => match VaultManager::fetch_vault_nonce(&state, &vault_pubkey).await {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                tracing::error!("Failed to fetch vault nonce: {}", e);
+                return e.to_response();
+            }
+        },
+    };
+
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            tracing::error!("Failed to get recent blockhash: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
+        }
+    };
+
+    let build = match action {
+        CollateralAction::Lock => TransactionBuilder::build_lock_tx,
+        CollateralAction::Unlock => TransactionBuilder::build_unlock_tx,
+    };
+    let transaction = match build(
+        &state.program_id,
+        &payer_pubkey,
+        &authority_program,
+        &vault_pubkey,
+        req.amount,
+        nonce,
+        recent_blockhash,
+    ) {
+        Ok(tx) => tx,
+        Err(e) => {
+            tracing::error!("Failed to build {} transaction: {}", action.verb().to_lowercase(), e);
+            return error::internal(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
+        }
+    };
+
+    let serialized = match serde_json::to_string(&transaction) {
+        Ok(json_str) => base64::encode(json_str.as_bytes()),
+        Err(e) => {
+            tracing::error!("Failed to serialize transaction: {}", e);
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
+        }
+    };
+
+    let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
+
+    let response = UnsignedTransactionResponse {
+        transaction: serialized,
+        blockhash: recent_blockhash.to_string(),
+        estimated_fee,
+        suggested_priority_fee,
+        signers: vec![req.payer_pubkey.clone(), req.authority_program.clone()],
+        message: format!("{} {} tokens in vault", action.verb(), req.amount),
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(response))
+}
+
+/// Build an unsigned `authority_to_add` transaction for the vault's owner to sign
+async fn build_authorize_program_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildAuthorizeProgramTxRequest>,
+) -> impl Responder {
+    tracing::info!(
+        "API: Build authorize program transaction - vault: {}, program: {}",
+        req.vault_pubkey,
+        req.program_id
+    );
+
+    let admin_pubkey = match Pubkey::from_str(&req.admin_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid admin pubkey");
+        }
+    };
+
+    let vault_pubkey = match Pubkey::from_str(&req.vault_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid vault pubkey");
+        }
+    };
+
+    let program_id = match Pubkey::from_str(&req.program_id) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid program id");
+        }
+    };
+
+    // The program only accepts the vault's owner as admin
+    match state.database.get_vault(&req.vault_pubkey).await {
+        Ok(Some(vault)) if vault.owner_pubkey != req.admin_pubkey => {
+            return error::error_response(
+                StatusCode::FORBIDDEN,
+                error_codes::UNAUTHORIZED,
+                "Only the vault owner can authorize programs",
+            );
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found");
+        }
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
+        }
+    }
+
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            tracing::error!("Failed to get recent blockhash: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
+        }
+    };
+
+    let transaction = match TransactionBuilder::build_authorize_program_tx(
+        &state.program_id,
+        &admin_pubkey,
+        &vault_pubkey,
+        &program_id,
+        recent_blockhash,
+    ) {
+        Ok(tx) => tx,
+        Err(e) => {
+            tracing::error!("Failed to build authorize program transaction: {}", e);
+            return error::internal(
+                error_codes::TRANSACTION_BUILD_ERROR,
+                format!("Failed to build transaction: {}", e),
+            );
+        }
+    };
+
+    let serialized = match serde_json::to_string(&transaction) {
+        Ok(json_str) => base64::encode(json_str.as_bytes()),
+        Err(e) => {
+            tracing::error!("Failed to serialize transaction: {}", e);
+            return error::internal(
+                error_codes::SERIALIZATION_ERROR,
+                "Failed to serialize transaction",
+            );
+        }
+    };
+
+    let estimated_fee = TransactionBuilder::estimate_fee(&transaction, 5000);
+    let suggested_priority_fee = suggested_priority_fee(&state, &transaction).await;
+
+    let response = UnsignedTransactionResponse {
+        transaction: serialized,
+        blockhash: recent_blockhash.to_string(),
+        estimated_fee,
+        suggested_priority_fee,
+        signers: vec![req.admin_pubkey.clone()],
+        message: format!("Authorize program {} for vault {}", program_id, vault_pubkey),
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(response))
+}
+
 /// Build an unsigned vault initialization transaction
 async fn build_initialize_transaction(
     state: web::Data<AppState>,
@@ -350,18 +1100,21 @@ async fn build_initialize_transaction(
     let user_pubkey = match Pubkey::from_str(&req.user_pubkey) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid user pubkey".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user pubkey");
         }
     };
 
     let mint_pubkey = match Pubkey::from_str(&req.mint_pubkey) {
         Ok(pk) => pk,
         Err(_) => {
-            return HttpResponse::BadRequest()
-                .json(ApiResponse::<()>::error("Invalid mint pubkey".to_string()));
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid mint pubkey");
         }
     };
+    if let Err(resp) =
+        validation::allowed_mint("mint_pubkey", &mint_pubkey, &state.config.allowed_mints)
+    {
+        return resp;
+    }
 
     // Derive the vault PDA
     let (vault_pda, _bump) = Pubkey::find_program_address(
@@ -372,24 +1125,33 @@ async fn build_initialize_transaction(
     // Check if vault already exists
     match state.database.get_vault(&vault_pda.to_string()).await {
         Ok(Some(_)) => {
-            return HttpResponse::Conflict()
-                .json(ApiResponse::<()>::error("Vault already exists for this user".to_string()));
+            return error::error_response(
+                StatusCode::CONFLICT,
+                error_codes::VAULT_ALREADY_EXISTS,
+                "Vault already exists for this user",
+            );
         }
         Ok(None) => { /* Good, vault doesn't exist */ }
         Err(e) => {
             tracing::error!("Database error: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Database error".to_string()));
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
         }
     }
 
     // Get recent blockhash
-    let recent_blockhash = match state.solana_client.get_latest_blockhash().await {
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!("Failed to get recent blockhash: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get recent blockhash".to_string()));
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
         }
     };
 
@@ -400,6 +1162,7 @@ async fn build_initialize_transaction(
         transaction: "".to_string(), // Would be filled with actual instruction
         blockhash: recent_blockhash.to_string(),
         estimated_fee: 10000, // Rent + fees
+        suggested_priority_fee: None,
         signers: vec![req.user_pubkey.clone()],
         message: format!("Initialize vault at {}", vault_pda),
     };
@@ -407,6 +1170,188 @@ async fn build_initialize_transaction(
     HttpResponse::Ok().json(ApiResponse::success(response))
 }
 
+/// Build every transaction a new user signs to start depositing
+///
+/// Creates the user's token account for the mint when it doesn't exist yet, then
+/// initializes the vault, which creates the vault's own token account and authority PDA.
+async fn build_onboard_transaction(
+    state: web::Data<AppState>,
+    req: web::Json<BuildOnboardTxRequest>,
+) -> impl Responder {
+    tracing::info!("API: Build onboarding bundle - user: {}", req.user_pubkey);
+
+    let user_pubkey = match Pubkey::from_str(&req.user_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid user pubkey");
+        }
+    };
+
+    let mint_pubkey = match Pubkey::from_str(&req.mint_pubkey) {
+        Ok(pk) => pk,
+        Err(_) => {
+            return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid mint pubkey");
+        }
+    };
+    if let Err(resp) =
+        validation::allowed_mint("mint_pubkey", &mint_pubkey, &state.config.allowed_mints)
+    {
+        return resp;
+    }
+
+    if req.max_balance != 0 && req.min_deposit > req.max_balance {
+        return error::bad_request(
+            error_codes::INVALID_AMOUNT,
+            "min_deposit cannot exceed a non-zero max_balance",
+        );
+    }
+
+    let (vault_pda, _bump) =
+        Pubkey::find_program_address(&[b"vault", user_pubkey.as_ref()], &state.program_id);
+
+    match state.database.get_vault(&vault_pda.to_string()).await {
+        Ok(Some(_)) => {
+            return error::error_response(
+                StatusCode::CONFLICT,
+                error_codes::VAULT_ALREADY_EXISTS,
+                "Vault already exists for this user",
+            );
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("Database error: {}", e);
+            return error::internal(error_codes::DATABASE_ERROR, "Database error");
+        }
+    }
+
+    // The mint is owned by the token program its accounts must be created under
+    let token_program = match BalanceTracker::detect_token_program(&state, &mint_pubkey).await {
+        Ok(token_program) => token_program.unwrap_or_default(),
+        Err(e) => {
+            tracing::error!("Failed to detect token program for mint {}: {}", mint_pubkey, e);
+            return e.to_response();
+        }
+    };
+
+    let user_token_account = get_associated_token_address_with_program_id(
+        &user_pubkey,
+        &mint_pubkey,
+        &token_program.id(),
+    );
+    let user_ata_exists = match rpc_retry("get_user_token_account", || {
+        state
+            .solana_client
+            .get_account_with_commitment(&user_token_account, state.solana_client.commitment())
+    })
+    .await
+    {
+        Ok(response) => response.value.is_some(),
+        Err(e) => {
+            tracing::error!("Failed to look up user token account: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to look up user token account",
+            );
+        }
+    };
+
+    let recent_blockhash = match rpc_retry("get_latest_blockhash", || {
+        state.solana_client.get_latest_blockhash()
+    })
+    .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            tracing::error!("Failed to get recent blockhash: {}", e);
+            return error::error_response(
+                StatusCode::BAD_GATEWAY,
+                error_codes::SOLANA_RPC_ERROR,
+                "Failed to get recent blockhash",
+            );
+        }
+    };
+
+    let mut steps = Vec::with_capacity(2);
+    if !user_ata_exists {
+        steps.push((
+            TransactionBuilder::build_create_ata_tx(
+                token_program,
+                &user_pubkey,
+                &mint_pubkey,
+                recent_blockhash,
+            ),
+            format!("Create token account {} for mint {}", user_token_account, mint_pubkey),
+        ));
+    }
+    steps.push((
+        TransactionBuilder::build_initialize_vault_tx(
+            &state.program_id,
+            token_program,
+            &user_pubkey,
+            &mint_pubkey,
+            req.min_deposit,
+            req.max_balance,
+            recent_blockhash,
+        ),
+        format!("Initialize vault at {}", vault_pda),
+    ));
+
+    let mut transactions = Vec::with_capacity(steps.len());
+    for (built, description) in steps {
+        let transaction = match built {
+            Ok(tx) => tx,
+            Err(e @ BuilderError::UnsupportedTokenProgram(_)) => return e.to_response(),
+            Err(e) => {
+                tracing::error!("Failed to build onboarding transaction: {}", e);
+                return error::internal(
+                    error_codes::TRANSACTION_BUILD_ERROR,
+                    format!("Failed to build transaction: {}", e),
+                );
+            }
+        };
+
+        let serialized = match serde_json::to_string(&transaction) {
+            Ok(json_str) => base64::encode(json_str.as_bytes()),
+            Err(e) => {
+                tracing::error!("Failed to serialize transaction: {}", e);
+                return error::internal(
+                    error_codes::SERIALIZATION_ERROR,
+                    "Failed to serialize transaction",
+                );
+            }
+        };
+
+        transactions.push(BundledTransaction {
+            transaction: serialized,
+            estimated_fee: TransactionBuilder::estimate_fee(&transaction, 5000),
+            suggested_priority_fee: suggested_priority_fee(&state, &transaction).await,
+            signers: vec![req.user_pubkey.clone()],
+            description,
+        });
+    }
+
+    let response = OnboardBundleResponse {
+        vault_pubkey: vault_pda.to_string(),
+        vault_token_account: get_associated_token_address_with_program_id(
+            &vault_pda,
+            &mint_pubkey,
+            &token_program.id(),
+        )
+        .to_string(),
+        user_token_account: user_token_account.to_string(),
+        blockhash: recent_blockhash.to_string(),
+        transactions,
+    };
+
+    tracing::info!(
+        "Built {}-step onboarding bundle for user {}",
+        response.transactions.len(),
+        req.user_pubkey
+    );
+    HttpResponse::Ok().json(ApiResponse::success(response))
+}
+
 // ============================================================================
 // Transaction History Endpoints
 // ============================================================================
@@ -414,44 +1359,36 @@ async fn build_initialize_transaction(
 /// Get transaction history with optional filters
 async fn get_transaction_history(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     query: web::Query<TransactionHistoryQuery>,
 ) -> impl Responder {
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    let (after, limit, offset) = match page_params(&query) {
+        Ok(page) => page,
+        Err(resp) => return resp,
+    };
+
+    if ndjson::accepts_ndjson(&http_req) {
+        let query = query.into_inner();
+        let rows = state
+            .database
+            .stream_transactions(query.vault_pubkey, query.tx_type, after, limit, offset);
+        return ndjson::stream(rows, TransactionRecord::from);
+    }
 
     match state.database.get_transactions(
         query.vault_pubkey.as_deref(),
         query.tx_type.as_deref(),
+        after.as_ref(),
         limit,
         offset,
     ).await {
         Ok(transactions) => {
-            let records: Vec<TransactionRecord> = transactions
-                .into_iter()
-                .map(|t| TransactionRecord {
-                    id: t.id,
-                    vault_pubkey: t.vault_pubkey,
-                    tx_signature: t.tx_signature,
-                    tx_type: t.tx_type,
-                    amount: t.amount,
-                    status: t.status,
-                    created_at: t.created_at.to_rfc3339(),
-                })
-                .collect();
-
-            let response = TransactionHistoryResponse {
-                transactions: records,
-                total: 0, // Would need a count query
-                limit,
-                offset,
-            };
-
+            let response = history_response(transactions, limit, offset);
             HttpResponse::Ok().json(ApiResponse::success(response))
         }
         Err(e) => {
             tracing::error!("Failed to get transaction history: {}", e);
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get transaction history".to_string()))
+            error::internal(error_codes::DATABASE_ERROR, "Failed to get transaction history")
         }
     }
 }
@@ -459,70 +1396,128 @@ async fn get_transaction_history(
 /// Get transactions for a specific vault
 async fn get_vault_transactions(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     vault_pubkey: web::Path<String>,
     query: web::Query<TransactionHistoryQuery>,
 ) -> impl Responder {
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.offset.unwrap_or(0);
+    let (after, limit, offset) = match page_params(&query) {
+        Ok(page) => page,
+        Err(resp) => return resp,
+    };
 
-    match state.database.get_vault_transactions(&vault_pubkey, limit).await {
-        Ok(transactions) => {
-            let records: Vec<TransactionRecord> = transactions
-                .into_iter()
-                .map(|t| TransactionRecord {
-                    id: t.id,
-                    vault_pubkey: t.vault_pubkey,
-                    tx_signature: t.tx_signature,
-                    tx_type: t.tx_type,
-                    amount: t.amount,
-                    status: t.status,
-                    created_at: t.created_at.to_rfc3339(),
-                })
-                .collect();
-
-            let response = TransactionHistoryResponse {
-                transactions: records,
-                total: 0,
-                limit,
-                offset,
-            };
+    if ndjson::accepts_ndjson(&http_req) {
+        let query = query.into_inner();
+        let rows = state.database.stream_vault_transactions(
+            vault_pubkey.into_inner(),
+            query.tx_type,
+            query.status,
+            after,
+            limit,
+            offset,
+        );
+        return ndjson::stream(rows, TransactionRecord::from);
+    }
 
+    match state
+        .database
+        .get_vault_transactions(
+            &vault_pubkey,
+            query.tx_type.as_deref(),
+            query.status.as_deref(),
+            after.as_ref(),
+            limit,
+            offset,
+        )
+        .await
+    {
+        Ok(transactions) => {
+            let response = history_response(transactions, limit, offset);
             HttpResponse::Ok().json(ApiResponse::success(response))
         }
         Err(e) => {
             tracing::error!("Failed to get vault transactions: {}", e);
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get vault transactions".to_string()))
+            error::internal(error_codes::DATABASE_ERROR, "Failed to get vault transactions")
         }
     }
 }
 
+/// Resolve `(after, limit, offset)`, rejecting a malformed cursor or one combined with an offset
+fn page_params(
+    query: &TransactionHistoryQuery,
+) -> Result<(Option<TransactionCursor>, i64, i64), HttpResponse> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.offset.unwrap_or(0);
+
+    let Some(after) = query.after.as_deref() else {
+        return Ok((None, limit, offset));
+    };
+    if offset != 0 {
+        return Err(error::bad_request(
+            error_codes::INVALID_REQUEST,
+            "Use either after or offset, not both",
+        ));
+    }
+    match decode_cursor(after) {
+        Some(cursor) => Ok((Some(cursor), limit, 0)),
+        None => Err(error::bad_request(error_codes::INVALID_REQUEST, "Invalid cursor")),
+    }
+}
+
+fn history_response(
+    transactions: Vec<shared::TransactionRecord>,
+    limit: i64,
+    offset: i64,
+) -> TransactionHistoryResponse {
+    // A short page is the last one
+    let next_cursor = transactions
+        .last()
+        .filter(|_| transactions.len() as i64 == limit)
+        .map(|t| encode_cursor(&TransactionCursor { created_at: t.created_at, id: t.id }));
+
+    TransactionHistoryResponse {
+        transactions: transactions.into_iter().map(TransactionRecord::from).collect(),
+        total: 0, // Would need a count query
+        limit,
+        offset,
+        next_cursor,
+    }
+}
+
+/// Opaque to clients: URL-safe base64 of `<created_at micros>:<id>`
+pub fn encode_cursor(cursor: &TransactionCursor) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}:{}", cursor.created_at.timestamp_micros(), cursor.id))
+}
+
+pub fn decode_cursor(cursor: &str) -> Option<TransactionCursor> {
+    let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+    let (micros, id) = decoded.split_once(':')?;
+    Some(TransactionCursor {
+        created_at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+        id: id.parse().ok()?,
+    })
+}
+
 /// Get a specific transaction by signature
 async fn get_transaction(
     state: web::Data<AppState>,
     tx_signature: web::Path<String>,
 ) -> impl Responder {
+    // Wallets poll a fresh signature until it confirms
+    if let Some(t) = state.cache.get_transaction(&tx_signature).await {
+        return HttpResponse::Ok().json(ApiResponse::success(TransactionRecord::from(t)));
+    }
+
     match state.database.get_transaction_by_signature(&tx_signature).await {
         Ok(Some(t)) => {
-            let record = TransactionRecord {
-                id: t.id,
-                vault_pubkey: t.vault_pubkey,
-                tx_signature: t.tx_signature,
-                tx_type: t.tx_type,
-                amount: t.amount,
-                status: t.status,
-                created_at: t.created_at.to_rfc3339(),
-            };
-            HttpResponse::Ok().json(ApiResponse::success(record))
+            state.cache.set_transaction(t.clone()).await;
+            HttpResponse::Ok().json(ApiResponse::success(TransactionRecord::from(t)))
         }
         Ok(None) => {
-            HttpResponse::NotFound()
-                .json(ApiResponse::<()>::error("Transaction not found".to_string()))
+            error::not_found(error_codes::TRANSACTION_NOT_FOUND, "Transaction not found")
         }
         Err(e) => {
             tracing::error!("Failed to get transaction: {}", e);
-            HttpResponse::InternalServerError()
-                .json(ApiResponse::<()>::error("Failed to get transaction".to_string()))
+            error::internal(error_codes::DATABASE_ERROR, "Failed to get transaction")
         }
     }
 }
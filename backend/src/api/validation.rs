@@ -0,0 +1,80 @@
+//! Request validation at the API boundary
+//!
+//! Thin wrappers over `shared::utils` that turn a failed check into a 400
+//! naming the offending field, so malformed input never reaches the DB or RPC.
+
+use actix_web::HttpResponse;
+use shared::{validate_amount, validate_pubkey, validate_signature, VaultError};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::api::error;
+
+pub type ValidationResult = Result<(), HttpResponse>;
+
+fn bad_request(field: &str, error: VaultError) -> HttpResponse {
+    error::bad_request(error.code(), format!("{}: {}", field, error))
+}
+
+pub fn pubkey(field: &str, value: &str) -> ValidationResult {
+    validate_pubkey(value).map_err(|e| bad_request(field, e))
+}
+
+/// [`pubkey`], returning the parsed key for handlers that go on to use it
+pub fn parse_pubkey(field: &str, value: &str) -> Result<Pubkey, HttpResponse> {
+    pubkey(field, value)?;
+    Pubkey::from_str(value)
+        .map_err(|e| bad_request(field, VaultError::InvalidPubkey(e.to_string())))
+}
+
+pub fn signature(field: &str, value: &str) -> ValidationResult {
+    validate_signature(value).map_err(|e| bad_request(field, e))
+}
+
+pub fn amount(field: &str, value: i64) -> ValidationResult {
+    validate_amount(value)
+        .map(|_| ())
+        .map_err(|e| bad_request(field, e))
+}
+
+/// `amount` for on-chain `u64` amounts, which must also fit the DB's `BIGINT`
+pub fn amount_u64(field: &str, value: u64) -> ValidationResult {
+    let value = i64::try_from(value).map_err(|_| {
+        bad_request(field, VaultError::InvalidAmount("Amount exceeds i64::MAX".to_string()))
+    })?;
+    amount(field, value)
+}
+
+/// Reject `value` above the `MAX_OPERATION_AMOUNT` cap `max`; 0 means no cap
+pub fn max_amount(field: &str, value: u64, max: u64) -> ValidationResult {
+    within_max_amount(value, max).map_err(|e| bad_request(field, e))
+}
+
+/// The check behind [`max_amount`], for callers that report the error instead of responding
+pub fn within_max_amount(value: u64, max: u64) -> Result<(), VaultError> {
+    if max == 0 || value <= max {
+        return Ok(());
+    }
+    Err(VaultError::AmountExceedsMax { amount: value, max })
+}
+
+/// Reject a mint missing from the `ALLOWED_MINTS` list `allowed`; an empty list allows any mint
+pub fn allowed_mint(field: &str, mint: &Pubkey, allowed: &[Pubkey]) -> ValidationResult {
+    if allowed.is_empty() || allowed.contains(mint) {
+        return Ok(());
+    }
+    Err(bad_request(field, VaultError::MintNotAllowed(mint.to_string())))
+}
+
+/// Checks shared by the deposit/withdraw/lock/unlock endpoints
+pub fn balance_change(
+    vault_pubkey: &str,
+    amount_value: i64,
+    tx_signature: &str,
+    max_operation_amount: u64,
+) -> ValidationResult {
+    pubkey("vault_pubkey", vault_pubkey)?;
+    amount("amount", amount_value)?;
+    max_amount("amount", amount_value as u64, max_operation_amount)?;
+    signature("tx_signature", tx_signature)
+}
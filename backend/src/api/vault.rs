@@ -1,10 +1,25 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::middleware::from_fn;
+use actix_web::{guard, web, HttpRequest, HttpResponse, Responder};
 use shared::{
-    ApiResponse, CreateVaultRequest, LockCollateralRequest, PaginationParams,
-    ProcessDepositRequest, ProcessWithdrawalRequest, UnlockCollateralRequest,
+    error_codes, validate_amount, ApiResponse, AuthorizedPrograms, BalanceHistoryParams,
+    BalanceSimulation, CreateVaultRequest, InitializeVaultResponse, LockCollateralRequest,
+    ProcessDepositRequest, ProcessWithdrawAllRequest, ProcessWithdrawalRequest, ReplayParams,
+    SetAutoLockRequest, SetDailyLimitsRequest, SetLowBalanceRequest, SimulateOperationRequest,
+    TopUtilizationParams, TransactionType, TvlHistoryParams, UnlockCollateralRequest,
+    VaultActivityParams, VaultListParams, VaultNonce, VaultResponse, VaultSearchParams,
+    VaultUtilization, MAX_DECIMALS, MIN_SEARCH_PREFIX_LEN, USDT_DECIMALS,
 };
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 
-use crate::services::{AppState, VaultManager};
+use crate::api::admin::admin_guard;
+use crate::api::auth::require_signed_request;
+use crate::api::error::{self, ApiError};
+use crate::api::{idempotency, ndjson, validation};
+use crate::services::{
+    AppState, BackendError, BalanceTracker, EventListener, EventListenerConfig, TokenProgram,
+    VaultManager, MAX_BALANCE_HISTORY_POINTS,
+};
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -12,13 +27,64 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("initialize", web::post().to(initialize_vault))
             .route("/balance/{vault_pubkey}", web::get().to(get_balance))
             .route("/owner/{owner_pubkey}", web::get().to(get_vault_by_owner))
-            .route("/deposit", web::post().to(process_deposit))
-            .route("/withdraw", web::post().to(process_withdrawal))
-            .route("/lock", web::post().to(process_lock))
-            .route("/unlock", web::post().to(process_unlock))
-            .route("/sync/{vault_pubkey}", web::post().to(sync_vault))
+            .route("/owner/{owner_pubkey}/all", web::get().to(get_vaults_by_owner))
+            .service(
+                web::resource("/deposit")
+                    .wrap(from_fn(require_signed_request))
+                    .route(web::post().to(process_deposit)),
+            )
+            .service(
+                web::resource("/withdraw")
+                    .wrap(from_fn(require_signed_request))
+                    .route(web::post().to(process_withdrawal)),
+            )
+            .service(
+                web::resource("/withdraw-all")
+                    .wrap(from_fn(require_signed_request))
+                    .route(web::post().to(process_withdraw_all)),
+            )
+            .service(
+                web::resource("/lock")
+                    .wrap(from_fn(require_signed_request))
+                    .route(web::post().to(process_lock)),
+            )
+            .service(
+                web::resource("/unlock")
+                    .wrap(from_fn(require_signed_request))
+                    .route(web::post().to(process_unlock)),
+            )
             .route("/tvl", web::get().to(get_tvl))
-            .route("/list", web::get().to(list_vaults)),
+            .route("/tvl/history", web::get().to(get_tvl_history))
+            .route("/list", web::get().to(list_vaults))
+            .route("/search", web::get().to(search_vaults))
+            .route("/utilization/top", web::get().to(get_top_utilization))
+            .route("/{vault_pubkey}/utilization", web::get().to(get_utilization))
+            .route("/{vault_pubkey}/onchain", web::get().to(get_onchain_vault))
+            .route("/{vault_pubkey}/nonce", web::get().to(get_vault_nonce))
+            .route(
+                "/{vault_pubkey}/authorized-programs",
+                web::get().to(get_authorized_programs),
+            )
+            .route("/{vault_pubkey}/positions", web::get().to(get_positions))
+            .route("/{vault_pubkey}/activity", web::get().to(get_activity))
+            .route("/{vault_pubkey}/balance-history", web::get().to(get_balance_history))
+            .route("/{vault_pubkey}/auto-lock", web::get().to(get_auto_lock))
+            .route("/{vault_pubkey}/daily-limits", web::get().to(get_daily_limits))
+            .route("/{vault_pubkey}/low-balance", web::get().to(get_low_balance))
+            .route("/{vault_pubkey}/simulate", web::post().to(simulate_operation))
+            // Admin-only vault operations, all behind one `admin_guard`. The empty prefix
+            // matches any path, so this stays last and is only entered by POST/PUT requests
+            // no public route above has claimed.
+            .service(
+                web::scope("")
+                    .guard(guard::Any(guard::Post()).or(guard::Put()))
+                    .wrap(from_fn(admin_guard))
+                    .route("/sync/{vault_pubkey}", web::post().to(sync_vault))
+                    .route("/{vault_pubkey}/replay", web::post().to(replay_vault))
+                    .route("/{vault_pubkey}/auto-lock", web::put().to(set_auto_lock))
+                    .route("/{vault_pubkey}/daily-limits", web::put().to(set_daily_limits))
+                    .route("/{vault_pubkey}/low-balance", web::put().to(set_low_balance)),
+            ),
     );
 }
 
@@ -28,18 +94,64 @@ async fn initialize_vault(
 ) -> impl Responder {
     tracing::info!("API: Initialize vault {}", req.vault_pubkey);
 
+    if let Err(resp) = validation::pubkey("vault_pubkey", &req.vault_pubkey)
+        .and_then(|_| validation::pubkey("owner_pubkey", &req.owner_pubkey))
+        .and_then(|_| validation::pubkey("token_account", &req.token_account))
+    {
+        return resp;
+    }
+
+    let decimals = req.decimals.unwrap_or(USDT_DECIMALS);
+    if decimals > MAX_DECIMALS {
+        return error::bad_request(
+            error_codes::INVALID_REQUEST,
+            format!("decimals must be at most {}", MAX_DECIMALS),
+        );
+    }
+
+    let token_program = match &req.token_program {
+        Some(program_id) => match TokenProgram::from_str(program_id) {
+            Ok(token_program) => token_program,
+            Err(e) => {
+                return error::bad_request(error_codes::UNSUPPORTED_TOKEN_PROGRAM, e.to_string());
+            }
+        },
+        None => {
+            let token_account = match Pubkey::from_str(&req.token_account) {
+                Ok(pk) => pk,
+                Err(_) => {
+                    return error::bad_request(error_codes::INVALID_PUBKEY, "Invalid token account");
+                }
+            };
+            match BalanceTracker::detect_token_program(&state, &token_account).await {
+                Ok(token_program) => token_program.unwrap_or_default(),
+                Err(e) => {
+                    tracing::error!("Failed to detect token program: {}", e);
+                    return e.to_response();
+                }
+            }
+        }
+    };
+
     match VaultManager::initialize_vault(
         &state,
         &req.vault_pubkey,
         &req.owner_pubkey,
         &req.token_account,
+        decimals,
+        token_program,
     )
     .await
     {
-        Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(vault)),
+        Ok((vault, already_existed)) => {
+            HttpResponse::Ok().json(ApiResponse::success(InitializeVaultResponse {
+                vault: VaultResponse::from(vault),
+                already_existed,
+            }))
+        }
         Err(e) => {
             tracing::error!("Failed to initialize vault: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            e.to_response()
         }
     }
 }
@@ -50,19 +162,21 @@ async fn get_balance(
 ) -> impl Responder {
     let start = std::time::Instant::now();
 
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
     match VaultManager::get_vault(&state, &vault_pubkey).await {
         Ok(Some(vault)) => {
             let elapsed = start.elapsed();
             tracing::debug!("Balance query took {:?}", elapsed);
 
-            HttpResponse::Ok().json(ApiResponse::success(vault))
-        }
-        Ok(None) => {
-            HttpResponse::NotFound().json(ApiResponse::<()>::error("Vault not found".to_string()))
+            HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault)))
         }
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
         Err(e) => {
             tracing::error!("Failed to get vault balance: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            e.to_response()
         }
     }
 }
@@ -70,20 +184,42 @@ async fn get_vault_by_owner(
     state: web::Data<AppState>,
     owner_pubkey: web::Path<String>,
 ) -> impl Responder {
+    if let Err(resp) = validation::pubkey("owner_pubkey", &owner_pubkey) {
+        return resp;
+    }
+
     match VaultManager::get_vault_by_owner(&state, &owner_pubkey).await {
-        Ok(Some(vault)) => HttpResponse::Ok().json(ApiResponse::success(vault)),
-        Ok(None) => HttpResponse::NotFound().json(ApiResponse::<()>::error(
-            "Vault not found for owner".to_string(),
-        )),
+        Ok(Some(vault)) => {
+            HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault)))
+        }
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found for owner"),
         Err(e) => {
             tracing::error!("Failed to get vault by owner: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            e.to_response()
+        }
+    }
+}
+
+async fn get_vaults_by_owner(
+    state: web::Data<AppState>,
+    owner_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("owner_pubkey", &owner_pubkey) {
+        return resp;
+    }
+
+    match VaultManager::get_vaults_by_owner(&state, &owner_pubkey).await {
+        Ok(vaults) => HttpResponse::Ok().json(ApiResponse::success(into_responses(vaults))),
+        Err(e) => {
+            tracing::error!("Failed to list vaults by owner: {}", e);
+            e.to_response()
         }
     }
 }
 
 async fn process_deposit(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     req: web::Json<ProcessDepositRequest>,
 ) -> impl Responder {
     tracing::info!(
@@ -92,26 +228,50 @@ async fn process_deposit(
         req.vault_pubkey
     );
 
-    let start = std::time::Instant::now();
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
 
-    match VaultManager::process_deposit(&state, &req.vault_pubkey, req.amount, &req.tx_signature)
-        .await
+    if let Err(resp) =
+        validation::balance_change(
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+            state.config.max_operation_amount,
+        )
     {
-        Ok(vault) => {
-            let elapsed = start.elapsed();
-            tracing::info!("Deposit processed in {:?}", elapsed);
+        return resp;
+    }
 
-            HttpResponse::Ok().json(ApiResponse::success(vault))
-        }
-        Err(e) => {
-            tracing::error!("Failed to process deposit: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+    idempotency::with_idempotency(&state, &http_req, "deposit", &*req, || async {
+        let start = std::time::Instant::now();
+
+        match VaultManager::process_deposit(
+            &state,
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+        )
+        .await
+        {
+            Ok(vault) => {
+                let elapsed = start.elapsed();
+                tracing::info!("Deposit processed in {:?}", elapsed);
+
+                HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault)))
+            }
+            Err(e) => {
+                tracing::error!("Failed to process deposit: {}", e);
+                e.to_response()
+            }
         }
-    }
+    })
+    .await
 }
 
 async fn process_withdrawal(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     req: web::Json<ProcessWithdrawalRequest>,
 ) -> impl Responder {
     tracing::info!(
@@ -120,26 +280,81 @@ async fn process_withdrawal(
         req.vault_pubkey
     );
 
-    let start = std::time::Instant::now();
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
 
-    match VaultManager::process_withdrawal(&state, &req.vault_pubkey, req.amount, &req.tx_signature)
-        .await
+    if let Err(resp) =
+        validation::balance_change(
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+            state.config.max_operation_amount,
+        )
     {
-        Ok(vault) => {
-            let elapsed = start.elapsed();
-            tracing::info!("Withdrawal processed in {:?}", elapsed);
+        return resp;
+    }
 
-            HttpResponse::Ok().json(ApiResponse::success(vault))
-        }
-        Err(e) => {
-            tracing::error!("Failed to process withdrawal: {}", e);
-            HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))
+    idempotency::with_idempotency(&state, &http_req, "withdraw", &*req, || async {
+        let start = std::time::Instant::now();
+
+        match VaultManager::process_withdrawal(
+            &state,
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+        )
+        .await
+        {
+            Ok(vault) => {
+                let elapsed = start.elapsed();
+                tracing::info!("Withdrawal processed in {:?}", elapsed);
+
+                HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault)))
+            }
+            Err(e) => {
+                tracing::error!("Failed to process withdrawal: {}", e);
+                e.to_response()
+            }
         }
+    })
+    .await
+}
+
+async fn process_withdraw_all(
+    state: web::Data<AppState>,
+    http_req: HttpRequest,
+    req: web::Json<ProcessWithdrawAllRequest>,
+) -> impl Responder {
+    tracing::info!("API: Process withdraw-all from vault {}", req.vault_pubkey);
+
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &req.vault_pubkey)
+        .and_then(|_| validation::signature("tx_signature", &req.tx_signature))
+    {
+        return resp;
     }
+
+    idempotency::with_idempotency(&state, &http_req, "withdraw-all", &*req, || async {
+        match VaultManager::process_withdraw_all(&state, &req.vault_pubkey, &req.tx_signature)
+            .await
+        {
+            Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault))),
+            Err(e) => {
+                tracing::error!("Failed to process withdraw-all: {}", e);
+                e.to_response()
+            }
+        }
+    })
+    .await
 }
 
 async fn process_lock(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     req: web::Json<LockCollateralRequest>,
 ) -> impl Responder {
     tracing::info!(
@@ -148,18 +363,43 @@ async fn process_lock(
         req.vault_pubkey
     );
 
-    match VaultManager::process_lock(&state, &req.vault_pubkey, req.amount, &req.tx_signature).await
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    if let Err(resp) =
+        validation::balance_change(
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+            state.config.max_operation_amount,
+        )
     {
-        Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(vault)),
-        Err(e) => {
-            tracing::error!("Failed to process lock: {}", e);
-            HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))
-        }
+        return resp;
     }
+
+    idempotency::with_idempotency(&state, &http_req, "lock", &*req, || async {
+        match VaultManager::process_lock(
+            &state,
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+        )
+        .await
+        {
+            Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault))),
+            Err(e) => {
+                tracing::error!("Failed to process lock: {}", e);
+                e.to_response()
+            }
+        }
+    })
+    .await
 }
 
 async fn process_unlock(
     state: web::Data<AppState>,
+    http_req: HttpRequest,
     req: web::Json<UnlockCollateralRequest>,
 ) -> impl Responder {
     tracing::info!(
@@ -168,25 +408,322 @@ async fn process_unlock(
         req.vault_pubkey
     );
 
-    match VaultManager::process_unlock(&state, &req.vault_pubkey, req.amount, &req.tx_signature)
-        .await
+    if let Err(e) = VaultManager::ensure_not_paused(&state) {
+        return e.to_response();
+    }
+
+    if let Err(resp) =
+        validation::balance_change(
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+            state.config.max_operation_amount,
+        )
     {
-        Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(vault)),
-        Err(e) => {
-            tracing::error!("Failed to process unlock: {}", e);
-            HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string()))
+        return resp;
+    }
+
+    idempotency::with_idempotency(&state, &http_req, "unlock", &*req, || async {
+        match VaultManager::process_unlock(
+            &state,
+            &req.vault_pubkey,
+            req.amount,
+            &req.tx_signature,
+        )
+        .await
+        {
+            Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault))),
+            Err(e) => {
+                tracing::error!("Failed to process unlock: {}", e);
+                e.to_response()
+            }
         }
+    })
+    .await
+}
+
+/// Preview a deposit, withdrawal, lock or unlock against the vault's current balances
+///
+/// Applies the same checks as the real endpoints but records nothing; a rejected operation
+/// is reported in the body with `valid: false` rather than as an error status.
+async fn simulate_operation(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    req: web::Json<SimulateOperationRequest>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    if !matches!(
+        req.operation,
+        TransactionType::Deposit
+            | TransactionType::Withdraw
+            | TransactionType::Lock
+            | TransactionType::Unlock
+    ) {
+        return error::bad_request(
+            error_codes::INVALID_REQUEST,
+            "operation must be one of deposit, withdraw, lock or unlock",
+        );
     }
+
+    let vault = match VaultManager::get_vault(&state, &vault_pubkey).await {
+        Ok(Some(vault)) => vault,
+        Ok(None) => return error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to get vault for simulation: {}", e);
+            return e.to_response();
+        }
+    };
+
+    let precheck = validate_amount(req.amount)
+        .and_then(|amount| {
+            validation::within_max_amount(amount as u64, state.config.max_operation_amount)
+        })
+        .and_then(|_| match req.operation {
+            TransactionType::Deposit => vault.check_deposit_limits(req.amount),
+            _ => Ok(()),
+        });
+
+    let outcome = match precheck {
+        Err(e) => Err((e.code(), e.to_string())),
+        Ok(()) => match VaultManager::simulate(&state, &vault, &req.operation, req.amount).await {
+            Ok(projected) => Ok(projected),
+            Err(e) if e.status().is_server_error() => {
+                tracing::error!("Failed to simulate {}: {}", req.operation.as_str(), e);
+                return e.to_response();
+            }
+            Err(e) => Err((e.code(), e.to_string())),
+        },
+    };
+
+    let (projected, rejection) = match outcome {
+        Ok(projected) => (projected, None),
+        Err(rejection) => (vault, Some(rejection)),
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(BalanceSimulation {
+        vault_pubkey: projected.vault_pubkey,
+        operation: req.operation.clone(),
+        amount: req.amount,
+        valid: rejection.is_none(),
+        error_code: rejection.as_ref().map(|(code, _)| code.to_string()),
+        reason: rejection.map(|(_, reason)| reason),
+        total: projected.total_balance,
+        available: projected.available_balance,
+        locked: projected.locked_balance,
+    }))
 }
 
 async fn sync_vault(state: web::Data<AppState>, vault_pubkey: web::Path<String>) -> impl Responder {
     tracing::info!("API: Sync vault {}", vault_pubkey);
 
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
     match VaultManager::sync_vault_from_chain(&state, &vault_pubkey).await {
-        Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(vault)),
+        Ok(vault) => HttpResponse::Ok().json(ApiResponse::success(VaultResponse::from(vault))),
         Err(e) => {
             tracing::error!("Failed to sync vault: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            e.to_response()
+        }
+    }
+}
+
+async fn get_onchain_vault(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match VaultManager::compare_with_chain(&state, &vault_pubkey).await {
+        Ok(comparison) => HttpResponse::Ok().json(ApiResponse::success(comparison)),
+        Err(e) => {
+            tracing::error!("Failed to compare vault with chain: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_vault_nonce(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    let vault = match validation::parse_pubkey("vault_pubkey", &vault_pubkey) {
+        Ok(pk) => pk,
+        Err(resp) => return resp,
+    };
+
+    match VaultManager::fetch_vault_nonce(&state, &vault).await {
+        Ok(nonce) => HttpResponse::Ok().json(ApiResponse::success(VaultNonce {
+            vault_pubkey: vault_pubkey.into_inner(),
+            nonce,
+        })),
+        Err(e) => {
+            tracing::error!("Failed to fetch nonce for vault {}: {}", vault_pubkey, e);
+            e.to_response()
+        }
+    }
+}
+
+/// Programs listed in the vault's on-chain `VaultAuthority`, cached for a few seconds
+async fn get_authorized_programs(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    let vault = match validation::parse_pubkey("vault_pubkey", &vault_pubkey) {
+        Ok(pk) => pk,
+        Err(resp) => return resp,
+    };
+
+    if let Some(cached) = state.cache.get_authorized_programs(&vault_pubkey).await {
+        return HttpResponse::Ok().json(ApiResponse::success(cached));
+    }
+
+    let (vault_authority, _bump) =
+        Pubkey::find_program_address(&[b"vault_authority", vault.as_ref()], &state.program_id);
+    let mut response = AuthorizedPrograms {
+        vault_pubkey: vault_pubkey.into_inner(),
+        vault_authority: vault_authority.to_string(),
+        programs: Vec::new(),
+    };
+
+    match VaultManager::fetch_authorized_programs(&state, &vault).await {
+        Ok(programs) => {
+            response.programs = programs.iter().map(Pubkey::to_string).collect();
+            state.cache.set_authorized_programs(response.clone()).await;
+            HttpResponse::Ok().json(ApiResponse::success(response))
+        }
+        Err(e) => {
+            // The chain is unreachable: answer from what the event listener has recorded,
+            // uncached so the next request goes back to the chain
+            if let BackendError::SolanaRpc(_) = e {
+                let recorded = state
+                    .database
+                    .get_recorded_authorized_programs(&response.vault_pubkey)
+                    .await
+                    .unwrap_or_default();
+                if !recorded.is_empty() {
+                    tracing::warn!(
+                        "Serving recorded authorized programs for vault {}: {}",
+                        response.vault_pubkey,
+                        e
+                    );
+                    response.programs = recorded;
+                    return HttpResponse::Ok().json(ApiResponse::success(response));
+                }
+            }
+            tracing::error!(
+                "Failed to fetch authorized programs for vault {}: {}",
+                response.vault_pubkey,
+                e
+            );
+            e.to_response()
+        }
+    }
+}
+
+/// Open positions, as recorded from `PositionOpenedEvent`s; empty when there are none
+async fn get_positions(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match state.database.get_positions(&vault_pubkey).await {
+        Ok(positions) => HttpResponse::Ok().json(ApiResponse::success(positions)),
+        Err(e) => {
+            tracing::error!("Failed to get positions for vault {}: {}", vault_pubkey, e);
+            e.to_response()
+        }
+    }
+}
+
+/// Transactions and alerts for the vault in one feed, newest first, paged by `limit`/`offset`
+async fn get_activity(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    query: web::Query<VaultActivityParams>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+
+    match state.database.get_vault_activity(&vault_pubkey, limit, offset).await {
+        Ok(activity) => HttpResponse::Ok().json(ApiResponse::success(activity)),
+        Err(e) => {
+            tracing::error!("Failed to get activity for vault {}: {}", vault_pubkey, e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_balance_history(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    query: web::Query<BalanceHistoryParams>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::hours(24));
+
+    if from >= to {
+        return error::bad_request(error_codes::INVALID_REQUEST, "`from` must be before `to`");
+    }
+    let buckets = (to - query.interval.truncate(from)).num_seconds()
+        / query.interval.duration().num_seconds();
+    if buckets >= MAX_BALANCE_HISTORY_POINTS {
+        return error::bad_request(
+            error_codes::INVALID_REQUEST,
+            format!(
+                "Range spans more than {} {} buckets",
+                MAX_BALANCE_HISTORY_POINTS,
+                query.interval.as_str()
+            ),
+        );
+    }
+
+    let history =
+        BalanceTracker::get_balance_history(&state, &vault_pubkey, from, to, query.interval).await;
+    match history {
+        Ok(points) => HttpResponse::Ok().json(ApiResponse::success(points)),
+        Err(e) => {
+            tracing::error!("Failed to get balance history for vault {}: {}", vault_pubkey, e);
+            e.to_response()
+        }
+    }
+}
+
+async fn replay_vault(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    query: web::Query<ReplayParams>,
+) -> impl Responder {
+    tracing::info!("API: Replay vault {} from slot {}", vault_pubkey, query.from_slot);
+
+    let vault = match validation::parse_pubkey("vault_pubkey", &vault_pubkey) {
+        Ok(pk) => pk,
+        Err(resp) => return resp,
+    };
+
+    let listener = EventListener::new(state, EventListenerConfig::default());
+    match listener.replay_vault(&vault, query.from_slot).await {
+        Ok(summary) => HttpResponse::Ok().json(ApiResponse::success(summary)),
+        Err(e) => {
+            tracing::error!("Failed to replay vault {}: {}", vault_pubkey, e);
+            e.to_response()
         }
     }
 }
@@ -205,24 +742,277 @@ async fn get_tvl(state: web::Data<AppState>) -> impl Responder {
         }
         Err(e) => {
             tracing::error!("Failed to get TVL stats: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            e.to_response()
+        }
+    }
+}
+
+async fn get_tvl_history(
+    state: web::Data<AppState>,
+    query: web::Query<TvlHistoryParams>,
+) -> impl Responder {
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::hours(24));
+
+    if from >= to {
+        return error::bad_request(error_codes::INVALID_REQUEST, "`from` must be before `to`");
+    }
+
+    match state.database.get_tvl_history(from, to, query.interval).await {
+        Ok(points) => HttpResponse::Ok().json(ApiResponse::success(points)),
+        Err(e) => {
+            tracing::error!("Failed to get TVL history: {}", e);
+            e.to_response()
         }
     }
 }
 
 async fn list_vaults(
     state: web::Data<AppState>,
-    query: web::Query<PaginationParams>,
+    http_req: HttpRequest,
+    query: web::Query<VaultListParams>,
 ) -> impl Responder {
+    if let Some(owner) = &query.owner {
+        if let Err(resp) = validation::pubkey("owner", owner) {
+            return resp;
+        }
+    }
+
+    if ndjson::accepts_ndjson(&http_req) {
+        let rows = state.database.stream_all_vaults(query.into_inner());
+        return ndjson::stream(rows, VaultResponse::from);
+    }
+
+    match state.database.get_all_vaults(&query).await {
+        Ok(vaults) => HttpResponse::Ok().json(ApiResponse::success(into_responses(vaults))),
+        Err(e) => {
+            tracing::error!("Failed to list vaults: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn search_vaults(
+    state: web::Data<AppState>,
+    query: web::Query<VaultSearchParams>,
+) -> impl Responder {
+    if query.q.len() < MIN_SEARCH_PREFIX_LEN {
+        return error::bad_request(
+            error_codes::INVALID_REQUEST,
+            format!("q must be at least {} characters", MIN_SEARCH_PREFIX_LEN),
+        );
+    }
+    // Base58 only, which also keeps LIKE wildcards out of the pattern
+    if bs58::decode(&query.q).into_vec().is_err() {
+        return error::bad_request(error_codes::INVALID_PUBKEY, "q must be base58");
+    }
+
+    let limit = query.limit.clamp(1, 100);
+
+    match state.database.search_vaults(&query.q, limit).await {
+        Ok(vaults) => HttpResponse::Ok().json(ApiResponse::success(into_responses(vaults))),
+        Err(e) => {
+            tracing::error!("Failed to search vaults: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_utilization(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match BalanceTracker::get_vault_utilization(&state, &vault_pubkey).await {
+        Ok(utilization) => HttpResponse::Ok().json(ApiResponse::success(utilization)),
+        Err(e) => {
+            tracing::error!("Failed to get vault utilization: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_top_utilization(
+    state: web::Data<AppState>,
+    query: web::Query<TopUtilizationParams>,
+) -> impl Responder {
+    let limit = query.limit.clamp(1, 100);
+
+    match state.database.get_top_utilized_vaults(limit).await {
+        Ok(vaults) => {
+            let utilization: Vec<VaultUtilization> =
+                vaults.iter().map(VaultUtilization::from).collect();
+            HttpResponse::Ok().json(ApiResponse::success(utilization))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get top utilized vaults: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_auto_lock(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match state.database.get_auto_lock_settings(&vault_pubkey).await {
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to get auto-lock settings: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn set_auto_lock(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    req: web::Json<SetAutoLockRequest>,
+) -> impl Responder {
+    tracing::info!(
+        "API: Set auto-lock for vault {} (enabled: {}, buffer: {})",
+        vault_pubkey,
+        req.enabled,
+        req.buffer
+    );
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+    if req.buffer < 0 {
+        return error::bad_request(error_codes::INVALID_AMOUNT, "buffer must not be negative");
+    }
+
     match state
         .database
-        .get_all_vaults(query.limit, query.offset)
+        .set_auto_lock_settings(&vault_pubkey, req.enabled, req.buffer)
         .await
     {
-        Ok(vaults) => HttpResponse::Ok().json(ApiResponse::success(vaults)),
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
         Err(e) => {
-            tracing::error!("Failed to list vaults: {}", e);
-            HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+            tracing::error!("Failed to set auto-lock settings: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_daily_limits(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match state.database.get_daily_limit_settings(&vault_pubkey).await {
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to get daily limits: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn set_daily_limits(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    req: web::Json<SetDailyLimitsRequest>,
+) -> impl Responder {
+    tracing::info!(
+        "API: Set daily limits for vault {} (deposit: {}, withdraw: {})",
+        vault_pubkey,
+        req.daily_deposit_limit,
+        req.daily_withdraw_limit
+    );
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+    if req.daily_deposit_limit < 0 || req.daily_withdraw_limit < 0 {
+        return error::bad_request(error_codes::INVALID_AMOUNT, "limits must not be negative");
+    }
+
+    match state
+        .database
+        .set_daily_limit_settings(&vault_pubkey, req.daily_deposit_limit, req.daily_withdraw_limit)
+        .await
+    {
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to set daily limits: {}", e);
+            e.to_response()
         }
     }
 }
+
+async fn get_low_balance(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+
+    match state.database.get_low_balance_settings(&vault_pubkey).await {
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to get low balance settings: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn set_low_balance(
+    state: web::Data<AppState>,
+    vault_pubkey: web::Path<String>,
+    req: web::Json<SetLowBalanceRequest>,
+) -> impl Responder {
+    tracing::info!(
+        "API: Set low balance threshold for vault {} (floor: {}, percent: {})",
+        vault_pubkey,
+        req.low_balance_floor,
+        req.low_balance_percent
+    );
+
+    if let Err(resp) = validation::pubkey("vault_pubkey", &vault_pubkey) {
+        return resp;
+    }
+    if req.low_balance_floor < 0 {
+        return error::bad_request(error_codes::INVALID_AMOUNT, "floor must not be negative");
+    }
+    if !(0.0..=100.0).contains(&req.low_balance_percent) {
+        return error::bad_request(
+            error_codes::INVALID_AMOUNT,
+            "percent must be between 0 and 100",
+        );
+    }
+
+    match state
+        .database
+        .set_low_balance_settings(&vault_pubkey, req.low_balance_floor, req.low_balance_percent)
+        .await
+    {
+        Ok(Some(settings)) => HttpResponse::Ok().json(ApiResponse::success(settings)),
+        Ok(None) => error::not_found(error_codes::VAULT_NOT_FOUND, "Vault not found"),
+        Err(e) => {
+            tracing::error!("Failed to set low balance settings: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+fn into_responses(vaults: Vec<shared::Vault>) -> Vec<VaultResponse> {
+    vaults.into_iter().map(VaultResponse::from).collect()
+}
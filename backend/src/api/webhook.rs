@@ -0,0 +1,154 @@
+use actix_web::{web, HttpResponse, Responder};
+use shared::{error_codes, ApiResponse, CreateWebhookRequest, UpdateWebhookRequest};
+
+use crate::api::error::{self, ApiError};
+use crate::api::validation;
+use crate::services::webhook_dispatcher::WEBHOOK_EVENT_TYPES;
+use crate::services::AppState;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/webhooks")
+            .route("", web::post().to(create_webhook))
+            .route("", web::get().to(list_webhooks))
+            .route("/{id}", web::get().to(get_webhook))
+            .route("/{id}", web::put().to(update_webhook))
+            .route("/{id}", web::delete().to(delete_webhook)),
+    );
+}
+
+async fn create_webhook(
+    state: web::Data<AppState>,
+    req: web::Json<CreateWebhookRequest>,
+) -> impl Responder {
+    tracing::info!("API: Create webhook for {}", req.url);
+
+    if let Err(resp) = validate_url(&req.url)
+        .and_then(|_| validate_secret(&req.secret))
+        .and_then(|_| validate_event_types(&req.event_types))
+    {
+        return resp;
+    }
+    if let Some(vault_pubkey) = &req.vault_pubkey {
+        if let Err(resp) = validation::pubkey("vault_pubkey", vault_pubkey) {
+            return resp;
+        }
+    }
+
+    match state
+        .database
+        .create_webhook(&req.url, &req.secret, &req.event_types, req.vault_pubkey.as_deref())
+        .await
+    {
+        Ok(webhook) => HttpResponse::Ok().json(ApiResponse::success(webhook)),
+        Err(e) => {
+            tracing::error!("Failed to create webhook: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn list_webhooks(state: web::Data<AppState>) -> impl Responder {
+    match state.database.list_webhooks().await {
+        Ok(webhooks) => HttpResponse::Ok().json(ApiResponse::success(webhooks)),
+        Err(e) => {
+            tracing::error!("Failed to list webhooks: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn get_webhook(state: web::Data<AppState>, id: web::Path<i64>) -> impl Responder {
+    match state.database.get_webhook(*id).await {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse::success(webhook)),
+        Ok(None) => error::not_found(error_codes::WEBHOOK_NOT_FOUND, "Webhook not found"),
+        Err(e) => {
+            tracing::error!("Failed to get webhook: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn update_webhook(
+    state: web::Data<AppState>,
+    id: web::Path<i64>,
+    req: web::Json<UpdateWebhookRequest>,
+) -> impl Responder {
+    tracing::info!("API: Update webhook {}", id);
+
+    let checks = req
+        .url
+        .as_deref()
+        .map_or(Ok(()), validate_url)
+        .and_then(|_| req.secret.as_deref().map_or(Ok(()), validate_secret))
+        .and_then(|_| req.event_types.as_deref().map_or(Ok(()), validate_event_types));
+    if let Err(resp) = checks {
+        return resp;
+    }
+
+    match state
+        .database
+        .update_webhook(
+            *id,
+            req.url.as_deref(),
+            req.secret.as_deref(),
+            req.event_types.as_deref(),
+            req.active,
+        )
+        .await
+    {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(ApiResponse::success(webhook)),
+        Ok(None) => error::not_found(error_codes::WEBHOOK_NOT_FOUND, "Webhook not found"),
+        Err(e) => {
+            tracing::error!("Failed to update webhook: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+async fn delete_webhook(state: web::Data<AppState>, id: web::Path<i64>) -> impl Responder {
+    tracing::info!("API: Delete webhook {}", id);
+
+    match state.database.delete_webhook(*id).await {
+        Ok(true) => HttpResponse::Ok().json(ApiResponse::success(*id)),
+        Ok(false) => error::not_found(error_codes::WEBHOOK_NOT_FOUND, "Webhook not found"),
+        Err(e) => {
+            tracing::error!("Failed to delete webhook: {}", e);
+            e.to_response()
+        }
+    }
+}
+
+fn validate_url(url: &str) -> validation::ValidationResult {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(error::bad_request(
+            error_codes::INVALID_REQUEST,
+            "url must be an absolute http(s) URL",
+        )),
+    }
+}
+
+fn validate_secret(secret: &str) -> validation::ValidationResult {
+    if secret.is_empty() {
+        return Err(error::bad_request(error_codes::INVALID_REQUEST, "secret must not be empty"));
+    }
+    Ok(())
+}
+
+fn validate_event_types(event_types: &[String]) -> validation::ValidationResult {
+    match event_types
+        .iter()
+        .find(|t| !WEBHOOK_EVENT_TYPES.contains(&t.as_str()))
+    {
+        Some(unknown) => Err(error::bad_request(
+            error_codes::INVALID_REQUEST,
+            format!(
+                "Unknown event type '{}'; expected one of {}",
+                unknown,
+                WEBHOOK_EVENT_TYPES.join(", ")
+            ),
+        )),
+        None => Ok(()),
+    }
+}
@@ -1,56 +1,166 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use moka::future::Cache as MokaCache;
-use shared::{TvlStats, Vault};
+use shared::{AnalyticsOverview, AuthorizedPrograms, TransactionRecord, TvlStats, Vault};
+use sqlx::postgres::{PgListener, PgPool, PgPoolOptions};
+
+/// Postgres `NOTIFY` channel instances share cache invalidations on
+pub const INVALIDATION_CHANNEL: &str = "vault_cache_invalidation";
+
+/// Vaults and owner lists unread for this long are evicted before their TTL
+const VAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a vault's on-chain authorized program list is served from cache
+pub const AUTHORIZED_PROGRAMS_TTL: Duration = Duration::from_secs(10);
 
 #[derive(Clone)]
 pub struct Cache {
     pub vaults: MokaCache<String, Vault>,
-    pub owner_to_vaults: MokaCache<String, String>,
+    /// Every vault pubkey an owner holds, oldest first, as loaded from the database
+    pub owner_to_vaults: MokaCache<String, Vec<String>>,
     pub tvl_cache: MokaCache<String, TvlStats>,
+    pub analytics_cache: MokaCache<String, AnalyticsOverview>,
+    /// Transaction rows by signature; kept briefly since pending rows change status
+    pub transactions: MokaCache<String, TransactionRecord>,
+    /// On-chain `VaultAuthority` lists by vault, kept for `AUTHORIZED_PROGRAMS_TTL`
+    pub authorized_programs: MokaCache<String, AuthorizedPrograms>,
+    /// Publishes invalidations to other instances; `None` on single-instance deploys
+    invalidation: Option<PgPool>,
+    /// Last `global_paused` seen on the program config, refreshed by the pause watcher
+    program_paused: Arc<AtomicBool>,
 }
 
 impl Cache {
-    pub fn new(max_capacity: u64) -> Self {
+    /// Vaults and owner lists live for `ttl`, the TVL for `tvl_ttl`
+    pub fn new(max_capacity: u64, ttl: Duration, tvl_ttl: Duration) -> Self {
         Self {
             vaults: MokaCache::builder()
                 .max_capacity(max_capacity)
-                .time_to_live(Duration::from_secs(300))
-                .time_to_idle(Duration::from_secs(60))
+                .time_to_live(ttl)
+                .time_to_idle(VAULT_IDLE_TIMEOUT.min(ttl))
                 .build(),
 
             owner_to_vaults: MokaCache::builder()
                 .max_capacity(max_capacity)
-                .time_to_live(Duration::from_secs(300))
-                .time_to_idle(Duration::from_secs(60))
+                .time_to_live(ttl)
+                .time_to_idle(VAULT_IDLE_TIMEOUT.min(ttl))
                 .build(),
 
             tvl_cache: MokaCache::builder()
                 .max_capacity(1)
-                .time_to_live(Duration::from_secs(60))
+                .time_to_live(tvl_ttl)
+                .build(),
+
+            analytics_cache: MokaCache::builder()
+                .max_capacity(1)
+                .time_to_live(Duration::from_secs(30))
                 .build(),
+
+            transactions: MokaCache::builder()
+                .max_capacity(max_capacity)
+                .time_to_live(Duration::from_secs(5))
+                .build(),
+
+            authorized_programs: MokaCache::builder()
+                .max_capacity(max_capacity)
+                .time_to_live(AUTHORIZED_PROGRAMS_TTL)
+                .build(),
+
+            invalidation: None,
+            program_paused: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Share invalidations with every other instance connected to `url`
+    ///
+    /// Spawns a `LISTEN` task that drops entries other instances invalidate; from then
+    /// on `invalidate_vault` and `invalidate_owner` also `NOTIFY` the other instances.
+    pub async fn with_invalidation_channel(mut self, url: &str) -> Result<Self, sqlx::Error> {
+        let mut listener = PgListener::connect(url).await?;
+        listener.listen(INVALIDATION_CHANNEL).await?;
+        tokio::spawn(listen_for_invalidations(self.clone(), listener));
+
+        self.invalidation = Some(PgPoolOptions::new().max_connections(2).connect(url).await?);
+        Ok(self)
+    }
+
+    pub fn is_program_paused(&self) -> bool {
+        self.program_paused.load(Ordering::Relaxed)
+    }
+
+    /// Record the on-chain pause flag, returning the previous value
+    pub fn set_program_paused(&self, paused: bool) -> bool {
+        self.program_paused.swap(paused, Ordering::Relaxed)
+    }
+
     pub async fn get_vault(&self, vault_pubkey: &str) -> Option<Vault> {
         self.vaults.get(vault_pubkey).await
     }
 
     pub async fn set_vault(&self, vault: Vault) {
-        let pubkey = vault.vault_pubkey.clone();
-        let owner_pubkey = vault.owner_pubkey.clone();
+        // A cached owner list that doesn't know about this vault is incomplete
+        if let Some(owned) = self.owner_to_vaults.get(&vault.owner_pubkey).await {
+            if !owned.contains(&vault.vault_pubkey) {
+                self.owner_to_vaults.invalidate(&vault.owner_pubkey).await;
+            }
+        }
 
-        self.vaults.insert(pubkey.clone(), vault).await;
-        self.owner_to_vaults.insert(owner_pubkey, pubkey).await;
+        self.vaults.insert(vault.vault_pubkey.clone(), vault).await;
     }
 
     pub async fn invalidate_vault(&self, vault_pubkey: &str) {
         self.vaults.invalidate(vault_pubkey).await;
+        self.publish_invalidation(InvalidationKey::Vault, vault_pubkey).await;
+    }
+
+    pub async fn invalidate_owner(&self, owner_pubkey: &str) {
+        self.owner_to_vaults.invalidate(owner_pubkey).await;
+        self.publish_invalidation(InvalidationKey::Owner, owner_pubkey).await;
+    }
+
+    pub async fn get_transaction(&self, tx_signature: &str) -> Option<TransactionRecord> {
+        self.transactions.get(tx_signature).await
+    }
+
+    pub async fn set_transaction(&self, record: TransactionRecord) {
+        self.transactions.insert(record.tx_signature.clone(), record).await;
+    }
+
+    /// Drop a transaction whose status changed
+    pub async fn invalidate_transaction(&self, tx_signature: &str) {
+        self.transactions.invalidate(tx_signature).await;
+        self.publish_invalidation(InvalidationKey::Transaction, tx_signature).await;
+    }
+
+    /// Best effort: a lost notification only leaves other instances stale until TTL
+    async fn publish_invalidation(&self, key: InvalidationKey, pubkey: &str) {
+        let Some(pool) = &self.invalidation else {
+            return;
+        };
+        let result = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(INVALIDATION_CHANNEL)
+            .bind(format!("{}:{}", key.prefix(), pubkey))
+            .execute(pool)
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to publish cache invalidation for {}: {}", pubkey, e);
+        }
     }
 
     pub async fn get_vault_by_owner(&self, owner_pubkey: &str) -> Option<String> {
+        self.owner_to_vaults.get(owner_pubkey).await?.into_iter().next()
+    }
+
+    pub async fn get_owner_vaults(&self, owner_pubkey: &str) -> Option<Vec<String>> {
         self.owner_to_vaults.get(owner_pubkey).await
     }
+
+    pub async fn set_owner_vaults(&self, owner_pubkey: &str, vault_pubkeys: Vec<String>) {
+        self.owner_to_vaults.insert(owner_pubkey.to_string(), vault_pubkeys).await;
+    }
+
     pub async fn update_vault_balances(
         &self,
         vault_pubkey: &str,
@@ -69,6 +179,19 @@ impl Cache {
         Some(())
     }
 
+    pub async fn get_authorized_programs(&self, vault_pubkey: &str) -> Option<AuthorizedPrograms> {
+        self.authorized_programs.get(vault_pubkey).await
+    }
+
+    pub async fn set_authorized_programs(&self, programs: AuthorizedPrograms) {
+        self.authorized_programs.insert(programs.vault_pubkey.clone(), programs).await;
+    }
+
+    /// Drop a vault's cached program list after its authorizations changed
+    pub async fn invalidate_authorized_programs(&self, vault_pubkey: &str) {
+        self.authorized_programs.invalidate(vault_pubkey).await;
+    }
+
     pub async fn get_tvl_stats(&self) -> Option<TvlStats> {
         self.tvl_cache.get("tvl").await
     }
@@ -77,6 +200,14 @@ impl Cache {
         self.tvl_cache.insert("tvl".to_string(), stats).await;
     }
 
+    pub async fn get_analytics_overview(&self) -> Option<AnalyticsOverview> {
+        self.analytics_cache.get("overview").await
+    }
+
+    pub async fn set_analytics_overview(&self, overview: AnalyticsOverview) {
+        self.analytics_cache.insert("overview".to_string(), overview).await;
+    }
+
     pub async fn get_stats(&self) -> CacheStats {
         CacheStats {
             vault_entries: self.vaults.entry_count(),
@@ -85,6 +216,47 @@ impl Cache {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+enum InvalidationKey {
+    Vault,
+    Owner,
+    Transaction,
+}
+
+impl InvalidationKey {
+    fn prefix(self) -> &'static str {
+        match self {
+            InvalidationKey::Vault => "vault",
+            InvalidationKey::Owner => "owner",
+            InvalidationKey::Transaction => "tx",
+        }
+    }
+}
+
+/// Drop the local entries named by notifications on `INVALIDATION_CHANNEL`
+///
+/// Our own notifications come back too; dropping an entry twice is harmless.
+async fn listen_for_invalidations(cache: Cache, mut listener: PgListener) {
+    loop {
+        // `recv` reconnects on its own; an error means reconnecting failed
+        let notification = match listener.recv().await {
+            Ok(notification) => notification,
+            Err(e) => {
+                tracing::error!("Cache invalidation listener error: {}", e);
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        match notification.payload().split_once(':') {
+            Some(("vault", pubkey)) => cache.vaults.invalidate(pubkey).await,
+            Some(("owner", pubkey)) => cache.owner_to_vaults.invalidate(pubkey).await,
+            Some(("tx", signature)) => cache.transactions.invalidate(signature).await,
+            _ => tracing::warn!("Ignoring cache invalidation {:?}", notification.payload()),
+        }
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct CacheStats {
     pub vault_entries: u64,
@@ -9,14 +9,63 @@
 //! |----------|-------------|---------|----------|
 //! | `DATABASE_URL` | PostgreSQL connection string | - | Yes |
 //! | `SOLANA_RPC_URL` | Solana RPC endpoint | `https://api.devnet.solana.com` | No |
+//! | `SOLANA_COMMITMENT` | RPC read commitment: `processed`, `confirmed` or `finalized` | `confirmed` | No |
 //! | `PROGRAM_ID` | Deployed program ID | - | Yes |
 //! | `HOST` | Server bind address | `0.0.0.0` | No |
 //! | `PORT` | Server port | `3000` | No |
+//! | `WORKER_THREADS` | HTTP worker threads (0 for one per CPU) | `0` | No |
 //! | `MAX_DB_CONNECTIONS` | Database connection pool size | `50` | No |
-//! | `CACHE_TTL_SECONDS` | Cache TTL in seconds | `300` | No |
+//! | `CACHE_TTL_SECONDS` | Vault and owner cache TTL in seconds | `300` | No |
+//! | `TVL_CACHE_TTL_SECONDS` | TVL cache TTL in seconds | `60` | No |
+//! | `CACHE_INVALIDATION_URL` | Postgres URL instances share cache invalidations through | - | No |
 //! | `RECONCILIATION_INTERVAL_SECONDS` | Balance reconciliation interval | `3600` | No |
+//! | `RECONCILIATION_BATCH_SIZE` | Vaults loaded per reconciliation page | `500` | No |
+//! | `RECONCILIATION_CONCURRENCY` | Vaults reconciled concurrently (bounds RPC load) | `10` | No |
 //! | `MONITORING_INTERVAL_SECONDS` | Monitoring interval | `60` | No |
+//! | `LOW_BALANCE_FLOOR` | Default available balance below which a vault is low | `0` | No |
+//! | `LOW_BALANCE_PERCENT` | Default share of the total balance, in percent, to keep available | `10` | No |
+//! | `LOW_BALANCE_ALERT_COOLDOWN_SECONDS` | Quiet period between `low_balance` alerts per vault | `3600` | No |
+//! | `ALERT_RETENTION_DAYS` | Days resolved and acknowledged alerts are kept (0 keeps all) | `30` | No |
+//! | `CRITICAL_ALERT_RETENTION_DAYS` | Days resolved and acknowledged critical alerts are kept | `90` | No |
+//! | `DORMANT_VAULT_DAYS` | Days without activity before a vault is flagged dormant (0 disables) | `90` | No |
+//! | `EVENT_POLL_INTERVAL_MS` | Interval between event listener polls in milliseconds | `2000` | No |
+//! | `EVENT_USE_WEBSOCKET` | Subscribe to program logs instead of polling | `false` | No |
+//! | `EVENT_MAX_RETRIES` | Attempts before a dead-lettered event is abandoned | `3` | No |
+//! | `HEARTBEAT_MISSED_CYCLES` | Cycles a background service may miss before `/health/ready` fails | `3` | No |
+//! | `WS_AUTH_REQUIRED` | Require signed auth before vault subscriptions | `false` | No |
+//! | `WS_RESYNC_LAGGING_CLIENTS` | Send `resync` to WebSocket clients that missed broadcasts | `true` | No |
+//! | `WS_MAX_CLIENTS` | WebSocket connections accepted at once (0 for no limit) | `10000` | No |
+//! | `WS_MAX_SUBS_PER_CLIENT` | Vaults one WebSocket client may subscribe to (0 for no limit) | `100` | No |
+//! | `RECONCILIATION_AUTO_REPAIR` | Sync mismatched vaults from chain during reconciliation | `false` | No |
+//! | `TVL_SAMPLE_INTERVAL_MINUTES` | Interval between TVL history samples | `5` | No |
+//! | `IDEMPOTENCY_TTL_SECONDS` | How long `Idempotency-Key` responses are replayed | `86400` | No |
+//! | `CONFIRMATION_CHECK_INTERVAL_SECONDS` | Interval between pending transaction checks | `30` | No |
+//! | `PENDING_CONFIRMATION_AGE_SECONDS` | Age a `pending` transaction is checked on-chain at | `60` | No |
+//! | `PENDING_STUCK_ALERT_SECONDS` | Age a still-`pending` transaction is alerted as stuck at (0 disables) | `600` | No |
+//! | `AUTO_LOCK_INTERVAL_SECONDS` | Interval between auto-lock sweeps | `300` | No |
+//! | `AUTO_LOCK_AUTHORITY` | Authorized program that auto-lock locks collateral for | - | No |
+//! | `STRICT_DEPOSIT_VERIFICATION` | Verify deposits on-chain before crediting them | `true` | No |
+//! | `DAILY_DEPOSIT_LIMIT` | Default 24h deposit cap per vault (0 for none) | `0` | No |
+//! | `DAILY_WITHDRAW_LIMIT` | Default 24h withdrawal cap per vault (0 for none) | `0` | No |
+//! | `MAX_OPERATION_AMOUNT` | Largest amount a single operation may move (0 for none) | `0` | No |
+//! | `ALLOWED_MINTS` | Comma-separated mints vaults may be created for (empty for any) | - | No |
+//! | `CACHE_WARM_VAULTS` | Recently active vaults loaded into the cache on startup | `1000` | No |
+//! | `REQUIRE_SIGNED_REQUESTS` | Require an HMAC session signature on balance-changing requests | `false` | No |
+//! | `SESSION_TTL_SECONDS` | How long a session secret issued by `/auth` stays valid | `3600` | No |
+//! | `ADMIN_API_KEY` | Key `/api/v1/admin`, vault sync and replay requests must send in `X-Admin-Key` | - | No |
+//! | `LOG_FORMAT` | Log output format, `pretty` or `json` | `pretty` | No |
+//! | `DEV_MODE` | Allow any CORS origin, method and header | `false` | No |
+//! | `CORS_ALLOWED_ORIGINS` | Comma-separated origins allowed to call the API | - | No |
+//! | `CORS_ALLOWED_METHODS` | Comma-separated methods allowed cross-origin | see below | No |
+//! | `CORS_ALLOWED_HEADERS` | Comma-separated headers allowed cross-origin | see below | No |
+//!
+//! `CORS_ALLOWED_METHODS` defaults to `GET,POST,PUT,DELETE,OPTIONS` and
+//! `CORS_ALLOWED_HEADERS` to
+//! `Content-Type,Authorization,Idempotency-Key,X-Session-Id,X-Signature`.
+
+use actix_web::http::{header::HeaderName, Method, Uri};
 
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -34,16 +83,105 @@ pub struct Config {
     pub database_url: String,
     /// Solana RPC endpoint URL
     pub solana_rpc_url: String,
+    /// Commitment the RPC client reads at
+    pub solana_commitment: CommitmentConfig,
     /// Public key of the deployed Anchor program
     pub program_id: Pubkey,
+    /// HTTP worker threads; `WORKER_THREADS=0` resolves to one per CPU
+    pub worker_threads: usize,
     /// Maximum number of database connections in the pool
     pub max_db_connections: u32,
-    /// Time-to-live for cached data in seconds
+    /// Time-to-live for cached vaults and owner lists in seconds
     pub cache_ttl_seconds: u32,
+    /// Time-to-live for the cached TVL in seconds
+    pub tvl_cache_ttl_seconds: u32,
+    /// Postgres URL whose `LISTEN/NOTIFY` carries cache invalidations between instances;
+    /// unset on single-instance deploys
+    pub cache_invalidation_url: Option<String>,
     /// Interval between balance reconciliation runs in seconds
     pub reconciliation_interval_seconds: u64,
+    /// How many vaults each reconciliation page loads from the database
+    pub reconciliation_batch_size: i64,
+    /// Maximum vaults reconciled at once, and so on-chain lookups in flight
+    pub reconciliation_concurrency: usize,
     /// Interval between monitoring checks in seconds
     pub monitoring_interval_seconds: u64,
+    /// Available balance below which the monitor flags a vault, unless the vault sets its own
+    pub low_balance_floor: i64,
+    /// Percentage of the total balance a vault should keep available, unless it sets its own
+    pub low_balance_percent: f64,
+    /// Seconds after a `low_balance` alert before the same vault is alerted again
+    pub low_balance_alert_cooldown_seconds: u64,
+    /// Days resolved and acknowledged alerts are kept before the monitor prunes them (0 keeps all)
+    pub alert_retention_days: u64,
+    /// Like `alert_retention_days` for critical alerts, usually longer
+    pub critical_alert_retention_days: u64,
+    /// Days since `last_activity` after which the monitor raises a `dormant_vault` alert
+    /// (0 disables the check)
+    pub dormant_vault_days: u64,
+    /// Interval between event listener polls in milliseconds
+    pub event_poll_interval_ms: u64,
+    /// Whether the event listener subscribes over WebSocket instead of polling
+    pub event_use_websocket: bool,
+    /// Attempts at a dead-lettered event before it is abandoned
+    pub event_max_retries: u32,
+    /// Cycles the monitor, reconciler or event listener may go without a heartbeat
+    /// before `/health/ready` reports it stale
+    pub heartbeat_missed_cycles: u32,
+    /// Whether WebSocket clients must authenticate before subscribing to a vault
+    pub ws_auth_required: bool,
+    /// Whether a WebSocket client that fell behind its broadcast channel is told to resync
+    pub ws_resync_lagging_clients: bool,
+    /// WebSocket connections accepted at once; later ones are closed with 1013 (0 for no limit)
+    pub ws_max_clients: usize,
+    /// Vaults a single WebSocket client may subscribe to (0 for no limit)
+    pub ws_max_subs_per_client: usize,
+    /// Whether reconciliation pulls on-chain state into the DB when it finds a mismatch
+    pub auto_repair: bool,
+    /// Interval between TVL history samples in minutes
+    pub tvl_sample_interval_minutes: u64,
+    /// How long a stored `Idempotency-Key` response is replayed, in seconds
+    pub idempotency_ttl_seconds: i64,
+    /// Interval between checks for stuck `pending` transactions in seconds
+    pub confirmation_check_interval_seconds: u64,
+    /// How old a `pending` transaction must be before its signature is looked up on-chain
+    pub pending_confirmation_age_seconds: i64,
+    /// Age at which a transaction still `pending` after its on-chain lookup raises a
+    /// `transaction_stuck` alert (0 disables the alert)
+    pub pending_stuck_alert_seconds: i64,
+    /// Interval between auto-lock sweeps in seconds
+    pub auto_lock_interval_seconds: u64,
+    /// Authorized program auto-lock locks collateral for; auto-lock is off when unset
+    pub auto_lock_authority: Option<Pubkey>,
+    /// Whether deposits are checked against the on-chain transaction before being credited
+    pub strict_deposit_verification: bool,
+    /// Deposits a vault may take in any 24h window unless it sets its own limit (0 for none)
+    pub daily_deposit_limit: i64,
+    /// Withdrawals a vault may make in any 24h window unless it sets its own limit (0 for none)
+    pub daily_withdraw_limit: i64,
+    /// Largest amount a single deposit, withdrawal, lock or unlock may move (0 for none)
+    pub max_operation_amount: u64,
+    /// Mints vault initialization transactions may be built for; empty allows any mint
+    pub allowed_mints: Vec<Pubkey>,
+    /// How many recently active vaults to load into the cache on startup (0 to skip)
+    pub cache_warm_vaults: i64,
+    /// Whether deposit, withdraw, withdraw-all, lock and unlock bodies must carry an
+    /// `X-Signature` HMAC keyed by a session secret from `/auth`
+    pub require_signed_requests: bool,
+    /// How long a session secret issued by `/auth` is accepted, in seconds
+    pub session_ttl_seconds: i64,
+    /// Key admin endpoints require in `X-Admin-Key`; they are disabled when unset
+    pub admin_api_key: Option<String>,
+    /// Log output format
+    pub log_format: LogFormat,
+    /// Development mode; CORS allows any origin, method and header
+    pub dev_mode: bool,
+    /// Origins allowed to make cross-origin requests (ignored in dev mode)
+    pub cors_allowed_origins: Vec<String>,
+    /// HTTP methods allowed on cross-origin requests (ignored in dev mode)
+    pub cors_allowed_methods: Vec<String>,
+    /// Request headers allowed on cross-origin requests (ignored in dev mode)
+    pub cors_allowed_headers: Vec<String>,
 }
 
 impl Config {
@@ -54,11 +192,49 @@ impl Config {
     /// Panics if required environment variables are missing or invalid:
     /// - `DATABASE_URL`: Must be a valid PostgreSQL connection string
     /// - `PROGRAM_ID`: Must be a valid Solana public key
+    /// - `SOLANA_COMMITMENT`: Must be `processed`, `confirmed` or `finalized` (if set)
     /// - `PORT`: Must be a valid port number (if set)
+    /// - `WORKER_THREADS`: Must be a valid number (if set)
     /// - `MAX_DB_CONNECTIONS`: Must be a valid number (if set)
     /// - `CACHE_TTL_SECONDS`: Must be a valid number (if set)
     /// - `RECONCILIATION_INTERVAL_SECONDS`: Must be a valid number (if set)
+    /// - `RECONCILIATION_BATCH_SIZE`: Must be a valid number (if set)
+    /// - `RECONCILIATION_CONCURRENCY`: Must be a valid number (if set)
     /// - `MONITORING_INTERVAL_SECONDS`: Must be a valid number (if set)
+    /// - `LOW_BALANCE_FLOOR`: Must be a valid non-negative number (if set)
+    /// - `LOW_BALANCE_PERCENT`: Must be a number from 0 to 100 (if set)
+    /// - `LOW_BALANCE_ALERT_COOLDOWN_SECONDS`: Must be a valid number (if set)
+    /// - `ALERT_RETENTION_DAYS`: Must be a valid number (if set)
+    /// - `CRITICAL_ALERT_RETENTION_DAYS`: Must be a valid number (if set)
+    /// - `DORMANT_VAULT_DAYS`: Must be a valid number (if set)
+    /// - `EVENT_POLL_INTERVAL_MS`: Must be a number greater than zero (if set)
+    /// - `EVENT_USE_WEBSOCKET`: Must be `true` or `false` (if set)
+    /// - `EVENT_MAX_RETRIES`: Must be a valid number (if set)
+    /// - `HEARTBEAT_MISSED_CYCLES`: Must be a number greater than zero (if set)
+    /// - `WS_AUTH_REQUIRED`: Must be `true` or `false` (if set)
+    /// - `WS_MAX_CLIENTS`: Must be a valid number (if set)
+    /// - `WS_MAX_SUBS_PER_CLIENT`: Must be a valid number (if set)
+    /// - `RECONCILIATION_AUTO_REPAIR`: Must be `true` or `false` (if set)
+    /// - `TVL_SAMPLE_INTERVAL_MINUTES`: Must be a valid number (if set)
+    /// - `IDEMPOTENCY_TTL_SECONDS`: Must be a valid number (if set)
+    /// - `CONFIRMATION_CHECK_INTERVAL_SECONDS`: Must be a valid number (if set)
+    /// - `PENDING_CONFIRMATION_AGE_SECONDS`: Must be a valid number (if set)
+    /// - `PENDING_STUCK_ALERT_SECONDS`: Must be a valid number (if set)
+    /// - `AUTO_LOCK_INTERVAL_SECONDS`: Must be a valid number (if set)
+    /// - `AUTO_LOCK_AUTHORITY`: Must be a valid Solana public key (if set)
+    /// - `STRICT_DEPOSIT_VERIFICATION`: Must be `true` or `false` (if set)
+    /// - `DAILY_DEPOSIT_LIMIT`: Must be a valid number (if set)
+    /// - `DAILY_WITHDRAW_LIMIT`: Must be a valid number (if set)
+    /// - `MAX_OPERATION_AMOUNT`: Must be a valid non-negative number (if set)
+    /// - `ALLOWED_MINTS`: Each entry must be a valid Solana public key (if set)
+    /// - `CACHE_WARM_VAULTS`: Must be a valid number (if set)
+    /// - `REQUIRE_SIGNED_REQUESTS`: Must be `true` or `false` (if set)
+    /// - `SESSION_TTL_SECONDS`: Must be a valid number (if set)
+    /// - `LOG_FORMAT`: Must be `pretty` or `json` (if set)
+    /// - `DEV_MODE`: Must be `true` or `false` (if set)
+    /// - `CORS_ALLOWED_ORIGINS`: Each entry must be a valid origin other than `*` (if set)
+    /// - `CORS_ALLOWED_METHODS`: Each entry must be a valid HTTP method (if set)
+    /// - `CORS_ALLOWED_HEADERS`: Each entry must be a valid header name (if set)
     ///
     /// # Examples
     ///
@@ -79,6 +255,11 @@ impl Config {
         let solana_rpc_url = std::env::var("SOLANA_RPC_URL")
             .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
 
+        let solana_commitment = parse_commitment(
+            &std::env::var("SOLANA_COMMITMENT")
+                .unwrap_or_else(|_| DEFAULT_SOLANA_COMMITMENT.to_string()),
+        )?;
+
         let program_id_str =
             std::env::var("PROGRAM_ID").map_err(|_| ConfigError::MissingEnvVar("PROGRAM_ID"))?;
 
@@ -92,6 +273,10 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidPort)?;
 
+        let worker_threads = parse_worker_threads(
+            &std::env::var("WORKER_THREADS").unwrap_or_else(|_| "0".to_string()),
+        )?;
+
         let max_db_connections = std::env::var("MAX_DB_CONNECTIONS")
             .unwrap_or_else(|_| "50".to_string())
             .parse()
@@ -101,30 +286,355 @@ impl Config {
             .unwrap_or_else(|_| "300".to_string())
             .parse()
             .map_err(|_| ConfigError::InvalidNumber("CACHE_TTL_SECONDS"))?;
+
+        let tvl_cache_ttl_seconds = std::env::var("TVL_CACHE_TTL_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("TVL_CACHE_TTL_SECONDS"))?;
+
+        let cache_invalidation_url = std::env::var("CACHE_INVALIDATION_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+
         let reconciliation_interval_seconds = std::env::var("RECONCILIATION_INTERVAL_SECONDS")
             .unwrap_or_else(|_| "3600".to_string())
             .parse()
             .map_err(|_| ConfigError::InvalidNumber("RECONCILIATION_INTERVAL_SECONDS"))?;
 
+        let reconciliation_batch_size = std::env::var("RECONCILIATION_BATCH_SIZE")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("RECONCILIATION_BATCH_SIZE"))?;
+
+        let reconciliation_concurrency = std::env::var("RECONCILIATION_CONCURRENCY")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("RECONCILIATION_CONCURRENCY"))?;
+
         let monitoring_interval_seconds = std::env::var("MONITORING_INTERVAL_SECONDS")
             .unwrap_or_else(|_| "60".to_string())
             .parse()
             .map_err(|_| ConfigError::InvalidNumber("MONITORING_INTERVAL_SECONDS"))?;
 
+        let low_balance_floor = std::env::var("LOW_BALANCE_FLOOR")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .ok()
+            .filter(|floor: &i64| *floor >= 0)
+            .ok_or(ConfigError::InvalidNumber("LOW_BALANCE_FLOOR"))?;
+
+        let low_balance_percent = std::env::var("LOW_BALANCE_PERCENT")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .ok()
+            .filter(|percent: &f64| (0.0..=100.0).contains(percent))
+            .ok_or(ConfigError::InvalidNumber("LOW_BALANCE_PERCENT"))?;
+
+        let low_balance_alert_cooldown_seconds =
+            std::env::var("LOW_BALANCE_ALERT_COOLDOWN_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidNumber("LOW_BALANCE_ALERT_COOLDOWN_SECONDS"))?;
+
+        let alert_retention_days = std::env::var("ALERT_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("ALERT_RETENTION_DAYS"))?;
+
+        let critical_alert_retention_days = std::env::var("CRITICAL_ALERT_RETENTION_DAYS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("CRITICAL_ALERT_RETENTION_DAYS"))?;
+
+        let dormant_vault_days = std::env::var("DORMANT_VAULT_DAYS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("DORMANT_VAULT_DAYS"))?;
+
+        // A zero interval would make `tokio::time::interval` panic in the listener
+        let event_poll_interval_ms = std::env::var("EVENT_POLL_INTERVAL_MS")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse()
+            .ok()
+            .filter(|ms: &u64| *ms > 0)
+            .ok_or(ConfigError::InvalidNumber("EVENT_POLL_INTERVAL_MS"))?;
+
+        let event_use_websocket = std::env::var("EVENT_USE_WEBSOCKET")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("EVENT_USE_WEBSOCKET"))?;
+
+        let event_max_retries = std::env::var("EVENT_MAX_RETRIES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("EVENT_MAX_RETRIES"))?;
+
+        // Zero cycles would report every service stale the moment it beats
+        let heartbeat_missed_cycles = std::env::var("HEARTBEAT_MISSED_CYCLES")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .ok()
+            .filter(|cycles: &u32| *cycles > 0)
+            .ok_or(ConfigError::InvalidNumber("HEARTBEAT_MISSED_CYCLES"))?;
+
+        let ws_auth_required = std::env::var("WS_AUTH_REQUIRED")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("WS_AUTH_REQUIRED"))?;
+
+        let ws_resync_lagging_clients = std::env::var("WS_RESYNC_LAGGING_CLIENTS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("WS_RESYNC_LAGGING_CLIENTS"))?;
+
+        let ws_max_clients = std::env::var("WS_MAX_CLIENTS")
+            .unwrap_or_else(|_| "10000".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("WS_MAX_CLIENTS"))?;
+
+        let ws_max_subs_per_client = std::env::var("WS_MAX_SUBS_PER_CLIENT")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("WS_MAX_SUBS_PER_CLIENT"))?;
+
+        let auto_repair = std::env::var("RECONCILIATION_AUTO_REPAIR")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("RECONCILIATION_AUTO_REPAIR"))?;
+
+        let tvl_sample_interval_minutes = std::env::var("TVL_SAMPLE_INTERVAL_MINUTES")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("TVL_SAMPLE_INTERVAL_MINUTES"))?;
+
+        let idempotency_ttl_seconds = std::env::var("IDEMPOTENCY_TTL_SECONDS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("IDEMPOTENCY_TTL_SECONDS"))?;
+
+        let confirmation_check_interval_seconds =
+            std::env::var("CONFIRMATION_CHECK_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidNumber("CONFIRMATION_CHECK_INTERVAL_SECONDS"))?;
+
+        let pending_confirmation_age_seconds = std::env::var("PENDING_CONFIRMATION_AGE_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("PENDING_CONFIRMATION_AGE_SECONDS"))?;
+
+        let pending_stuck_alert_seconds = std::env::var("PENDING_STUCK_ALERT_SECONDS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("PENDING_STUCK_ALERT_SECONDS"))?;
+
+        let auto_lock_interval_seconds = std::env::var("AUTO_LOCK_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("AUTO_LOCK_INTERVAL_SECONDS"))?;
+
+        let auto_lock_authority = std::env::var("AUTO_LOCK_AUTHORITY")
+            .ok()
+            .map(|s| Pubkey::from_str(&s))
+            .transpose()
+            .map_err(|_| ConfigError::InvalidPubkey("AUTO_LOCK_AUTHORITY"))?;
+
+        let strict_deposit_verification = std::env::var("STRICT_DEPOSIT_VERIFICATION")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("STRICT_DEPOSIT_VERIFICATION"))?;
+
+        let daily_deposit_limit = std::env::var("DAILY_DEPOSIT_LIMIT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("DAILY_DEPOSIT_LIMIT"))?;
+
+        let daily_withdraw_limit = std::env::var("DAILY_WITHDRAW_LIMIT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("DAILY_WITHDRAW_LIMIT"))?;
+
+        let max_operation_amount = std::env::var("MAX_OPERATION_AMOUNT")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("MAX_OPERATION_AMOUNT"))?;
+
+        let allowed_mints = parse_list(&std::env::var("ALLOWED_MINTS").unwrap_or_default())
+            .iter()
+            .map(|mint| Pubkey::from_str(mint))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ConfigError::InvalidList("ALLOWED_MINTS"))?;
+
+        let cache_warm_vaults = std::env::var("CACHE_WARM_VAULTS")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("CACHE_WARM_VAULTS"))?;
+
+        let require_signed_requests = std::env::var("REQUIRE_SIGNED_REQUESTS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("REQUIRE_SIGNED_REQUESTS"))?;
+
+        let session_ttl_seconds = std::env::var("SESSION_TTL_SECONDS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidNumber("SESSION_TTL_SECONDS"))?;
+
+        let admin_api_key = std::env::var("ADMIN_API_KEY")
+            .ok()
+            .filter(|key| !key.is_empty());
+
+        let log_format = std::env::var("LOG_FORMAT")
+            .unwrap_or_else(|_| "pretty".to_string())
+            .parse()?;
+
+        let dev_mode = std::env::var("DEV_MODE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidBool("DEV_MODE"))?;
+
+        let cors_allowed_origins =
+            parse_cors_origins(&std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default())?;
+
+        let cors_allowed_methods = parse_list(
+            &std::env::var("CORS_ALLOWED_METHODS")
+                .unwrap_or_else(|_| "GET,POST,PUT,DELETE,OPTIONS".to_string()),
+        );
+        if cors_allowed_methods.iter().any(|m| Method::from_bytes(m.as_bytes()).is_err()) {
+            return Err(ConfigError::InvalidList("CORS_ALLOWED_METHODS"));
+        }
+
+        let cors_allowed_headers = parse_list(
+            &std::env::var("CORS_ALLOWED_HEADERS")
+                .unwrap_or_else(|_| DEFAULT_CORS_ALLOWED_HEADERS.to_string()),
+        );
+        if cors_allowed_headers.iter().any(|h| HeaderName::from_str(h).is_err()) {
+            return Err(ConfigError::InvalidList("CORS_ALLOWED_HEADERS"));
+        }
+
         Ok(Config {
             host,
             port,
             database_url,
             solana_rpc_url,
+            solana_commitment,
             program_id,
+            worker_threads,
             max_db_connections,
             cache_ttl_seconds,
+            tvl_cache_ttl_seconds,
+            cache_invalidation_url,
             reconciliation_interval_seconds,
+            reconciliation_batch_size,
+            reconciliation_concurrency,
             monitoring_interval_seconds,
+            low_balance_floor,
+            low_balance_percent,
+            low_balance_alert_cooldown_seconds,
+            alert_retention_days,
+            critical_alert_retention_days,
+            dormant_vault_days,
+            event_poll_interval_ms,
+            event_use_websocket,
+            event_max_retries,
+            heartbeat_missed_cycles,
+            ws_auth_required,
+            ws_resync_lagging_clients,
+            ws_max_clients,
+            ws_max_subs_per_client,
+            auto_repair,
+            tvl_sample_interval_minutes,
+            idempotency_ttl_seconds,
+            confirmation_check_interval_seconds,
+            pending_confirmation_age_seconds,
+            pending_stuck_alert_seconds,
+            auto_lock_interval_seconds,
+            auto_lock_authority,
+            strict_deposit_verification,
+            daily_deposit_limit,
+            daily_withdraw_limit,
+            max_operation_amount,
+            allowed_mints,
+            cache_warm_vaults,
+            require_signed_requests,
+            session_ttl_seconds,
+            admin_api_key,
+            log_format,
+            dev_mode,
+            cors_allowed_origins,
+            cors_allowed_methods,
+            cors_allowed_headers,
         })
     }
 }
 
+/// Log output format selected by `LOG_FORMAT`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Pretty,
+    /// One JSON object per line with structured fields
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(ConfigError::InvalidLogFormat(s.to_string())),
+        }
+    }
+}
+
+/// `CORS_ALLOWED_HEADERS` when unset
+pub const DEFAULT_CORS_ALLOWED_HEADERS: &str =
+    "Content-Type,Authorization,Idempotency-Key,X-Session-Id,X-Signature";
+
+/// `SOLANA_COMMITMENT` when unset
+pub const DEFAULT_SOLANA_COMMITMENT: &str = "confirmed";
+
+/// Parse `SOLANA_COMMITMENT`
+pub fn parse_commitment(value: &str) -> Result<CommitmentConfig, ConfigError> {
+    let commitment = value
+        .trim()
+        .to_ascii_lowercase()
+        .parse::<CommitmentLevel>()
+        .map_err(|_| ConfigError::InvalidCommitment(value.to_string()))?;
+    Ok(CommitmentConfig { commitment })
+}
+
+/// Parse `WORKER_THREADS`, resolving 0 to the number of CPUs
+pub fn parse_worker_threads(value: &str) -> Result<usize, ConfigError> {
+    match value.trim().parse() {
+        Ok(0) => Ok(num_cpus::get()),
+        Ok(threads) => Ok(threads),
+        Err(_) => Err(ConfigError::InvalidNumber("WORKER_THREADS")),
+    }
+}
+
+/// Split a comma-separated value, trimming entries and dropping empty ones
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parse `CORS_ALLOWED_ORIGINS`; a wildcard is rejected, use `DEV_MODE` instead
+pub fn parse_cors_origins(value: &str) -> Result<Vec<String>, ConfigError> {
+    let origins = parse_list(value);
+    if origins.iter().any(|o| o == "*" || Uri::from_str(o).is_err()) {
+        return Err(ConfigError::InvalidList("CORS_ALLOWED_ORIGINS"));
+    }
+    Ok(origins)
+}
+
 /// Configuration errors that can occur during loading
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -143,4 +653,24 @@ pub enum ConfigError {
     /// A numeric environment variable has an invalid value
     #[error("Invalid number for {0}")]
     InvalidNumber(&'static str),
+
+    /// A boolean environment variable has an invalid value
+    #[error("Invalid boolean for {0}")]
+    InvalidBool(&'static str),
+
+    /// A public key environment variable is not a valid Solana public key
+    #[error("Invalid public key for {0}")]
+    InvalidPubkey(&'static str),
+
+    /// `LOG_FORMAT` is neither `pretty` nor `json`
+    #[error("Invalid log format: {0} (expected pretty or json)")]
+    InvalidLogFormat(String),
+
+    /// `SOLANA_COMMITMENT` is not a known commitment level
+    #[error("Invalid commitment: {0} (expected processed, confirmed or finalized)")]
+    InvalidCommitment(String),
+
+    /// A comma-separated environment variable has an invalid entry
+    #[error("Invalid entry in {0}")]
+    InvalidList(&'static str),
 }
@@ -1,9 +1,107 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use shared::{
-    Alert, AuditTrailEntry, BalanceSnapshot, ReconciliationLog, TransactionRecord, TvlStats, Vault,
+    Alert, ApiSession, AuditChainVerification, AuditTrailEntry, AutoLockSettings,
+    BalanceSnapshot, DailyLimitSettings, FailedEvent, IdempotencyRecord, LowBalanceSettings,
+    ProtocolVolume, ReconciliationLog, ReconciliationRun, TransactionRecord, TvlHistoryPoint,
+    TvlInterval, TvlStats, Vault, VaultActivity, VaultActivityStats, VaultFreezeStatus,
+    VaultListParams, VaultPosition, Webhook,
 };
-use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use sqlx::postgres::{PgArguments, PgPoolOptions};
+use sqlx::query::QueryAs;
+use sqlx::{PgExecutor, PgPool, PgTransaction, Postgres, Row};
+use std::future::Future;
 use std::time::Duration;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Rows a query has produced so far, see [`row_stream`]
+pub type RowStream<T> = BoxStream<'static, Result<T, sqlx::Error>>;
+
+/// Rows buffered between a streaming query and its consumer
+const ROW_STREAM_BUFFER: usize = 64;
+
+/// Run `fetch` in a background task and hand its rows out as they arrive
+///
+/// The query borrows its SQL and bind values, so it runs in a task that owns
+/// them. Dropping the stream closes the channel and `fetch` stops early.
+fn row_stream<T, F, Fut>(fetch: F) -> RowStream<T>
+where
+    T: Send + 'static,
+    F: FnOnce(mpsc::Sender<Result<T, sqlx::Error>>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(ROW_STREAM_BUFFER);
+    tokio::spawn(fetch(tx));
+    stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|row| (row, rx)) }).boxed()
+}
+
+/// Send each row to `tx` until the rows or the receiver run out
+async fn forward_rows<T>(
+    mut rows: impl Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    tx: mpsc::Sender<Result<T, sqlx::Error>>,
+) {
+    while let Some(row) = rows.next().await {
+        if tx.send(row).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// `prev_hash` of the first entry in an audit chain
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Hex sha256 of `prev_hash` followed by the entry's contents as a JSON array
+///
+/// Covers everything but `id` and the hashes themselves.
+pub fn audit_entry_hash(prev_hash: &str, entry: &AuditTrailEntry) -> String {
+    let contents = serde_json::json!([
+        entry.event_type,
+        entry.vault_pubkey,
+        entry.user_pubkey,
+        entry.amount,
+        entry.tx_signature,
+        entry.event_data,
+        entry.ip_address,
+        entry.user_agent,
+        entry.created_at,
+    ]);
+
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(contents.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// New balance values for a single vault row
+#[derive(Debug, Clone)]
+pub struct BalanceUpdate<'a> {
+    pub vault_pubkey: &'a str,
+    pub total_balance: i64,
+    pub locked_balance: i64,
+    pub total_deposited: Option<i64>,
+    pub total_withdrawn: Option<i64>,
+}
+
+/// Transaction history row written alongside a [`BalanceUpdate`]
+#[derive(Debug, Clone)]
+pub struct TransactionEntry<'a> {
+    pub tx_signature: &'a str,
+    pub tx_type: &'a str,
+    pub amount: i64,
+    pub from_vault: Option<&'a str>,
+    pub to_vault: Option<&'a str>,
+    pub status: &'a str,
+}
+
+/// Keyset position in a newest-first transaction listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
 
 #[derive(Clone)]
 pub struct Database {
@@ -32,14 +130,18 @@ impl Database {
             r#"
         INSERT INTO vaults(
           vault_pubkey, owner_pubkey, token_account,
-          total_balance, locked_balance, total_deposited, total_withdrawn, created_at
-        ) VALUES ($1 , $2 , $3 , $4 , $5 ,$6 , $7 , $8)
+          total_balance, locked_balance, total_deposited, total_withdrawn, created_at,
+          min_deposit, max_balance, decimals, token_program, last_activity
+        ) VALUES ($1 , $2 , $3 , $4 , $5 ,$6 , $7 , $8 , $9 , $10, $11, $12, $13)
          ON CONFLICT (vault_pubkey)
          DO UPDATE SET
                 total_balance = EXCLUDED.total_balance,
                 locked_balance = EXCLUDED.locked_balance,
                 total_deposited = EXCLUDED.total_deposited,
                 total_withdrawn = EXCLUDED.total_withdrawn,
+                min_deposit = EXCLUDED.min_deposit,
+                max_balance = EXCLUDED.max_balance,
+                last_activity = GREATEST(vaults.last_activity, EXCLUDED.last_activity),
                 updated_at = NOW()     
       "#,
         )
@@ -51,11 +153,46 @@ impl Database {
         .bind(vault.total_deposited)
         .bind(vault.total_withdrawn)
         .bind(&vault.created_at)
+        .bind(vault.min_deposit)
+        .bind(vault.max_balance)
+        .bind(vault.decimals)
+        .bind(&vault.token_program)
+        .bind(vault.last_activity)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
+    /// Insert `vault` unless a row with its pubkey exists; returns whether it was inserted
+    pub async fn insert_vault_if_absent(&self, vault: &Vault) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+        INSERT INTO vaults(
+          vault_pubkey, owner_pubkey, token_account,
+          total_balance, locked_balance, total_deposited, total_withdrawn, created_at,
+          min_deposit, max_balance, decimals, token_program, last_activity
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (vault_pubkey) DO NOTHING
+      "#,
+        )
+        .bind(&vault.vault_pubkey)
+        .bind(&vault.owner_pubkey)
+        .bind(&vault.token_account)
+        .bind(vault.total_balance)
+        .bind(vault.locked_balance)
+        .bind(vault.total_deposited)
+        .bind(vault.total_withdrawn)
+        .bind(vault.created_at)
+        .bind(vault.min_deposit)
+        .bind(vault.max_balance)
+        .bind(vault.decimals)
+        .bind(&vault.token_program)
+        .bind(vault.last_activity)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
     pub async fn get_vault(&self, vault_pubkey: &str) -> Result<Option<Vault>, sqlx::Error> {
         let vault = sqlx::query_as::<_, Vault>(
             "
@@ -69,37 +206,397 @@ impl Database {
         Ok(vault)
     }
 
-    pub async fn get_vault_by_owner(
+    /// All vaults held by `owner_pubkey`, oldest first
+    pub async fn get_vaults_by_owner(&self, owner_pubkey: &str) -> Result<Vec<Vault>, sqlx::Error> {
+        let vaults = sqlx::query_as(
+            r#"
+            SELECT * FROM vaults
+            WHERE owner_pubkey = $1 AND status = 'active'
+            ORDER BY created_at, vault_pubkey
+            "#,
+        )
+        .bind(owner_pubkey)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(vaults)
+    }
+
+    /// Active vaults whose vault or owner pubkey starts with `prefix`
+    ///
+    /// `prefix` must not contain LIKE wildcards; callers pass base58 only.
+    pub async fn search_vaults(&self, prefix: &str, limit: i64) -> Result<Vec<Vault>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM vaults
+            WHERE status = 'active'
+              AND (vault_pubkey LIKE $1 || '%' OR owner_pubkey LIKE $1 || '%')
+            ORDER BY created_at, vault_pubkey
+            LIMIT $2
+            "#,
+        )
+        .bind(prefix)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Soft-delete a vault closed on-chain; it drops out of listings and TVL
+    ///
+    /// Returns false if the vault is unknown or already closed.
+    pub async fn mark_vault_closed(&self, vault_pubkey: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE vaults
+            SET status = 'closed', closed_at = NOW(), updated_at = NOW()
+            WHERE vault_pubkey = $1 AND status = 'active'
+            "#,
+        )
+        .bind(vault_pubkey)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_vault_owner(
         &self,
+        vault_pubkey: &str,
         owner_pubkey: &str,
-    ) -> Result<Option<Vault>, sqlx::Error> {
-        let vault = sqlx::query_as(" SELECT * FROM vaults WHERE owner_pubkey = $1")
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE vaults SET owner_pubkey = $1, updated_at = NOW() WHERE vault_pubkey = $2")
             .bind(owner_pubkey)
-            .fetch_optional(&self.pool)
+            .bind(vault_pubkey)
+            .execute(&self.pool)
             .await?;
 
-        Ok(vault)
+        Ok(())
     }
 
-    pub async fn get_all_vaults(&self, limit: i64, offset: i64) -> Result<Vec<Vault>, sqlx::Error> {
-        let vaults =
-            sqlx::query_as("SELECT * FROM vaults ORDER BY created_at DESC LIMIT $1 OFFSET $2")
-                .bind(limit)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await?;
+    pub async fn get_all_vaults(
+        &self,
+        params: &VaultListParams,
+    ) -> Result<Vec<Vault>, sqlx::Error> {
+        let query = Self::vault_list_sql(params);
+        Self::bind_vault_list(sqlx::query_as(&query), params)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// [`Database::get_all_vaults`] yielding rows as Postgres returns them
+    pub fn stream_all_vaults(&self, params: VaultListParams) -> RowStream<Vault> {
+        let pool = self.pool.clone();
+        row_stream(move |tx| async move {
+            let query = Self::vault_list_sql(&params);
+            let rows = Self::bind_vault_list(sqlx::query_as(&query), &params).fetch(&pool);
+            forward_rows(rows, tx).await;
+        })
+    }
+
+    fn vault_list_sql(params: &VaultListParams) -> String {
+        let mut query = "SELECT * FROM vaults WHERE status = 'active'".to_string();
+        let mut param_count = 0;
+
+        if params.owner.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" AND owner_pubkey = ${}", param_count));
+        }
+
+        if params.min_balance.is_some() {
+            param_count += 1;
+            query.push_str(&format!(" AND total_balance >= ${}", param_count));
+        }
+
+        if params.min_utilization.is_some() {
+            param_count += 1;
+            query.push_str(&format!(
+                " AND locked_balance::FLOAT8 * 100 / NULLIF(total_balance, 0) >= ${}",
+                param_count
+            ));
+        }
+
+        // Sort column and direction come from whitelisted enums, never from raw input
+        query.push_str(&format!(
+            " ORDER BY {} {}, vault_pubkey LIMIT ${} OFFSET ${}",
+            params.sort_by.column(),
+            params.order.as_sql(),
+            param_count + 1,
+            param_count + 2
+        ));
+
+        query
+    }
+
+    fn bind_vault_list<'q>(
+        mut q: QueryAs<'q, Postgres, Vault, PgArguments>,
+        params: &'q VaultListParams,
+    ) -> QueryAs<'q, Postgres, Vault, PgArguments> {
+        if let Some(owner) = &params.owner {
+            q = q.bind(owner);
+        }
+
+        if let Some(min_balance) = params.min_balance {
+            q = q.bind(min_balance);
+        }
+
+        if let Some(min_utilization) = params.min_utilization {
+            q = q.bind(min_utilization);
+        }
+
+        q.bind(params.limit).bind(params.offset)
+    }
+
+    /// Most recently updated vaults first, for cache warming
+    pub async fn get_recently_active_vaults(&self, limit: i64) -> Result<Vec<Vault>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM vaults WHERE status = 'active' ORDER BY updated_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_top_utilized_vaults(&self, limit: i64) -> Result<Vec<Vault>, sqlx::Error> {
+        let vaults = sqlx::query_as(
+            r#"
+            SELECT * FROM vaults
+            WHERE total_balance > 0 AND status = 'active'
+            ORDER BY locked_balance::FLOAT8 / NULLIF(total_balance, 0) DESC NULLS LAST
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(vaults)
     }
 
+    /// Round-trip `SELECT 1` to confirm the pool can reach Postgres
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
     pub async fn get_vault_count(&self) -> Result<i64, sqlx::Error> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM vaults")
+        let row = sqlx::query("SELECT COUNT(*) as count FROM vaults WHERE status = 'active'")
             .fetch_one(&self.pool)
             .await?;
 
         Ok(row.get("count"))
     }
 
+    /// Active vaults idle since before `idle_since`, with their `last_activity`, oldest first
+    ///
+    /// Vaults that already got a `dormant_vault` alert after `idle_since` are left out, so
+    /// each is flagged at most once per dormancy window.
+    pub async fn get_dormant_vaults(
+        &self,
+        idle_since: DateTime<Utc>,
+    ) -> Result<Vec<(String, DateTime<Utc>)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT v.vault_pubkey, v.last_activity FROM vaults v
+            WHERE v.status = 'active' AND v.last_activity < $1
+              AND NOT EXISTS (
+                SELECT 1 FROM alerts a
+                WHERE a.vault_pubkey = v.vault_pubkey
+                  AND a.alert_type = 'dormant_vault'
+                  AND a.created_at > $1
+              )
+            ORDER BY v.last_activity
+            "#,
+        )
+        .bind(idle_since)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_auto_lock_settings(
+        &self,
+        vault_pubkey: &str,
+    ) -> Result<Option<AutoLockSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT vault_pubkey, auto_lock_enabled, auto_lock_buffer FROM vaults
+            WHERE vault_pubkey = $1
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Returns `None` when the vault does not exist
+    pub async fn set_auto_lock_settings(
+        &self,
+        vault_pubkey: &str,
+        enabled: bool,
+        buffer: i64,
+    ) -> Result<Option<AutoLockSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE vaults
+            SET auto_lock_enabled = $2, auto_lock_buffer = $3, updated_at = NOW()
+            WHERE vault_pubkey = $1
+            RETURNING vault_pubkey, auto_lock_enabled, auto_lock_buffer
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(enabled)
+        .bind(buffer)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// `None` when the vault does not exist
+    pub async fn is_vault_frozen(&self, vault_pubkey: &str) -> Result<Option<bool>, sqlx::Error> {
+        let row = sqlx::query("SELECT frozen FROM vaults WHERE vault_pubkey = $1")
+            .bind(vault_pubkey)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("frozen")))
+    }
+
+    /// Returns `None` when the vault does not exist
+    pub async fn set_vault_frozen(
+        &self,
+        vault_pubkey: &str,
+        frozen: bool,
+    ) -> Result<Option<VaultFreezeStatus>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE vaults
+            SET frozen = $2, updated_at = NOW()
+            WHERE vault_pubkey = $1
+            RETURNING vault_pubkey, frozen
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(frozen)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_daily_limit_settings(
+        &self,
+        vault_pubkey: &str,
+    ) -> Result<Option<DailyLimitSettings>, sqlx::Error> {
+        Self::get_daily_limit_settings_in(&self.pool, vault_pubkey).await
+    }
+
+    /// `get_daily_limit_settings` against any executor, e.g. an open transaction
+    pub async fn get_daily_limit_settings_in<'e, E: PgExecutor<'e>>(
+        executor: E,
+        vault_pubkey: &str,
+    ) -> Result<Option<DailyLimitSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT vault_pubkey, daily_deposit_limit, daily_withdraw_limit FROM vaults
+            WHERE vault_pubkey = $1
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Returns `None` when the vault does not exist
+    pub async fn set_daily_limit_settings(
+        &self,
+        vault_pubkey: &str,
+        daily_deposit_limit: i64,
+        daily_withdraw_limit: i64,
+    ) -> Result<Option<DailyLimitSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE vaults
+            SET daily_deposit_limit = $2, daily_withdraw_limit = $3, updated_at = NOW()
+            WHERE vault_pubkey = $1
+            RETURNING vault_pubkey, daily_deposit_limit, daily_withdraw_limit
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(daily_deposit_limit)
+        .bind(daily_withdraw_limit)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_low_balance_settings(
+        &self,
+        vault_pubkey: &str,
+    ) -> Result<Option<LowBalanceSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT vault_pubkey, low_balance_floor, low_balance_percent FROM vaults
+            WHERE vault_pubkey = $1
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Returns `None` when the vault does not exist
+    pub async fn set_low_balance_settings(
+        &self,
+        vault_pubkey: &str,
+        low_balance_floor: i64,
+        low_balance_percent: f64,
+    ) -> Result<Option<LowBalanceSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE vaults
+            SET low_balance_floor = $2, low_balance_percent = $3, updated_at = NOW()
+            WHERE vault_pubkey = $1
+            RETURNING vault_pubkey, low_balance_floor, low_balance_percent
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(low_balance_floor)
+        .bind(low_balance_percent)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Total `tx_type` amount recorded for a vault after `since`, excluding failed transactions
+    pub async fn get_volume_since_in(
+        tx: &mut PgTransaction<'_>,
+        vault_pubkey: &str,
+        tx_type: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)::BIGINT AS volume FROM transactions
+            WHERE vault_pubkey = $1 AND tx_type = $2 AND status <> 'failed' AND created_at > $3
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(tx_type)
+        .bind(since)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(row.get("volume"))
+    }
+
+    /// Opted-in vaults whose available balance is above their buffer
+    pub async fn get_auto_lock_candidates(&self) -> Result<Vec<AutoLockSettings>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT vault_pubkey, auto_lock_enabled, auto_lock_buffer FROM vaults
+            WHERE auto_lock_enabled AND available_balance > auto_lock_buffer
+              AND status = 'active'
+            ORDER BY vault_pubkey
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
 pub async fn update_vault_balances(
     &self,
     vault_pubkey: &str,
@@ -107,6 +604,26 @@ pub async fn update_vault_balances(
     locked_balance: i64,
     total_deposited: Option<i64>,
     total_withdrawn: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    Self::update_vault_balances_in(
+        &self.pool,
+        vault_pubkey,
+        total_balance,
+        locked_balance,
+        total_deposited,
+        total_withdrawn,
+    )
+    .await
+}
+
+/// `update_vault_balances` against any executor, e.g. an open transaction
+pub async fn update_vault_balances_in<'e, E: PgExecutor<'e>>(
+    executor: E,
+    vault_pubkey: &str,
+    total_balance: i64,
+    locked_balance: i64,
+    total_deposited: Option<i64>,
+    total_withdrawn: Option<i64>,
 ) -> Result<(), sqlx::Error> {
     // NOTE: Do NOT update available_balance - it's a GENERATED column!
     // PostgreSQL automatically computes: available_balance = total_balance - locked_balance
@@ -125,7 +642,7 @@ pub async fn update_vault_balances(
     }
 
     query.push_str(&format!(
-        ", updated_at = NOW() WHERE vault_pubkey = ${}",
+        ", updated_at = NOW(), last_activity = NOW() WHERE vault_pubkey = ${}",
         param_count
     ));
 
@@ -143,7 +660,7 @@ pub async fn update_vault_balances(
     }
 
     q = q.bind(vault_pubkey); // Last param
-    q.execute(&self.pool).await?;
+    q.execute(executor).await?;
 
     Ok(())
 }
@@ -156,41 +673,160 @@ pub async fn update_vault_balances(
         from_vault: Option<&str>,
         to_vault: Option<&str>,
         status: &str,
+    ) -> Result<(), sqlx::Error> {
+        Self::record_transaction_in(
+            &self.pool,
+            vault_pubkey,
+            tx_signature,
+            tx_type,
+            amount,
+            from_vault,
+            to_vault,
+            status,
+        )
+        .await
+    }
+
+    /// `record_transaction` against any executor, e.g. an open transaction
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_transaction_in<'e, E: PgExecutor<'e>>(
+        executor: E,
+        vault_pubkey: &str,
+        tx_signature: &str,
+        tx_type: &str,
+        amount: i64,
+        from_vault: Option<&str>,
+        to_vault: Option<&str>,
+        status: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO transactions (
+                vault_pubkey, tx_signature, tx_type, amount,
+                from_vault, to_vault, status
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (tx_signature) DO NOTHING
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(tx_signature)
+        .bind(tx_type)
+        .bind(amount)
+        .bind(from_vault)
+        .bind(to_vault)
+        .bind(status)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Merge `meta`'s keys into a transaction's `meta` object
+    pub async fn merge_transaction_meta(
+        &self,
+        tx_signature: &str,
+        meta: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE transactions
+            SET meta = COALESCE(meta, '{}'::jsonb) || $2
+            WHERE tx_signature = $1
+            "#,
+        )
+        .bind(tx_signature)
+        .bind(meta)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_transaction_status(
+        &self,
+        tx_signature: &str,
+        status: &str,
+        block_time: Option<i64>,
+        slot: Option<i64>,
     ) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            INSERT INTO transactions (
-                vault_pubkey, tx_signature, tx_type, amount,
-                from_vault, to_vault, status
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
-            ON CONFLICT (tx_signature) DO NOTHING
+            UPDATE transactions 
+            SET status = $1, block_time = COALESCE($2, block_time), slot = COALESCE($3, slot),
+                confirmed_at = NOW()
+            WHERE tx_signature = $4
+            "#,
+        )
+        .bind(status)
+        .bind(block_time)
+        .bind(slot)
+        .bind(tx_signature)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `pending` transactions created before `older_than`, oldest first
+    ///
+    /// Pass the last row of the previous page as `after` to continue from it.
+    pub async fn get_stale_pending_transactions(
+        &self,
+        older_than: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, &str)>,
+        limit: i64,
+    ) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        let (after_created_at, after_signature) = after.unzip();
+        sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT * FROM transactions
+            WHERE status = 'pending' AND created_at < $1
+              AND ($2::timestamptz IS NULL OR (created_at, tx_signature) > ($2, $3))
+            ORDER BY created_at ASC, tx_signature ASC
+            LIMIT $4
+            "#,
+        )
+        .bind(older_than)
+        .bind(after_created_at)
+        .bind(after_signature)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Whether an `alert_type` alert was already raised for `tx_signature`
+    pub async fn has_transaction_alert(
+        &self,
+        tx_signature: &str,
+        alert_type: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM alerts
+                WHERE alert_type = $2 AND details->>'tx_signature' = $1
+            )
             "#,
         )
-        .bind(vault_pubkey)
         .bind(tx_signature)
-        .bind(tx_type)
-        .bind(amount)
-        .bind(from_vault)
-        .bind(to_vault)
-        .bind(status)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        .bind(alert_type)
+        .fetch_one(&self.pool)
+        .await
     }
 
-    pub async fn update_transaction_status(
+    /// Settle a `pending` transaction; returns `false` if it was no longer pending
+    pub async fn resolve_pending_transaction(
         &self,
         tx_signature: &str,
         status: &str,
         block_time: Option<i64>,
         slot: Option<i64>,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
             r#"
-            UPDATE transactions 
+            UPDATE transactions
             SET status = $1, block_time = $2, slot = $3, confirmed_at = NOW()
-            WHERE tx_signature = $4
+            WHERE tx_signature = $4 AND status = 'pending'
             "#,
         )
         .bind(status)
@@ -200,36 +836,100 @@ pub async fn update_vault_balances(
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
+    /// Transactions newest first
+    ///
+    /// With `after`, only rows strictly older than the cursor are returned, so pages stay
+    /// stable while new rows arrive; otherwise `offset` rows are skipped.
     pub async fn get_transactions(
         &self,
         vault_pubkey: Option<&str>,
         tx_type: Option<&str>,
+        after: Option<&TransactionCursor>,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        let query =
+            Self::transactions_sql(vault_pubkey.is_some(), tx_type.is_some(), after.is_some());
+        Self::bind_transactions(
+            sqlx::query_as(&query),
+            vault_pubkey,
+            tx_type,
+            after,
+            limit,
+            offset,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// [`Database::get_transactions`] yielding rows as Postgres returns them
+    pub fn stream_transactions(
+        &self,
+        vault_pubkey: Option<String>,
+        tx_type: Option<String>,
+        after: Option<TransactionCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> RowStream<TransactionRecord> {
+        let pool = self.pool.clone();
+        row_stream(move |tx| async move {
+            let query =
+                Self::transactions_sql(vault_pubkey.is_some(), tx_type.is_some(), after.is_some());
+            let rows = Self::bind_transactions(
+                sqlx::query_as(&query),
+                vault_pubkey.as_deref(),
+                tx_type.as_deref(),
+                after.as_ref(),
+                limit,
+                offset,
+            )
+            .fetch(&pool);
+            forward_rows(rows, tx).await;
+        })
+    }
+
+    fn transactions_sql(by_vault: bool, by_type: bool, after: bool) -> String {
         let mut query = "SELECT id, vault_pubkey, tx_signature, tx_type, amount, status, created_at FROM transactions WHERE 1=1".to_string();
         let mut param_count = 0;
 
-        if vault_pubkey.is_some() {
+        if by_vault {
             param_count += 1;
             query.push_str(&format!(" AND vault_pubkey = ${}", param_count));
         }
 
-        if tx_type.is_some() {
+        if by_type {
             param_count += 1;
             query.push_str(&format!(" AND tx_type = ${}", param_count));
         }
 
+        if after {
+            query.push_str(&format!(
+                " AND (created_at, id) < (${}, ${})",
+                param_count + 1,
+                param_count + 2
+            ));
+            param_count += 2;
+        }
+
         param_count += 1;
-        query.push_str(&format!(" ORDER BY created_at DESC LIMIT ${}", param_count));
+        query.push_str(&format!(" ORDER BY created_at DESC, id DESC LIMIT ${}", param_count));
         param_count += 1;
         query.push_str(&format!(" OFFSET ${}", param_count));
 
-        let mut q = sqlx::query_as::<_, TransactionRecord>(&query);
+        query
+    }
 
+    fn bind_transactions<'q>(
+        mut q: QueryAs<'q, Postgres, TransactionRecord, PgArguments>,
+        vault_pubkey: Option<&'q str>,
+        tx_type: Option<&'q str>,
+        after: Option<&TransactionCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> QueryAs<'q, Postgres, TransactionRecord, PgArguments> {
         if let Some(vault) = vault_pubkey {
             q = q.bind(vault);
         }
@@ -238,32 +938,212 @@ pub async fn update_vault_balances(
             q = q.bind(tx_type_val);
         }
 
-        q = q.bind(limit);
-        q = q.bind(offset);
+        if let Some(cursor) = after {
+            q = q.bind(cursor.created_at).bind(cursor.id);
+        }
 
-        q.fetch_all(&self.pool).await
+        q = q.bind(limit);
+        q.bind(if after.is_some() { 0 } else { offset })
     }
 
+    /// A vault's transactions, newest first, optionally narrowed by type and status
+    ///
+    /// Paged like [`Database::get_transactions`].
     pub async fn get_vault_transactions(
         &self,
         vault_pubkey: &str,
+        tx_type: Option<&str>,
+        status: Option<&str>,
+        after: Option<&TransactionCursor>,
         limit: i64,
+        offset: i64,
     ) -> Result<Vec<TransactionRecord>, sqlx::Error> {
-        let transactions = sqlx::query_as::<_, TransactionRecord>(
-            r#"
-            SELECT * FROM transactions 
-            WHERE vault_pubkey = $1 
-            ORDER BY created_at DESC 
-            LIMIT $2
-            "#,
+        let query =
+            Self::vault_transactions_sql(tx_type.is_some(), status.is_some(), after.is_some());
+        Self::bind_vault_transactions(
+            sqlx::query_as(&query),
+            vault_pubkey,
+            tx_type,
+            status,
+            after,
+            limit,
+            offset,
         )
-        .bind(vault_pubkey)
-        .bind(limit)
         .fetch_all(&self.pool)
+        .await
+    }
+
+    /// [`Database::get_vault_transactions`] yielding rows as Postgres returns them
+    pub fn stream_vault_transactions(
+        &self,
+        vault_pubkey: String,
+        tx_type: Option<String>,
+        status: Option<String>,
+        after: Option<TransactionCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> RowStream<TransactionRecord> {
+        let pool = self.pool.clone();
+        row_stream(move |tx| async move {
+            let query =
+                Self::vault_transactions_sql(tx_type.is_some(), status.is_some(), after.is_some());
+            let rows = Self::bind_vault_transactions(
+                sqlx::query_as(&query),
+                &vault_pubkey,
+                tx_type.as_deref(),
+                status.as_deref(),
+                after.as_ref(),
+                limit,
+                offset,
+            )
+            .fetch(&pool);
+            forward_rows(rows, tx).await;
+        })
+    }
+
+    fn vault_transactions_sql(by_type: bool, by_status: bool, after: bool) -> String {
+        let mut query = "SELECT * FROM transactions WHERE vault_pubkey = $1".to_string();
+        let mut param_count = 1;
+
+        if by_type {
+            param_count += 1;
+            query.push_str(&format!(" AND tx_type = ${}", param_count));
+        }
+
+        if by_status {
+            param_count += 1;
+            query.push_str(&format!(" AND status = ${}", param_count));
+        }
+
+        if after {
+            query.push_str(&format!(
+                " AND (created_at, id) < (${}, ${})",
+                param_count + 1,
+                param_count + 2
+            ));
+            param_count += 2;
+        }
+
+        query.push_str(&format!(
+            " ORDER BY created_at DESC, id DESC LIMIT ${} OFFSET ${}",
+            param_count + 1,
+            param_count + 2
+        ));
+
+        query
+    }
+
+    fn bind_vault_transactions<'q>(
+        q: QueryAs<'q, Postgres, TransactionRecord, PgArguments>,
+        vault_pubkey: &'q str,
+        tx_type: Option<&'q str>,
+        status: Option<&'q str>,
+        after: Option<&TransactionCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> QueryAs<'q, Postgres, TransactionRecord, PgArguments> {
+        let mut q = q.bind(vault_pubkey);
+
+        if let Some(tx_type_val) = tx_type {
+            q = q.bind(tx_type_val);
+        }
+
+        if let Some(status_val) = status {
+            q = q.bind(status_val);
+        }
+
+        if let Some(cursor) = after {
+            q = q.bind(cursor.created_at).bind(cursor.id);
+        }
+
+        q.bind(limit).bind(if after.is_some() { 0 } else { offset })
+    }
+    /// Update a vault's balances and record the transaction atomically
+    ///
+    /// Both writes run inside one `BEGIN`/`COMMIT`; if either fails the
+    /// transaction is rolled back and the vault is left unchanged.
+    pub async fn apply_balance_change(
+        &self,
+        update: &BalanceUpdate<'_>,
+        entry: &TransactionEntry<'_>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        Self::apply_balance_change_in(&mut tx, update, entry).await?;
+        tx.commit().await
+    }
+
+    /// `apply_balance_change` inside a transaction the caller commits
+    pub async fn apply_balance_change_in(
+        tx: &mut PgTransaction<'_>,
+        update: &BalanceUpdate<'_>,
+        entry: &TransactionEntry<'_>,
+    ) -> Result<(), sqlx::Error> {
+        Self::update_vault_balances_in(
+            &mut **tx,
+            update.vault_pubkey,
+            update.total_balance,
+            update.locked_balance,
+            update.total_deposited,
+            update.total_withdrawn,
+        )
         .await?;
 
-        Ok(transactions)
+        Self::record_transaction_in(
+            &mut **tx,
+            update.vault_pubkey,
+            entry.tx_signature,
+            entry.tx_type,
+            entry.amount,
+            entry.from_vault,
+            entry.to_vault,
+            entry.status,
+        )
+        .await
+    }
+
+    /// Start a database transaction
+    pub async fn begin(&self) -> Result<PgTransaction<'static>, sqlx::Error> {
+        self.pool.begin().await
+    }
+
+    /// Serialize concurrent processing of the same signature until the transaction ends
+    pub async fn lock_tx_signature(
+        tx: &mut PgTransaction<'_>,
+        tx_signature: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(tx_signature)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Load a vault and lock its row until the transaction ends
+    pub async fn get_vault_for_update(
+        tx: &mut PgTransaction<'_>,
+        vault_pubkey: &str,
+    ) -> Result<Option<Vault>, sqlx::Error> {
+        sqlx::query_as::<_, Vault>("SELECT * FROM vaults WHERE vault_pubkey = $1 FOR UPDATE")
+            .bind(vault_pubkey)
+            .fetch_optional(&mut **tx)
+            .await
+    }
+
+    /// `get_transaction_by_signature` inside an open transaction
+    pub async fn get_transaction_by_signature_in(
+        tx: &mut PgTransaction<'_>,
+        tx_signature: &str,
+    ) -> Result<Option<TransactionRecord>, sqlx::Error> {
+        sqlx::query_as!(
+            TransactionRecord,
+            "SELECT * FROM transactions WHERE tx_signature = $1",
+            tx_signature
+        )
+        .fetch_optional(&mut **tx)
+        .await
     }
+
     pub async fn get_transaction_by_signature(
         &self,
         tx_signature: &str,
@@ -325,6 +1205,53 @@ pub async fn update_vault_balances(
         Ok(snapshot)
     }
 
+    /// Latest snapshot taken at or before `at`
+    pub async fn get_snapshot_at(
+        &self,
+        vault_pubkey: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<BalanceSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, BalanceSnapshot>(
+            r#"
+            SELECT * FROM balance_snapshots
+            WHERE vault_pubkey = $1 AND snapshot_ts <= $2
+            ORDER BY snapshot_ts DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Non-failed transactions touching a vault in `(after, before)`, oldest first
+    ///
+    /// Includes transfers into the vault, which are recorded under the sender.
+    /// With no `after` the window is open-ended, back to the vault's first transaction.
+    pub async fn get_balance_changes(
+        &self,
+        vault_pubkey: &str,
+        after: Option<DateTime<Utc>>,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<TransactionRecord>, sqlx::Error> {
+        sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT * FROM transactions
+            WHERE (vault_pubkey = $1 OR to_vault = $1)
+              AND status <> 'failed'
+              AND ($2::TIMESTAMPTZ IS NULL OR created_at > $2)
+              AND created_at < $3
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(after)
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await
+    }
+
     pub async fn log_reconciliation_issue(
         &self,
         vault_pubkey: &str,
@@ -384,11 +1311,220 @@ pub async fn update_vault_balances(
             LIMIT $1
             "#,
         )
-        .bind(limit)
-        .fetch_all(&self.pool)
-        .await?;
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+
+    /// Dead-letter an event the listener could not apply
+    pub async fn record_failed_event(
+        &self,
+        tx_signature: &str,
+        event_type: &str,
+        event_data: &[u8],
+        error: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let rec = sqlx::query(
+            r#"
+            INSERT INTO failed_events (tx_signature, event_type, event_data, error)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(tx_signature)
+        .bind(event_type)
+        .bind(event_data)
+        .bind(error)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(rec.get("id"))
+    }
+
+    /// Newest signature the event listener has handled for `program_id`
+    pub async fn get_listener_cursor(
+        &self,
+        program_id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("SELECT last_signature FROM listener_cursors WHERE program_id = $1")
+            .bind(program_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("last_signature")))
+    }
+
+    pub async fn set_listener_cursor(
+        &self,
+        program_id: &str,
+        last_signature: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO listener_cursors (program_id, last_signature)
+            VALUES ($1, $2)
+            ON CONFLICT (program_id)
+            DO UPDATE SET last_signature = EXCLUDED.last_signature, updated_at = NOW()
+            "#,
+        )
+        .bind(program_id)
+        .bind(last_signature)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Dead-lettered events still awaiting a retry, oldest first
+    pub async fn get_pending_failed_events(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<FailedEvent>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM failed_events
+            WHERE status = 'pending'
+            ORDER BY created_at, id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn resolve_failed_event(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE failed_events SET status = 'resolved', updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Count another failed retry; `abandon` stops further retries
+    pub async fn record_failed_event_retry(
+        &self,
+        id: i64,
+        error: &str,
+        abandon: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE failed_events
+            SET retry_count = retry_count + 1,
+                error = $2,
+                status = CASE WHEN $3 THEN 'abandoned' ELSE status END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(abandon)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_webhook(
+        &self,
+        url: &str,
+        secret: &str,
+        event_types: &[String],
+        vault_pubkey: Option<&str>,
+    ) -> Result<Webhook, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO webhooks (url, secret, event_types, vault_pubkey)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(url)
+        .bind(secret)
+        .bind(event_types)
+        .bind(vault_pubkey)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_webhook(&self, id: i64) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM webhooks WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn list_webhooks(&self) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM webhooks ORDER BY id")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Active webhooks subscribed to `event_type` on `vault_pubkey`
+    pub async fn get_webhooks_for_event(
+        &self,
+        event_type: &str,
+        vault_pubkey: &str,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM webhooks
+            WHERE active
+              AND (cardinality(event_types) = 0 OR $1 = ANY(event_types))
+              AND (vault_pubkey IS NULL OR vault_pubkey = $2)
+            ORDER BY id
+            "#,
+        )
+        .bind(event_type)
+        .bind(vault_pubkey)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Apply the `Some` fields; returns `None` if the webhook doesn't exist
+    pub async fn update_webhook(
+        &self,
+        id: i64,
+        url: Option<&str>,
+        secret: Option<&str>,
+        event_types: Option<&[String]>,
+        active: Option<bool>,
+    ) -> Result<Option<Webhook>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE webhooks
+            SET url = COALESCE($2, url),
+                secret = COALESCE($3, secret),
+                event_types = COALESCE($4, event_types),
+                active = COALESCE($5, active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(url)
+        .bind(secret)
+        .bind(event_types)
+        .bind(active)
+        .fetch_optional(&self.pool)
+        .await
+    }
 
-        Ok(logs)
+    pub async fn delete_webhook(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn create_alert(
@@ -432,6 +1568,52 @@ pub async fn update_vault_balances(
 
         Ok(alerts)
     }
+    /// Whether an `alert_type` alert was raised for the vault after `since`
+    pub async fn has_recent_alert(
+        &self,
+        vault_pubkey: &str,
+        alert_type: &str,
+        since: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM alerts
+                WHERE vault_pubkey = $1 AND alert_type = $2 AND created_at > $3
+            )
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(alert_type)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Delete resolved and acknowledged alerts created before `before`, returning how many
+    ///
+    /// Active alerts are never pruned. With `keep_critical` set, critical alerts are kept too.
+    pub async fn prune_alerts(
+        &self,
+        before: DateTime<Utc>,
+        keep_critical: bool,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM alerts
+            WHERE status IN ('resolved', 'acknowledged')
+              AND created_at < $1
+              AND NOT ($2 AND severity = 'critical')
+            "#,
+        )
+        .bind(before)
+        .bind(keep_critical)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn acknowledge_alert(&self, alert_id: i64) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
@@ -462,6 +1644,13 @@ pub async fn update_vault_balances(
         Ok(())
     }
 
+    /// Append an entry to the audit trail's hash chain for `vault_pubkey`
+    ///
+    /// Each vault's entries, and the entries with no vault, form their own chain:
+    /// `prev_hash` is the previous entry's `entry_hash` ([`AUDIT_GENESIS_HASH`] for the
+    /// first) and `entry_hash` is [`audit_entry_hash`] of the row as stored. Appends to
+    /// one chain are serialized by an advisory lock. Rows written by the `vaults` audit
+    /// trigger carry no hash and sit outside the chain.
     pub async fn create_audit_entry(
         &self,
         event_type: &str,
@@ -473,13 +1662,32 @@ pub async fn update_vault_balances(
         ip_address: Option<&str>,
         user_agent: Option<&str>,
     ) -> Result<i64, sqlx::Error> {
-        let entry = sqlx::query(
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(format!("audit:{}", vault_pubkey.unwrap_or_default()))
+            .execute(&mut *tx)
+            .await?;
+
+        let prev_hash: String = sqlx::query_scalar(
+            r#"
+            SELECT entry_hash FROM audit_trail
+            WHERE vault_pubkey IS NOT DISTINCT FROM $1 AND entry_hash IS NOT NULL
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch_optional(&mut *tx)
+        .await?
+        .unwrap_or_else(|| AUDIT_GENESIS_HASH.to_string());
+
+        let entry = sqlx::query_as::<_, AuditTrailEntry>(
             r#"
             INSERT INTO audit_trail (
                 event_type, vault_pubkey, user_pubkey, amount, 
                 tx_signature, event_data, ip_address, user_agent
             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id
+            RETURNING *
             "#,
         )
         .bind(event_type)
@@ -490,10 +1698,73 @@ pub async fn update_vault_balances(
         .bind(event_data)
         .bind(ip_address)
         .bind(user_agent)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        Ok(entry.get("id"))
+        // Hash the row as Postgres stored it, so `event_data` and `created_at`
+        // serialize the same way when the chain is verified
+        let entry_hash = audit_entry_hash(&prev_hash, &entry);
+        sqlx::query("UPDATE audit_trail SET prev_hash = $2, entry_hash = $3 WHERE id = $1")
+            .bind(entry.id)
+            .bind(&prev_hash)
+            .bind(&entry_hash)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(entry.id)
+    }
+
+    /// Walk `vault_pubkey`'s audit chain in order, stopping at the first broken link
+    ///
+    /// An entry is broken if its `prev_hash` isn't the previous entry's `entry_hash`
+    /// (an entry was removed, reordered or relinked) or its `entry_hash` doesn't match
+    /// its contents (the entry was edited).
+    pub async fn verify_audit_chain(
+        &self,
+        vault_pubkey: &str,
+    ) -> Result<AuditChainVerification, sqlx::Error> {
+        let mut entries = sqlx::query_as::<_, AuditTrailEntry>(
+            r#"
+            SELECT * FROM audit_trail
+            WHERE vault_pubkey = $1 AND entry_hash IS NOT NULL
+            ORDER BY id
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch(&self.pool);
+
+        let mut report = AuditChainVerification {
+            vault_pubkey: vault_pubkey.to_string(),
+            entries_checked: 0,
+            valid: true,
+            broken_entry_id: None,
+            reason: None,
+        };
+        let mut expected_prev = AUDIT_GENESIS_HASH.to_string();
+
+        while let Some(entry) = entries.next().await.transpose()? {
+            report.entries_checked += 1;
+            let entry_hash = audit_entry_hash(&expected_prev, &entry);
+
+            let reason = if entry.prev_hash.as_deref() != Some(expected_prev.as_str()) {
+                Some("prev_hash does not match the previous entry")
+            } else if entry.entry_hash.as_deref() != Some(entry_hash.as_str()) {
+                Some("entry_hash does not match the entry's contents")
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                report.valid = false;
+                report.broken_entry_id = Some(entry.id);
+                report.reason = Some(reason.to_string());
+                break;
+            }
+            expected_prev = entry_hash;
+        }
+
+        Ok(report)
     }
 
     pub async fn get_vault_audit_trail(
@@ -528,6 +1799,7 @@ pub async fn update_vault_balances(
             COALESCE(AVG(total_balance), 0)::FLOAT8 AS avg_vault_balance,
             COALESCE(MAX(total_balance)::BIGINT, 0) AS max_vault_balance
         FROM vaults
+        WHERE status = 'active'
         "#
     )
     .fetch_one(&self.pool)
@@ -543,6 +1815,417 @@ pub async fn update_vault_balances(
         timestamp: Utc::now(),
     })
 }
+
+    /// Funded-vault count and mean utilization over active vaults
+    pub async fn get_vault_activity_stats(&self) -> Result<VaultActivityStats, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE total_balance > 0)::BIGINT AS funded_vaults,
+                COALESCE(
+                    AVG(locked_balance::FLOAT8 / total_balance) FILTER (WHERE total_balance > 0),
+                    0
+                )::FLOAT8 * 100 AS avg_utilization_pct
+            FROM vaults
+            WHERE status = 'active'
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_top_vaults_by_balance(&self, limit: i64) -> Result<Vec<Vault>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM vaults
+            WHERE status = 'active'
+            ORDER BY total_balance DESC, vault_pubkey
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_protocol_volume(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<ProtocolVolume, sqlx::Error> {
+        Self::get_protocol_volume_in(&self.pool, since, until).await
+    }
+
+    /// Deposits and withdrawals across all vaults in `[since, until)`, excluding failed transactions
+    pub async fn get_protocol_volume_in<'e, E: PgExecutor<'e>>(
+        executor: E,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<ProtocolVolume, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(SUM(amount) FILTER (WHERE tx_type = 'deposit'), 0)::BIGINT AS deposit_volume,
+                COALESCE(SUM(amount) FILTER (WHERE tx_type = 'withdraw'), 0)::BIGINT AS withdraw_volume
+            FROM transactions
+            WHERE status <> 'failed' AND created_at >= $1 AND created_at < $2
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Claim `key` for a new request, or return the record already holding it
+    ///
+    /// Records older than `ttl_seconds` are discarded first so the key can be reused.
+    pub async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        request_hash: &str,
+        ttl_seconds: i64,
+    ) -> Result<Option<IdempotencyRecord>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM idempotency_keys WHERE idempotency_key = $1 AND created_at < NOW() - make_interval(secs => $2)",
+        )
+        .bind(key)
+        .bind(ttl_seconds as f64)
+        .execute(&mut *tx)
+        .await?;
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (idempotency_key, request_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (idempotency_key) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(request_hash)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        let existing = if claimed {
+            None
+        } else {
+            sqlx::query_as::<_, IdempotencyRecord>(
+                "SELECT * FROM idempotency_keys WHERE idempotency_key = $1",
+            )
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?
+        };
+
+        tx.commit().await?;
+        Ok(existing)
+    }
+
+    /// Store the response for a claimed key so repeats can replay it
+    pub async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        status_code: i32,
+        response: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE idempotency_keys SET status_code = $1, response = $2 WHERE idempotency_key = $3",
+        )
+        .bind(status_code)
+        .bind(response)
+        .bind(key)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop a claim whose request failed, so the client may retry with the same key
+    pub async fn release_idempotency_key(&self, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE idempotency_key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store a new `/auth` session for `owner_pubkey` lasting `ttl_seconds`
+    pub async fn create_session(
+        &self,
+        owner_pubkey: &str,
+        secret: &str,
+        ttl_seconds: i64,
+    ) -> Result<ApiSession, sqlx::Error> {
+        sqlx::query_as::<_, ApiSession>(
+            r#"
+            INSERT INTO api_sessions (session_id, owner_pubkey, secret, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(owner_pubkey)
+        .bind(secret)
+        .bind(ttl_seconds as f64)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The session `session_id`, unless it doesn't exist or has expired
+    pub async fn get_active_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<ApiSession>, sqlx::Error> {
+        sqlx::query_as::<_, ApiSession>(
+            "SELECT * FROM api_sessions WHERE session_id = $1 AND expires_at > NOW()",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Append a TVL sample to `tvl_history`, stamped with the stats' timestamp
+    pub async fn record_tvl_sample(&self, stats: &TvlStats) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO tvl_history (
+                total_vaults, total_value_locked, total_locked, total_available, recorded_at
+            ) VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(stats.total_vaults)
+        .bind(stats.total_value_locked)
+        .bind(stats.total_locked)
+        .bind(stats.total_available)
+        .bind(stats.timestamp)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the outcome of a reconciliation cycle
+    pub async fn record_reconciliation_run(
+        &self,
+        started_at: chrono::DateTime<Utc>,
+        total_vaults: i64,
+        mismatches: i64,
+        repaired: i64,
+        errors: i64,
+        duration_ms: i64,
+    ) -> Result<ReconciliationRun, sqlx::Error> {
+        sqlx::query_as::<_, ReconciliationRun>(
+            r#"
+            INSERT INTO reconciliation_runs (
+                started_at, total_vaults, mismatches, repaired, errors, duration_ms
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(started_at)
+        .bind(total_vaults)
+        .bind(mismatches)
+        .bind(repaired)
+        .bind(errors)
+        .bind(duration_ms)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The `limit` most recent reconciliation runs, newest first
+    pub async fn get_reconciliation_runs(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<ReconciliationRun>, sqlx::Error> {
+        sqlx::query_as::<_, ReconciliationRun>(
+            "SELECT * FROM reconciliation_runs ORDER BY started_at DESC, id DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record a position opened on-chain; replaying the same event is a no-op
+    pub async fn insert_position(&self, position: &VaultPosition) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO positions (
+                vault_pubkey, position_id, amount, authority_program,
+                opened_at, unlock_after, tx_signature
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (vault_pubkey, position_id) DO NOTHING
+            "#,
+        )
+        .bind(&position.vault_pubkey)
+        .bind(position.position_id)
+        .bind(position.amount)
+        .bind(&position.authority_program)
+        .bind(position.opened_at)
+        .bind(position.unlock_after)
+        .bind(&position.tx_signature)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Drop a closed position; false if it was not recorded
+    pub async fn delete_position(
+        &self,
+        vault_pubkey: &str,
+        position_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM positions WHERE vault_pubkey = $1 AND position_id = $2")
+                .bind(vault_pubkey)
+                .bind(position_id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// A vault's open positions, oldest first
+    pub async fn get_positions(&self, vault_pubkey: &str) -> Result<Vec<VaultPosition>, sqlx::Error> {
+        sqlx::query_as::<_, VaultPosition>(
+            "SELECT * FROM positions WHERE vault_pubkey = $1 ORDER BY opened_at ASC, position_id ASC",
+        )
+        .bind(vault_pubkey)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record `program_id` as authorized on `vault_pubkey`; `false` if it already was
+    pub async fn insert_authorized_program(
+        &self,
+        vault_pubkey: &str,
+        program_id: &str,
+        authorized_at: DateTime<Utc>,
+        tx_signature: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO vault_authorized_programs (
+                vault_pubkey, program_id, authorized_at, tx_signature
+            ) VALUES ($1, $2, $3, $4)
+            ON CONFLICT (vault_pubkey, program_id) DO NOTHING
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(program_id)
+        .bind(authorized_at)
+        .bind(tx_signature)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Programs the event listener has seen authorized on a vault, oldest first
+    pub async fn get_recorded_authorized_programs(
+        &self,
+        vault_pubkey: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT program_id FROM vault_authorized_programs
+            WHERE vault_pubkey = $1
+            ORDER BY authorized_at ASC, program_id ASC
+            "#,
+        )
+        .bind(vault_pubkey)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// A vault's transactions and alerts merged into one feed, newest first
+    ///
+    /// Each source is read up to `offset + limit` rows deep, which is all a page
+    /// of the merged feed can draw from; on equal timestamps transactions come
+    /// before alerts.
+    pub async fn get_vault_activity(
+        &self,
+        vault_pubkey: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VaultActivity>, sqlx::Error> {
+        let depth = limit + offset;
+
+        let transactions = sqlx::query_as::<_, TransactionRecord>(
+            r#"
+            SELECT * FROM transactions
+            WHERE vault_pubkey = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(depth)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let alerts = sqlx::query_as::<_, Alert>(
+            r#"
+            SELECT * FROM alerts
+            WHERE vault_pubkey = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(vault_pubkey)
+        .bind(depth)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut activity: Vec<VaultActivity> = transactions
+            .into_iter()
+            .map(VaultActivity::Transaction)
+            .chain(alerts.into_iter().map(VaultActivity::Alert))
+            .collect();
+        // Stable, so each source keeps its own id order on ties
+        activity.sort_by_key(|entry| std::cmp::Reverse(entry.created_at()));
+
+        Ok(activity
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    /// TVL samples in `[from, to)` averaged into UTC `date_trunc(interval)` buckets
+    pub async fn get_tvl_history(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+        interval: TvlInterval,
+    ) -> Result<Vec<TvlHistoryPoint>, sqlx::Error> {
+        sqlx::query_as::<_, TvlHistoryPoint>(
+            r#"
+            SELECT
+                date_trunc($1, recorded_at, 'UTC') AS bucket,
+                MAX(total_vaults)::BIGINT AS total_vaults,
+                ROUND(AVG(total_value_locked))::BIGINT AS total_value_locked,
+                ROUND(AVG(total_locked))::BIGINT AS total_locked,
+                ROUND(AVG(total_available))::BIGINT AS total_available,
+                COUNT(*)::BIGINT AS samples
+            FROM tvl_history
+            WHERE recorded_at >= $2 AND recorded_at < $3
+            GROUP BY bucket
+            ORDER BY bucket ASC
+            "#,
+        )
+        .bind(interval.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
 pub async fn cleanup_invalid_vaults(&self) -> Result<u64, sqlx::Error> {
         // Start a transaction to ensure atomicity
         let mut tx = self.pool.begin().await?;
@@ -599,6 +2282,22 @@ pub async fn cleanup_invalid_vaults(&self) -> Result<u64, sqlx::Error> {
         .execute(&mut *tx)
         .await;
         
+        let _ = sqlx::query(
+            r#"
+            DELETE FROM positions 
+            WHERE vault_pubkey IN (
+                SELECT vault_pubkey FROM vaults 
+                WHERE LENGTH(vault_pubkey) < 32 
+                   OR LENGTH(vault_pubkey) > 44
+                   OR vault_pubkey LIKE '%1111111111111111%'
+                   OR vault_pubkey LIKE 'Wf%'
+                   OR vault_pubkey LIKE 'WorkflowTest%'
+            )
+            "#,
+        )
+        .execute(&mut *tx)
+        .await;
+        
         let _ = sqlx::query(
             r#"
             DELETE FROM alerts 
@@ -25,19 +25,24 @@
 //! 5. **Background Services**:
 //!    - Vault monitor for periodic health checks
 //!    - Balance reconciler for on-chain/off-chain sync
+//!    - Auto-locker for opted-in vaults' idle balance
+//!    - Confirmation checker for stuck `pending` transactions
 //!    - Event listener for real-time blockchain events
 //! 6. **HTTP Server**: Actix-web server with CORS, logging, compression
 //!
 //! ## API Endpoints
 //!
 //! - `GET /health` - Health check
+//! - `GET /health/live` - Liveness probe
+//! - `GET /health/ready` - Readiness probe, including background service heartbeats
 //! - `GET /metrics` - Prometheus metrics
 //! - `GET /ws` - WebSocket connection
+//! - `GET /ws/stats` - WebSocket connection metrics
 //! - `/api/v1/vault/*` - Vault operations
 //! - `/api/v1/transaction/*` - Transaction building
+//! - `/api/v1/admin/*` - Maintenance, gated by `ADMIN_API_KEY`
 
 use std::{sync::Arc, time::Duration};
-use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer};
 use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -53,18 +58,23 @@ mod api_tests;
 
 use config::Config;
 
-use crate::{cache::Cache, database::Database, services::{event_listner, vault_moniter}};
+use crate::{
+    cache::Cache,
+    database::Database,
+    services::{event_listner, vault_moniter, EventListenerConfig},
+};
 
 /// Main entry point for the Collateral Vault Backend Server
 ///
 /// Initializes all components in order:
-/// 1. Logging and tracing
-/// 2. Environment configuration
+/// 1. Environment configuration
+/// 2. Logging and tracing
 /// 3. Database connection and migrations
-/// 4. Cache initialization
+/// 4. Cache initialization and warm-up
 /// 5. Solana RPC client
-/// 6. Background services (monitor, reconciler, event listener)
+/// 6. Background services (monitor, reconciler, TVL recorder, auto-locker, pause watcher, event listener)
 /// 7. HTTP server with routes
+/// 8. Graceful shutdown on Ctrl+C / SIGTERM
 ///
 /// # Panics
 ///
@@ -79,23 +89,23 @@ use crate::{cache::Cache, database::Database, services::{event_listner, vault_mo
 /// See `Config::from_env()` for required environment variables.
 #[actix_web::main]
 async fn main() -> Result<(), std::io::Error>{
+    // Load environment variables from .env file if present
+    dotenv::dotenv().ok();
+
+    // Load and validate configuration; LOG_FORMAT is needed before tracing starts
+    let config = Config::from_env().expect("Failed to load configuration");
+
     // Initialize tracing with default level filters
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "backend=debug,actix_web=info".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(monitering::logging::fmt_layer(config.log_format, std::io::stdout))
         .init();
 
     tracing::info!(" Starting Collateral Vault Management System Backend");
-
-    // Load environment variables from .env file if present
-    dotenv::dotenv().ok();
-
-    // Load and validate configuration
-    let config = Config::from_env().expect("Failed to load configuration");
-    tracing::info!(" Configuration loaded");
+    tracing::info!(" Configuration loaded (log format: {:?})", config.log_format);
 
     // Initialize database connection and run migrations
     let database = Database::new(&config.database_url)
@@ -118,12 +128,30 @@ async fn main() -> Result<(), std::io::Error>{
         tracing::warn!("Failed to cleanup invalid vaults: {}", e);
     }
 }
-    // Initialize cache with specified capacity
-    let cache = Cache::new(20_000);
-    tracing::info!(" Cache initialized with 20,000 entry capacity");
+    // Initialize cache with specified capacity and configured TTLs
+    let mut cache = Cache::new(
+        20_000,
+        Duration::from_secs(config.cache_ttl_seconds.into()),
+        Duration::from_secs(config.tvl_cache_ttl_seconds.into()),
+    );
+    tracing::info!(
+        " Cache initialized with 20,000 entry capacity ({}s TTL, {}s TVL TTL)",
+        config.cache_ttl_seconds,
+        config.tvl_cache_ttl_seconds
+    );
+    if let Some(url) = &config.cache_invalidation_url {
+        cache = cache
+            .with_invalidation_channel(url)
+            .await
+            .expect("Failed to subscribe to cache invalidations");
+        tracing::info!(" Cache invalidations shared across instances");
+    }
 
     // Initialize Solana RPC client
-    let solana_client = AsyncRpcClient::new(config.solana_rpc_url.clone());
+    let solana_client = AsyncRpcClient::new_with_commitment(
+        config.solana_rpc_url.clone(),
+        config.solana_commitment,
+    );
     tracing::info!(" Solana RPC client initialized: {}", config.solana_rpc_url);
 
     // Create shared application state
@@ -133,8 +161,15 @@ async fn main() -> Result<(), std::io::Error>{
         config: config.clone(),
         solana_client: Arc::new(solana_client),
         program_id: config.program_id,
+        heartbeats: Default::default(),
     });
 
+    // Warm the cache before taking traffic so the first requests don't all hit Postgres
+    match services::cache_warmer::warm_cache(&app_state).await {
+        Ok(count) => tracing::info!(" Cache warmed with {} vaults", count),
+        Err(e) => tracing::warn!("Cache warm-up failed, starting cold: {}", e),
+    }
+
     // Start background services
 
     // Vault monitor - periodic health checks and maintenance
@@ -149,33 +184,51 @@ async fn main() -> Result<(), std::io::Error>{
         services::balance_reconciler::run_reconciler(reconcile_state).await;
     });
 
+    // TVL recorder - periodic samples for TVL history charts
+    let tvl_state = app_state.clone();
+    tokio::spawn(async move {
+        services::tvl_recorder::run_tvl_recorder(tvl_state).await;
+    });
+
+    // Confirmation checker - settle API transactions the event listener never confirmed
+    let confirmation_state = app_state.clone();
+    tokio::spawn(async move {
+        services::confirmation_checker::run_confirmation_checker(confirmation_state).await;
+    });
+
+    // Auto-locker - lock idle balance above each opted-in vault's buffer
+    let auto_lock_state = app_state.clone();
+    tokio::spawn(async move {
+        services::auto_locker::run_auto_locker(auto_lock_state).await;
+    });
+
+    // Pause watcher - mirror the on-chain global pause so mutations fail fast
+    let pause_state = app_state.clone();
+    tokio::spawn(async move {
+        services::program_pause::run_pause_watcher(pause_state).await;
+    });
+
     // Event listener - monitor blockchain for vault events
     let event_listener_state = app_state.clone();
+    let event_listener_config = EventListenerConfig::from_config(&config);
     tokio::spawn(async move {
-        loop {
-            let state = event_listener_state.clone();
-            match tokio::spawn(async move {
-                event_listner::run_event_listener(state).await;
-            }).await {
-                Ok(_) => tracing::warn!("Event listener exited, restarting..."),
-                Err(e) => tracing::error!("Event listener panicked: {:?}", e),
-            }
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
+        event_listner::supervise_event_listener(event_listener_state, event_listener_config)
+            .await;
     });
-    tracing::info!(" Background services started (monitor, reconciler, event listener)");
+    tracing::info!(
+        " Background services started (monitor, reconciler, TVL recorder, auto-locker, event listener)"
+    );
+
+    // Cap WebSocket connections and per-client subscriptions
+    websocket::WS_REGISTRY.set_limits(config.ws_max_clients, config.ws_max_subs_per_client);
 
     // Configure and start HTTP server
     let bind_address = format!("{}:{}", config.host, config.port);
     tracing::info!(" Server listening on http://{}", bind_address);
 
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         // Configure CORS for cross-origin requests
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+        let cors = api::cors::cors(&app_state.config);
 
         App::new()
             .app_data(app_state.clone())
@@ -183,25 +236,89 @@ async fn main() -> Result<(), std::io::Error>{
             .wrap(middleware::Logger::default())
             // Response compression
             .wrap(middleware::Compress::default())
-            // Tracing integration
-            .wrap(tracing_actix_web::TracingLogger::default())
+            // Tracing integration, with the root span keyed by the request ID
+            .wrap(tracing_actix_web::TracingLogger::<api::request_id::RequestIdRootSpan>::new())
             .wrap(cors)
+            // Assign X-Request-Id before anything else runs
+            .wrap(middleware::from_fn(api::request_id::assign_request_id))
             // Health check endpoint
             .route("/health", web::get().to(api::health::health_check))
+            // Liveness and readiness probes for load balancers
+            .route("/health/live", web::get().to(api::health::liveness))
+            .route("/health/ready", web::get().to(api::health::readiness_check))
             // Metrics endpoint for monitoring
             .route("/metrics", web::get().to(monitering::metrics::metrics))
             // WebSocket endpoint for real-time updates
             .route("/ws", web::get().to(websocket::ws_handler))
+            // WebSocket connection load
+            .route("/ws/stats", web::get().to(websocket::ws_stats))
             // API v1 routes
             .service(
                 web::scope("/api/v1")
+                    .configure(api::auth::configure)
                     .configure(api::vault::configure)
-                    .configure(api::transaction::configure),
+                    .configure(api::transaction::configure)
+                    .configure(api::webhook::configure)
+                    .configure(api::analytics::configure)
+                    .configure(api::reconciliation::configure)
+                    .configure(api::admin::configure),
             )
     })
-    // Configure worker threads (2x CPU cores for optimal performance)
-    .workers(num_cpus::get() * 2)
+    // One worker per CPU unless WORKER_THREADS says otherwise
+    .workers(config.worker_threads)
+    // Signals are handled below so WebSocket clients can be drained first
+    .disable_signals()
+    .shutdown_timeout(websocket::SHUTDOWN_DRAIN_TIMEOUT.as_secs())
     .bind(bind_address)?
-    .run()
-    .await
+    .run();
+
+    // Graceful shutdown: notify WebSocket clients, wait for them to drain, then stop the server
+    let server_handle = server.handle();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        tracing::info!(" Shutdown signal received, draining WebSocket clients");
+
+        websocket::broadcast_shutdown().await;
+        let remaining = websocket::WS_REGISTRY
+            .drain(websocket::SHUTDOWN_DRAIN_TIMEOUT)
+            .await;
+        if remaining > 0 {
+            tracing::warn!("{} WebSocket clients still connected at shutdown", remaining);
+        }
+
+        server_handle.stop(true).await;
+    });
+
+    server.await
+}
+
+/// Resolve on Ctrl+C or, on Unix, SIGTERM (e.g. container stop)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
 }
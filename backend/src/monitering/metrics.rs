@@ -13,6 +13,38 @@ static TVL: Lazy<Gauge> = Lazy::new(|| {
     REGISTRY.register(Box::new(gauge.clone())).unwrap();
     gauge
 });
+static WS_CLIENTS: Lazy<Gauge> = Lazy::new(|| {
+    let gauge = Gauge::new("websocket_clients", "Connected WebSocket clients").unwrap();
+    REGISTRY.register(Box::new(gauge.clone())).unwrap();
+    gauge
+});
+static WS_DROPPED_MESSAGES: Lazy<Counter> = Lazy::new(|| {
+    let counter = Counter::new(
+        "websocket_dropped_messages_total",
+        "Broadcast messages lagging WebSocket clients missed",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+});
+static RECONCILIATION_MISMATCHES: Lazy<Gauge> = Lazy::new(|| {
+    let gauge = Gauge::new(
+        "reconciliation_last_mismatches",
+        "Balance mismatches found by the last reconciliation cycle",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(gauge.clone())).unwrap();
+    gauge
+});
+static RECONCILIATION_DURATION: Lazy<Gauge> = Lazy::new(|| {
+    let gauge = Gauge::new(
+        "reconciliation_last_duration_seconds",
+        "Duration of the last reconciliation cycle",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(gauge.clone())).unwrap();
+    gauge
+});
 static API_REQUESTS: Lazy<Counter> = Lazy::new(|| {
     let counter = Counter::new("api_requests_total", "Total API Requests").unwrap();
     REGISTRY.register(Box::new(counter.clone())).unwrap();
@@ -28,6 +60,16 @@ pub fn set_vault_count(count: f64) {
 pub fn set_tvl(tvl: f64) {
     TVL.set(tvl);
 }
+pub fn set_ws_clients(count: f64) {
+    WS_CLIENTS.set(count);
+}
+pub fn add_ws_dropped_messages(count: u64) {
+    WS_DROPPED_MESSAGES.inc_by(count as f64);
+}
+pub fn set_last_reconciliation(mismatches: f64, duration_secs: f64) {
+    RECONCILIATION_MISMATCHES.set(mismatches);
+    RECONCILIATION_DURATION.set(duration_secs);
+}
 pub async fn metrics() -> impl Responder {
     let encoder = TextEncoder::new();
     let metrics_families = REGISTRY.gather();
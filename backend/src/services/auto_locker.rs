@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::time::Duration;
+
+use actix_web::web::Data;
+use tokio::time;
+
+use crate::services::{AppState, CPIError, CpiManager, VaultManager};
+use crate::websocket::broadcast_alert;
+
+pub async fn run_auto_locker(state: Data<AppState>) {
+    let Some(authority) = state.config.auto_lock_authority else {
+        tracing::info!("Auto-locker disabled (AUTO_LOCK_AUTHORITY not set)");
+        return;
+    };
+
+    let interval_secs = state.config.auto_lock_interval_seconds.max(1);
+    let mut interval = time::interval(Duration::from_secs(interval_secs));
+
+    tracing::info!(
+        "Auto-locker started (interval: {}s, authority: {})",
+        interval_secs,
+        authority
+    );
+
+    loop {
+        interval.tick().await;
+
+        let lock = |vault_pubkey: String, amount: u64| {
+            let state = state.clone();
+            async move {
+                CpiManager::lock_collateral_cpi(&state, &vault_pubkey, &authority, amount).await
+            }
+        };
+
+        match auto_lock_cycle(&state, lock).await {
+            Ok(locked) if locked > 0 => tracing::info!("Auto-locked excess in {} vaults", locked),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Auto-lock cycle error: {}", e),
+        }
+    }
+}
+
+/// Lock the available balance above each opted-in vault's buffer
+///
+/// `lock` submits the on-chain lock and returns its signature. A failed lock
+/// raises an `auto_lock_failed` alert and the sweep moves on to the next vault.
+/// Returns the number of vaults locked.
+pub(crate) async fn auto_lock_cycle<F, Fut>(
+    state: &AppState,
+    mut lock: F,
+) -> Result<usize, AutoLockError>
+where
+    F: FnMut(String, u64) -> Fut,
+    Fut: Future<Output = Result<String, CPIError>>,
+{
+    let candidates = state
+        .database
+        .get_auto_lock_candidates()
+        .await
+        .map_err(|e| AutoLockError::DatabaseError(e.to_string()))?;
+
+    tracing::debug!("Auto-lock candidates: {}", candidates.len());
+
+    let mut locked = 0;
+    for settings in candidates {
+        let vault_pubkey = &settings.vault_pubkey;
+
+        // Re-read the balance; the candidate list may be stale by now
+        let vault = match state.database.get_vault(vault_pubkey).await {
+            Ok(Some(vault)) => vault,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::error!("Auto-lock failed to load vault {}: {}", vault_pubkey, e);
+                continue;
+            }
+        };
+
+        let Some(amount) = settings.excess(vault.available_balance) else {
+            continue;
+        };
+
+        let signature = match lock(vault_pubkey.clone(), amount as u64).await {
+            Ok(signature) => signature,
+            Err(e) => {
+                CpiManager::handle_cpi_error(&e, "auto-lock");
+                raise_failure_alert(state, vault_pubkey, amount, &e).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = VaultManager::process_lock(state, vault_pubkey, amount, &signature).await {
+            // The lock landed on-chain; the event listener or reconciler will catch the DB up
+            tracing::error!(
+                "Auto-lock {} for vault {} succeeded on-chain but was not recorded: {}",
+                signature,
+                vault_pubkey,
+                e
+            );
+            continue;
+        }
+
+        let event_data = serde_json::json!({ "buffer": settings.auto_lock_buffer });
+        if let Err(e) = state
+            .database
+            .create_audit_entry(
+                "auto_lock",
+                Some(vault_pubkey),
+                None,
+                Some(amount),
+                Some(&signature),
+                event_data,
+                None,
+                None,
+            )
+            .await
+        {
+            tracing::error!("Failed to audit auto-lock for vault {}: {}", vault_pubkey, e);
+        }
+
+        tracing::info!(
+            "Auto-locked {} in vault {} (buffer {})",
+            amount,
+            vault_pubkey,
+            settings.auto_lock_buffer
+        );
+        locked += 1;
+    }
+
+    Ok(locked)
+}
+
+async fn raise_failure_alert(state: &AppState, vault_pubkey: &str, amount: i64, error: &CPIError) {
+    let message = format!("Auto-lock of {} failed: {}", amount, error);
+
+    match state
+        .database
+        .create_alert("auto_lock_failed", "warning", Some(vault_pubkey), &message, None)
+        .await
+    {
+        Ok(_) => {
+            broadcast_alert("auto_lock_failed", "warning", Some(vault_pubkey), &message).await;
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to persist auto-lock alert for vault {}: {}",
+                vault_pubkey,
+                e
+            );
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AutoLockError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
@@ -1,17 +1,28 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt};
+use shared::{ReconciliationRun, SortOrder, VaultListParams, VaultSortBy};
 use tokio::time;
 
-use crate::services::{AppState, BalanceTracker};
+use crate::monitering::set_last_reconciliation;
+use crate::services::{
+    AppState, BackgroundService, BalanceError, BalanceTracker, ReconciliationResult,
+    ReconciliationStatus,
+};
+use crate::websocket::broadcast_alert;
 
 pub async fn run_reconciler(state: actix_web::web::Data<AppState>) {
     let interval_secs = state.config.reconciliation_interval_seconds;
     let mut interval = time::interval(Duration::from_secs(interval_secs));
+    // A cycle that overruns the interval pushes the next one back instead of bunching them up
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
 
     tracing::info!("Balance Reconciler started (interval: {}s)", interval_secs);
 
     loop {
         interval.tick().await;
+        state.heartbeats.beat(BackgroundService::Reconciler);
 
         if let Err(e) = reconciliation_cycle(&state).await {
             tracing::error!("Reconciliation cycle error: {}", e);
@@ -19,77 +30,185 @@ pub async fn run_reconciler(state: actix_web::web::Data<AppState>) {
     }
 }
 
-async fn reconciliation_cycle(state: &AppState) -> Result<(), ReconcilerError> {
+/// Vault counts for one reconciliation cycle, or one batch of it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReconciliationTotals {
+    pub total_vaults: usize,
+    pub mismatches: usize,
+    pub repaired: usize,
+    pub errors: usize,
+}
+
+impl std::ops::AddAssign for ReconciliationTotals {
+    fn add_assign(&mut self, other: Self) {
+        self.total_vaults += other.total_vaults;
+        self.mismatches += other.mismatches;
+        self.repaired += other.repaired;
+        self.errors += other.errors;
+    }
+}
+
+/// Page through every active vault, oldest first, reconciling each page concurrently
+pub async fn reconciliation_cycle(state: &AppState) -> Result<ReconciliationTotals, ReconcilerError> {
     tracing::info!("Starting reconciliation cycle...");
-    let vaults = state
-        .database
-        .get_all_vaults(10000, 0)
-        .await
-        .map_err(|_| ReconcilerError::DatabaseError("Database error".to_string()))?;
-    let mut total_vaults = 0;
-    let mut mismatches = 0;
-    let mut errors = 0;
-
-    for vault in vaults {
-        total_vaults += 1;
-         if vault.vault_pubkey.len() < 32 || vault.vault_pubkey.len() > 44 {
-            tracing::warn!("Skipping vault with invalid pubkey format: {}", vault.vault_pubkey);
-            errors += 1;
-            continue;
-        }
-        match BalanceTracker::recomcile_balance(state, &vault.vault_pubkey).await {
-            Ok(result) => match result.status {
-                crate::services::balance_tracker::ReconciliationStatus::Mismatch => {
-                    mismatches += 1;
-                    tracing::warn!(
-                        "Mismatch for vault {}: expected {}, actual {}, diff {}",
-                        result.vault_pubkey,
-                        result.expected_balance,
-                        result.actual_balance,
-                        result.discrepancy
-                    );
-                }
-                _ => {}
-            },
-            Err(e) => {
-                errors += 1;
-                tracing::error!(
-                    "Reconciliation failed for vault {}: {}",
-                    vault.vault_pubkey,
-                    e
-                );
-            }
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let batch_size = state.config.reconciliation_batch_size.max(1);
+    let mut totals = ReconciliationTotals::default();
+    let mut offset = 0;
+
+    loop {
+        let vaults = state
+            .database
+            .get_all_vaults(&VaultListParams {
+                limit: batch_size,
+                offset,
+                sort_by: VaultSortBy::CreatedAt,
+                order: SortOrder::Asc,
+                ..Default::default()
+            })
+            .await
+            .map_err(|_| ReconcilerError::DatabaseError("Database error".to_string()))?;
+
+        let page_len = vaults.len() as i64;
+        let pubkeys: Vec<String> = vaults.into_iter().map(|v| v.vault_pubkey).collect();
+        totals += reconcile_batch(state, &pubkeys).await;
+
+        if page_len < batch_size {
+            break;
         }
+        offset += page_len;
     }
 
+    let ReconciliationTotals { total_vaults, mismatches, repaired, errors } = totals;
     tracing::info!(
-        "Reconciliation cycle completed: {} vaults, {} mismatches, {} errors",
+        "Reconciliation cycle completed in {:.2?}: {} vaults, {} mismatches ({} auto-repaired), {} errors",
+        started.elapsed(),
         total_vaults,
         mismatches,
+        repaired,
         errors
     );
 
+    record_run(state, started_at, started.elapsed(), totals).await?;
+
     if mismatches > 0 {
+        let message = format!(
+            "Reconciliation found {} mismatches out of {} vaults",
+            mismatches, total_vaults
+        );
         state
             .database
             .create_alert(
                 "reconciliation_summary",
                 "warning",
                 None,
-                &format!(
-                    "Reconciliation found {} mismatches out of {} vaults",
-                    mismatches, total_vaults
-                ),
+                &message,
                 Some(serde_json::json!({
                     "total_vaults": total_vaults,
                     "mismatches": mismatches,
+                    "repaired": repaired,
                     "errors": errors,
+                    "duration_ms": started.elapsed().as_millis() as u64,
                 })),
             )
             .await
             .map_err(|e| ReconcilerError::DatabaseError(e.to_string()))?;
+
+        broadcast_alert("reconciliation_summary", "warning", None, &message).await;
+    }
+    Ok(totals)
+}
+
+/// Persist a finished cycle to `reconciliation_runs` and publish it as the last-run gauges
+pub async fn record_run(
+    state: &AppState,
+    started_at: DateTime<Utc>,
+    duration: Duration,
+    totals: ReconciliationTotals,
+) -> Result<ReconciliationRun, ReconcilerError> {
+    set_last_reconciliation(totals.mismatches as f64, duration.as_secs_f64());
+
+    state
+        .database
+        .record_reconciliation_run(
+            started_at,
+            totals.total_vaults as i64,
+            totals.mismatches as i64,
+            totals.repaired as i64,
+            totals.errors as i64,
+            duration.as_millis() as i64,
+        )
+        .await
+        .map_err(|e| ReconcilerError::DatabaseError(e.to_string()))
+}
+
+/// Reconcile `vault_pubkeys` with at most `reconciliation_concurrency` RPC lookups in flight
+pub async fn reconcile_batch(state: &AppState, vault_pubkeys: &[String]) -> ReconciliationTotals {
+    let concurrency = state.config.reconciliation_concurrency.max(1);
+
+    let lookups: Vec<_> = vault_pubkeys.iter().map(|pk| reconcile_one(state, pk)).collect();
+    let mut results = stream::iter(lookups).buffer_unordered(concurrency);
+
+    let mut totals = ReconciliationTotals::default();
+    while let Some(vault) = results.next().await {
+        totals += vault;
+    }
+    totals
+}
+
+/// Reconcile each of `vault_pubkeys` on demand, outside the periodic cycle
+///
+/// Runs with the same `reconciliation_concurrency` bound as [`reconcile_batch`] and
+/// returns one result per vault, in the order given.
+pub async fn reconcile_vaults(
+    state: &AppState,
+    vault_pubkeys: &[String],
+) -> Vec<Result<ReconciliationResult, BalanceError>> {
+    let concurrency = state.config.reconciliation_concurrency.max(1);
+
+    let lookups: Vec<_> = vault_pubkeys
+        .iter()
+        .map(|pk| BalanceTracker::recomcile_balance(state, pk))
+        .collect();
+    stream::iter(lookups).buffered(concurrency).collect().await
+}
+
+async fn reconcile_one(state: &AppState, vault_pubkey: &str) -> ReconciliationTotals {
+    let mut totals = ReconciliationTotals {
+        total_vaults: 1,
+        ..Default::default()
+    };
+
+    if vault_pubkey.len() < 32 || vault_pubkey.len() > 44 {
+        tracing::warn!("Skipping vault with invalid pubkey format: {}", vault_pubkey);
+        totals.errors += 1;
+        return totals;
+    }
+    match BalanceTracker::recomcile_balance(state, vault_pubkey).await {
+        Ok(result) => match result.status {
+            ReconciliationStatus::Mismatch => {
+                totals.mismatches += 1;
+                tracing::warn!(
+                    "Mismatch for vault {}: expected {}, actual {}, diff {}",
+                    result.vault_pubkey,
+                    result.expected_balance,
+                    result.actual_balance,
+                    result.discrepancy
+                );
+            }
+            ReconciliationStatus::Repaired => {
+                totals.mismatches += 1;
+                totals.repaired += 1;
+            }
+            ReconciliationStatus::Match => {}
+        },
+        Err(e) => {
+            totals.errors += 1;
+            tracing::error!("Reconciliation failed for vault {}: {}", vault_pubkey, e);
+        }
     }
-    Ok(())
+    totals
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -1,12 +1,18 @@
-use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
-use spl_token::state::Account as TokenAccount;
+use chrono::{DateTime, Utc};
+use shared::{BalanceHistoryPoint, TransactionRecord, TvlInterval, VaultUtilization};
+use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 
-use crate::services::AppState;
+use crate::services::{rpc_retry, AppState, TokenProgram, VaultManager};
+use crate::websocket::broadcast_alert;
+
+/// Most buckets one balance history request may span
+pub const MAX_BALANCE_HISTORY_POINTS: i64 = 1_000;
 
 pub struct BalanceTracker;
 
 impl BalanceTracker {
+    /// Read a token account's balance, unpacking it with the program that owns it
     pub async fn get_on_chain_balance(
         state: &AppState,
         token_account_pubkey: &str,
@@ -14,14 +20,37 @@ impl BalanceTracker {
         let pubkey =
             Pubkey::from_str(token_account_pubkey).map_err(|_| BalanceError::InvalidPubkey)?;
 
-        let account_data = state
-            .solana_client
-            .get_account_data(&pubkey)
+        let account = rpc_retry("get_account", || state.solana_client.get_account(&pubkey))
             .await
             .map_err(|e| BalanceError::SolanaRpcError(e.to_string()))?;
-        let token_account = TokenAccount::unpack(&account_data)
-            .map_err(|e| BalanceError::DeserializationError(e.to_string()))?;
-        Ok(token_account.amount)
+        let token_program = TokenProgram::from_id(&account.owner)
+            .ok_or_else(|| BalanceError::UnsupportedTokenProgram(account.owner.to_string()))?;
+
+        token_program
+            .unpack_amount(&account.data)
+            .map_err(|e| BalanceError::DeserializationError(e.to_string()))
+    }
+
+    /// Token program owning `token_account`, or `None` if the account doesn't exist yet
+    pub async fn detect_token_program(
+        state: &AppState,
+        token_account: &Pubkey,
+    ) -> Result<Option<TokenProgram>, BalanceError> {
+        let account = rpc_retry("get_token_account", || {
+            state
+                .solana_client
+                .get_account_with_commitment(token_account, state.solana_client.commitment())
+        })
+        .await
+        .map_err(|e| BalanceError::SolanaRpcError(e.to_string()))?
+        .value;
+
+        match account {
+            Some(account) => TokenProgram::from_id(&account.owner)
+                .map(Some)
+                .ok_or_else(|| BalanceError::UnsupportedTokenProgram(account.owner.to_string())),
+            None => Ok(None),
+        }
     }
 
     pub async fn has_sufficient_balance(
@@ -43,6 +72,15 @@ impl BalanceTracker {
         state: &AppState,
         vault_pubkey: &str,
     ) -> Result<f64, BalanceError> {
+        Ok(Self::get_vault_utilization(state, vault_pubkey)
+            .await?
+            .utilization_pct)
+    }
+
+    pub async fn get_vault_utilization(
+        state: &AppState,
+        vault_pubkey: &str,
+    ) -> Result<VaultUtilization, BalanceError> {
         let vault = state
             .database
             .get_vault(vault_pubkey)
@@ -50,17 +88,62 @@ impl BalanceTracker {
             .map_err(|e| BalanceError::DatabaseError(e.to_string()))?
             .ok_or(BalanceError::VaultNotFound)?;
 
-        if vault.total_balance == 0 {
-            return Ok(0.0);
-        }
+        Ok(VaultUtilization::from(&vault))
+    }
 
-        Ok((vault.locked_balance as f64 / vault.total_balance as f64) * 100.0)
+    /// Reconstruct a vault's balances over `[from, to)`, one point per `interval` bucket
+    ///
+    /// Starts from the last snapshot at or before `from` (or an empty vault when
+    /// there is none) and replays the vault's transactions from there.
+    pub async fn get_balance_history(
+        state: &AppState,
+        vault_pubkey: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: TvlInterval,
+    ) -> Result<Vec<BalanceHistoryPoint>, BalanceError> {
+        state
+            .database
+            .get_vault(vault_pubkey)
+            .await
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?
+            .ok_or(BalanceError::VaultNotFound)?;
+
+        let snapshot = state
+            .database
+            .get_snapshot_at(vault_pubkey, from)
+            .await
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+        let (start, since) = match snapshot {
+            Some(s) => (
+                Balances {
+                    total: s.total_balance,
+                    available: s.available_balance,
+                    locked: s.locked_balance,
+                },
+                Some(s.snapshot_ts),
+            ),
+            None => (Balances::default(), None),
+        };
+
+        let transactions = state
+            .database
+            .get_balance_changes(vault_pubkey, since, to)
+            .await
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+
+        Ok(balance_series(vault_pubkey, start, &transactions, from, to, interval))
     }
 
+    /// Raise a `low_balance` alert when the vault's available balance is under its threshold
+    ///
+    /// The threshold comes from the vault's [`LowBalanceSettings`](shared::LowBalanceSettings),
+    /// falling back to `LOW_BALANCE_FLOOR` / `LOW_BALANCE_PERCENT`. No alert is raised
+    /// within `LOW_BALANCE_ALERT_COOLDOWN_SECONDS` of the vault's previous one. Returns the
+    /// new alert's ID.
     pub async fn check_low_balances(
         state: &AppState,
         vault_pubkey: &str,
-        threshold: i64,
     ) -> Result<Option<i64>, BalanceError> {
         let vault = state
             .database
@@ -68,23 +151,43 @@ impl BalanceTracker {
             .await
             .map_err(|e| BalanceError::DatabaseError(e.to_string()))?
             .ok_or(BalanceError::VaultNotFound)?;
+        let settings = state
+            .database
+            .get_low_balance_settings(vault_pubkey)
+            .await
+            .map_err(|e| BalanceError::DatabaseError(e.to_string()))?
+            .ok_or(BalanceError::VaultNotFound)?;
 
+        let threshold = settings.threshold(
+            vault.total_balance,
+            state.config.low_balance_floor,
+            state.config.low_balance_percent,
+        );
         if vault.available_balance < threshold {
+            let cooldown =
+                chrono::Duration::seconds(state.config.low_balance_alert_cooldown_seconds as i64);
+            let recently_alerted = state
+                .database
+                .has_recent_alert(vault_pubkey, "low_balance", Utc::now() - cooldown)
+                .await
+                .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+            if recently_alerted {
+                tracing::debug!("Low balance alert for vault {} still cooling down", vault_pubkey);
+                return Ok(None);
+            }
+
+            let message = format!(
+                "Available balance ({}) below threshold ({})",
+                vault.available_balance, threshold
+            );
             let alert_id = state
                 .database
-                .create_alert(
-                    "low_balance",
-                    "warning",
-                    Some(vault_pubkey),
-                    &format!(
-                        "Available balance ({}) below threshold ({})",
-                        vault.available_balance, threshold
-                    ),
-                    None,
-                )
+                .create_alert("low_balance", "warning", Some(vault_pubkey), &message, None)
                 .await
                 .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
 
+            broadcast_alert("low_balance", "warning", Some(vault_pubkey), &message).await;
+
             tracing::warn!(
                 "Low balance alert created for vault {}: {} < {}",
                 vault_pubkey,
@@ -147,16 +250,17 @@ impl BalanceTracker {
                 .await
                 .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
 
+            let message = format!(
+                "Balance mismatch: expected {}, actual {}, diff {}",
+                expected_balance, actual_balance, discrepancy
+            );
             state
                 .database
                 .create_alert(
                     "balance_discrepancy",
                     "critical",
                     Some(vault_pubkey),
-                    &format!(
-                        "Balance mismatch: expected {}, actual {}, diff {}",
-                        expected_balance, actual_balance, discrepancy
-                    ),
+                    &message,
                     Some(serde_json::json!({
                         "reconciliation_log_id": log_id,
                         "expected": expected_balance,
@@ -166,6 +270,8 @@ impl BalanceTracker {
                 )
                 .await
                 .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
+
+            broadcast_alert("balance_discrepancy", "critical", Some(vault_pubkey), &message).await;
             tracing::error!(
                 "Balance discrepancy detected for vault {}: expected {}, actual {}, diff {}",
                 vault_pubkey,
@@ -174,12 +280,20 @@ impl BalanceTracker {
                 discrepancy
             );
 
+            let status = if state.config.auto_repair
+                && Self::auto_repair(state, vault_pubkey, log_id).await
+            {
+                ReconciliationStatus::Repaired
+            } else {
+                ReconciliationStatus::Mismatch
+            };
+
             return Ok(ReconciliationResult {
                 vault_pubkey: vault_pubkey.to_string(),
                 expected_balance,
                 actual_balance,
                 discrepancy,
-                status: ReconciliationStatus::Mismatch,
+                status,
             });
         }
         tracing::debug!("Balance reconciliation OK for vault {}", vault_pubkey);
@@ -193,6 +307,37 @@ impl BalanceTracker {
         })
     }
 
+    /// Pull authoritative on-chain balances into the DB and resolve the log entry
+    ///
+    /// Returns whether the repair succeeded; failures are logged and leave the
+    /// reconciliation log unresolved for manual follow-up.
+    async fn auto_repair(state: &AppState, vault_pubkey: &str, log_id: i64) -> bool {
+        let vault = match VaultManager::sync_vault_from_chain(state, vault_pubkey).await {
+            Ok(vault) => vault,
+            Err(e) => {
+                tracing::error!("Auto-repair failed for vault {}: {}", vault_pubkey, e);
+                return false;
+            }
+        };
+
+        let notes = format!(
+            "auto-repaired: synced from chain (total={}, locked={})",
+            vault.total_balance, vault.locked_balance
+        );
+        if let Err(e) = state.database.resolve_reconciliation(log_id, &notes).await {
+            tracing::error!(
+                "Auto-repaired vault {} but failed to resolve log {}: {}",
+                vault_pubkey,
+                log_id,
+                e
+            );
+            return false;
+        }
+
+        tracing::info!("Auto-repaired vault {} (log {})", vault_pubkey, log_id);
+        true
+    }
+
     pub async fn verify_balance_invariant(
         state: &AppState,
         vault_pubkey: &str,
@@ -213,21 +358,18 @@ impl BalanceTracker {
                 vault.locked_balance, calculated_total
             );
 
+            let message = format!(
+                "Balance invariant violated: {} != {} + {}",
+                vault.total_balance, vault.available_balance, vault.locked_balance
+            );
             state
                 .database
-                .create_alert(
-                    "invariant_violation",
-                    "critical",
-                    Some(vault_pubkey),
-                    &format!(
-                        "Balance invariant violated: {} != {} + {}",
-                        vault.total_balance, vault.available_balance, vault.locked_balance
-                    ),
-                    None,
-                )
+                .create_alert("invariant_violation", "critical", Some(vault_pubkey), &message, None)
                 .await
                 .map_err(|e| BalanceError::DatabaseError(e.to_string()))?;
 
+            broadcast_alert("invariant_violation", "critical", Some(vault_pubkey), &message).await;
+
             return Ok(false);
         }
 
@@ -235,6 +377,87 @@ impl BalanceTracker {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+struct Balances {
+    total: i64,
+    available: i64,
+    locked: i64,
+}
+
+impl Balances {
+    /// Apply one recorded transaction the way the program moves balances
+    fn apply(&mut self, vault_pubkey: &str, tx: &TransactionRecord) {
+        let amount = tx.amount;
+        match tx.tx_type.as_str() {
+            "deposit" => {
+                self.total += amount;
+                self.available += amount;
+            }
+            "withdraw" => {
+                self.total -= amount;
+                self.available -= amount;
+            }
+            "lock" => {
+                self.locked += amount;
+                self.available -= amount;
+            }
+            "unlock" => {
+                self.locked -= amount;
+                self.available += amount;
+            }
+            // Recorded once, under the sender; moves available balance
+            "transfer" if tx.to_vault.as_deref() == Some(vault_pubkey) => {
+                self.total += amount;
+                self.available += amount;
+            }
+            "transfer" => {
+                self.total -= amount;
+                self.available -= amount;
+            }
+            // Seized collateral leaves the vault from its locked balance
+            "liquidation" => {
+                self.total -= amount;
+                self.locked -= amount;
+            }
+            other => {
+                tracing::warn!("Skipping unknown transaction type {} in balance history", other)
+            }
+        }
+    }
+}
+
+/// Fold `transactions` (oldest first) onto `balances`, sampling at the close of
+/// each `interval` bucket from the one holding `from` up to `to`
+fn balance_series(
+    vault_pubkey: &str,
+    mut balances: Balances,
+    transactions: &[TransactionRecord],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    interval: TvlInterval,
+) -> Vec<BalanceHistoryPoint> {
+    let step = interval.duration();
+    let mut pending = transactions.iter().peekable();
+    let mut points = Vec::new();
+
+    let mut bucket = interval.truncate(from);
+    while bucket < to {
+        let close = (bucket + step).min(to);
+        while let Some(tx) = pending.next_if(|tx| tx.created_at < close) {
+            balances.apply(vault_pubkey, tx);
+        }
+        points.push(BalanceHistoryPoint {
+            timestamp: bucket,
+            total: balances.total,
+            available: balances.available,
+            locked: balances.locked,
+        });
+        bucket += step;
+    }
+
+    points
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct ReconciliationResult {
     pub vault_pubkey: String,
@@ -248,6 +471,8 @@ pub struct ReconciliationResult {
 pub enum ReconciliationStatus {
     Match,
     Mismatch,
+    /// Mismatch that was fixed by syncing the vault from chain
+    Repaired,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -266,4 +491,7 @@ pub enum BalanceError {
 
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
+
+    #[error("Account is owned by {0}, not a supported token program")]
+    UnsupportedTokenProgram(String),
 }
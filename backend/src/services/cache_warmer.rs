@@ -0,0 +1,38 @@
+use crate::services::AppState;
+
+/// Load the `cache_warm_vaults` most recently updated vaults and the TVL stats into the cache
+///
+/// Run once on startup; returns the number of vaults cached.
+pub async fn warm_cache(state: &AppState) -> Result<usize, CacheWarmError> {
+    let limit = state.config.cache_warm_vaults;
+    if limit <= 0 {
+        return Ok(0);
+    }
+
+    let vaults = state
+        .database
+        .get_recently_active_vaults(limit)
+        .await
+        .map_err(|e| CacheWarmError::DatabaseError(e.to_string()))?;
+
+    let count = vaults.len();
+    for vault in vaults {
+        state.cache.set_vault(vault).await;
+    }
+
+    let stats = state
+        .database
+        .get_tvl_stats()
+        .await
+        .map_err(|e| CacheWarmError::DatabaseError(e.to_string()))?;
+    state.cache.set_tvl_stats(stats).await;
+
+    tracing::debug!("Warmed cache with {} of up to {} vaults", count, limit);
+    Ok(count)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheWarmError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
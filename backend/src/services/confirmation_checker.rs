@@ -0,0 +1,249 @@
+//! Settles transactions the API recorded as `pending`
+//!
+//! The event listener confirms most of them as their events arrive. Anything still
+//! `pending` after `pending_confirmation_age_seconds` is looked up with
+//! `getSignatureStatuses` and flipped to `confirmed` or `failed` from the result.
+//! One still unsettled after `pending_stuck_alert_seconds` raises a single
+//! `transaction_stuck` alert.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use actix_web::web::Data;
+use shared::{TransactionRecord, TransactionStatus};
+use solana_sdk::signature::Signature;
+use tokio::time;
+
+use crate::services::{history_commitment, rpc_retry, AppState};
+use crate::websocket::broadcast_alert;
+
+/// Most signatures `getSignatureStatuses` accepts per call
+const STATUS_BATCH_SIZE: i64 = 256;
+
+/// A signature the cluster still doesn't know after this long will never land
+const PENDING_EXPIRY: chrono::Duration = chrono::Duration::hours(1);
+
+pub async fn run_confirmation_checker(state: Data<AppState>) {
+    let interval_secs = state.config.confirmation_check_interval_seconds.max(1);
+    let mut interval = time::interval(Duration::from_secs(interval_secs));
+
+    tracing::info!("Confirmation checker started (interval: {}s)", interval_secs);
+
+    loop {
+        interval.tick().await;
+
+        match check_pending_transactions(&state).await {
+            Ok(settled) if settled > 0 => {
+                tracing::info!("Settled {} pending transactions", settled)
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Confirmation check error: {}", e),
+        }
+    }
+}
+
+/// Look up stale `pending` transactions on-chain and settle the ones that resolved
+///
+/// Pages through every stale transaction, [`STATUS_BATCH_SIZE`] signatures per RPC call.
+/// Errored or unparsable signatures, and ones still unknown after [`PENDING_EXPIRY`], become
+/// `failed` and raise a `transaction_failed` alert since their balance change was
+/// applied optimistically. Returns the number of transactions settled.
+pub async fn check_pending_transactions(
+    state: &AppState,
+) -> Result<usize, ConfirmationCheckerError> {
+    let now = chrono::Utc::now();
+    let older_than =
+        now - chrono::Duration::seconds(state.config.pending_confirmation_age_seconds);
+
+    let mut settled = 0;
+    let mut after: Option<(chrono::DateTime<chrono::Utc>, String)> = None;
+    loop {
+        let pending = state
+            .database
+            .get_stale_pending_transactions(
+                older_than,
+                after.as_ref().map(|(created_at, sig)| (*created_at, sig.as_str())),
+                STATUS_BATCH_SIZE,
+            )
+            .await
+            .map_err(|e| ConfirmationCheckerError::DatabaseError(e.to_string()))?;
+
+        settled += settle_batch(state, &pending, now).await?;
+
+        match pending.last() {
+            Some(last) if pending.len() as i64 == STATUS_BATCH_SIZE => {
+                after = Some((last.created_at, last.tx_signature.clone()));
+            }
+            _ => return Ok(settled),
+        }
+    }
+}
+
+/// Settle one page of `pending` transactions from a single `getSignatureStatuses` call
+async fn settle_batch(
+    state: &AppState,
+    pending: &[TransactionRecord],
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<usize, ConfirmationCheckerError> {
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let signatures: Vec<Option<Signature>> = pending
+        .iter()
+        .map(|tx| Signature::from_str(&tx.tx_signature).ok())
+        .collect();
+    let lookup: Vec<Signature> = signatures.iter().flatten().copied().collect();
+
+    let mut statuses = if lookup.is_empty() {
+        Vec::new()
+    } else {
+        rpc_retry("get_signature_statuses", || {
+            state.solana_client.get_signature_statuses_with_history(&lookup)
+        })
+        .await
+        .map_err(|e| ConfirmationCheckerError::RpcError(e.to_string()))?
+        .value
+    }
+    .into_iter();
+
+    let commitment = history_commitment(&state.solana_client);
+    let mut settled = 0;
+
+    for (tx, signature) in pending.iter().zip(&signatures) {
+        let status = match signature {
+            Some(_) => statuses.next().flatten(),
+            None => None,
+        };
+
+        let (new_status, slot) = match status {
+            Some(status) if status.err.is_some() => (TransactionStatus::Failed, Some(status.slot)),
+            Some(status) if status.satisfies_commitment(commitment) => {
+                (TransactionStatus::Confirmed, Some(status.slot))
+            }
+            // Landed but not yet at our commitment; check again next pass
+            Some(_) => {
+                alert_if_stuck(state, tx, now).await?;
+                continue;
+            }
+            // The API validates signatures, so an unparsable one was never going to land
+            None if signature.is_none() || now - tx.created_at > PENDING_EXPIRY => {
+                (TransactionStatus::Failed, None)
+            }
+            None => {
+                alert_if_stuck(state, tx, now).await?;
+                continue;
+            }
+        };
+
+        let block_time = match slot {
+            Some(slot) => state.solana_client.get_block_time(slot).await.ok(),
+            None => None,
+        };
+
+        let resolved = state
+            .database
+            .resolve_pending_transaction(
+                &tx.tx_signature,
+                new_status.as_str(),
+                block_time,
+                slot.map(|s| s as i64),
+            )
+            .await
+            .map_err(|e| ConfirmationCheckerError::DatabaseError(e.to_string()))?;
+        if !resolved {
+            // The event listener confirmed it in the meantime
+            continue;
+        }
+        state.cache.invalidate_transaction(&tx.tx_signature).await;
+        settled += 1;
+
+        if new_status == TransactionStatus::Failed {
+            raise_failure_alert(state, tx).await;
+        }
+    }
+
+    Ok(settled)
+}
+
+/// Raise one `transaction_stuck` alert for a transaction pending past
+/// `pending_stuck_alert_seconds`
+async fn alert_if_stuck(
+    state: &AppState,
+    tx: &TransactionRecord,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), ConfirmationCheckerError> {
+    let threshold = state.config.pending_stuck_alert_seconds;
+    if threshold == 0 || now - tx.created_at < chrono::Duration::seconds(threshold) {
+        return Ok(());
+    }
+
+    let already_alerted = state
+        .database
+        .has_transaction_alert(&tx.tx_signature, "transaction_stuck")
+        .await
+        .map_err(|e| ConfirmationCheckerError::DatabaseError(e.to_string()))?;
+    if already_alerted {
+        return Ok(());
+    }
+
+    let message = format!(
+        "{} of {} ({}) has been pending for {} minutes",
+        tx.tx_type,
+        tx.amount,
+        tx.tx_signature,
+        (now - tx.created_at).num_minutes()
+    );
+    tracing::warn!("Vault {}: {}", tx.vault_pubkey, message);
+
+    let details = serde_json::json!({ "tx_signature": tx.tx_signature });
+    state
+        .database
+        .create_alert(
+            "transaction_stuck",
+            "warning",
+            Some(&tx.vault_pubkey),
+            &message,
+            Some(details),
+        )
+        .await
+        .map_err(|e| ConfirmationCheckerError::DatabaseError(e.to_string()))?;
+    broadcast_alert("transaction_stuck", "warning", Some(&tx.vault_pubkey), &message).await;
+
+    Ok(())
+}
+
+async fn raise_failure_alert(state: &AppState, tx: &TransactionRecord) {
+    let message = format!(
+        "{} of {} ({}) failed on-chain after being applied",
+        tx.tx_type, tx.amount, tx.tx_signature
+    );
+    tracing::warn!("Vault {}: {}", tx.vault_pubkey, message);
+
+    match state
+        .database
+        .create_alert("transaction_failed", "warning", Some(&tx.vault_pubkey), &message, None)
+        .await
+    {
+        Ok(_) => {
+            broadcast_alert("transaction_failed", "warning", Some(&tx.vault_pubkey), &message)
+                .await;
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to persist transaction failure alert for {}: {}",
+                tx.tx_signature,
+                e
+            );
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfirmationCheckerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("RPC error: {0}")]
+    RpcError(String),
+}
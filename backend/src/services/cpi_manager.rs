@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use solana_sdk::{message::{AccountMeta, Instruction}, pubkey::Pubkey, transaction::Transaction};
 
-use crate::services::AppState;
+use crate::services::{anchor_discriminator, program_config_address, AppState, VaultManager};
 
 pub struct CpiManager;
 
@@ -20,12 +20,14 @@ impl CpiManager{
     let vault_pk = Pubkey::from_str(vault_pubkey).map_err(|e| CPIError::InvalidPubkey(e.to_string()))?;
     let (vault_authority_pda , _bump) = Pubkey::find_program_address(
       &[b"vault_authority" , vault_pk.as_ref()], &state.program_id);
+    let nonce = VaultManager::fetch_vault_nonce(state, &vault_pk).await.map_err(|e| CPIError::RpcError(e.to_string()))?;
     let lock_ix = build_lock_instruction(
       &state.program_id, 
       &vault_pk, 
       &vault_authority_pda, 
       &authority, 
-      amount
+      amount,
+      nonce
     )?;
 
     let recent_blockhash = state.solana_client.get_latest_blockhash().await.map_err(|e| CPIError::RpcError(e.to_string()))?;
@@ -52,12 +54,14 @@ impl CpiManager{
 
     let (vault_authority_pda , _bump) = Pubkey::find_program_address(
       &[b"vault_authority" , vault_pk.as_ref()], &state.program_id);
+    let nonce = VaultManager::fetch_vault_nonce(state, &vault_pk).await.map_err(|e| CPIError::RpcError(e.to_string()))?;
     let unlock_ix = build_unlock_instruction(
       &state.program_id, 
       &vault_pk, 
       &vault_authority_pda, 
       &authority, 
-      amount
+      amount,
+      nonce
     )?;
 
     let recent_blockhash = state.solana_client.get_latest_blockhash().await.map_err(|e| CPIError::RpcError(e.to_string()))?;
@@ -103,16 +107,20 @@ impl CpiManager{
         &[b"vault_authority", from_vault_pk.as_ref()],
         &state.program_id,
     );
+    let nonce = VaultManager::fetch_vault_nonce(state, &from_vault_pk).await.map_err(|e| CPIError::RpcError(e.to_string()))?;
 
     let transfer_ix = build_transfer_instruction(
-      &state.program_id, 
-      &from_vault_pk, 
-      &to_vault_pk,
-      &from_token_account, 
-      &to_token_account, 
-      &vault_authority_pda, 
-      authority, 
-      amount
+      &state.program_id,
+      &TransferAccounts {
+        from_vault: &from_vault_pk,
+        to_vault: &to_vault_pk,
+        from_token_account: &from_token_account,
+        to_token_account: &to_token_account,
+        vault_authority: &vault_authority_pda,
+        authority_program: authority,
+      },
+      amount,
+      nonce
     )?;
 
     let transaction = Transaction::new_with_payer(
@@ -143,17 +151,20 @@ impl CpiManager{
     }
 }
 
-fn build_lock_instruction(
+/// Accounts in `LockCollateral` order
+pub(crate) fn build_lock_instruction(
   program_id : &Pubkey,
   vault: &Pubkey,
   vault_authority: &Pubkey,
   authority_program: &Pubkey,
   amount : u64,
+  nonce : u64,
 ) -> Result<Instruction , CPIError>{
-  let discriminator: [u8; 8] = [0,1,2,3,4,5,6,7];
-  let mut data = Vec::with_capacity(16);
+  let discriminator = anchor_discriminator("lock_collateral");
+  let mut data = Vec::with_capacity(24);
   data.extend_from_slice(&discriminator);
   data.extend_from_slice(&amount.to_le_bytes());
+  data.extend_from_slice(&nonce.to_le_bytes());
 
   Ok(
     Instruction { 
@@ -161,24 +172,28 @@ fn build_lock_instruction(
       accounts: vec![
         AccountMeta::new(*vault, false),
         AccountMeta::new(*vault_authority,false),
-        AccountMeta::new(*authority_program, false),
+        AccountMeta::new_readonly(*authority_program, true),
+        AccountMeta::new_readonly(program_config_address(program_id), false),
       ], 
       data
     }
   ) 
 }
 
-fn build_unlock_instruction(
+/// Accounts in `UnLockCollateral` order
+pub(crate) fn build_unlock_instruction(
   program_id : &Pubkey,
   vault: &Pubkey,
   vault_authority: &Pubkey,
   authority_program: &Pubkey,
-  amount : u64
+  amount : u64,
+  nonce : u64
 ) -> Result<Instruction , CPIError>{
-  let discriminator: [u8; 8] = [0,1,2,3,4,5,6,8];
-  let mut data = Vec::with_capacity(16);
+  let discriminator = anchor_discriminator("unlock_collateral");
+  let mut data = Vec::with_capacity(24);
   data.extend_from_slice(&discriminator);
   data.extend_from_slice(&amount.to_le_bytes());
+  data.extend_from_slice(&nonce.to_le_bytes());
 
   Ok(
     Instruction { 
@@ -186,40 +201,53 @@ fn build_unlock_instruction(
       accounts: vec![
         AccountMeta::new(*vault, false),
         AccountMeta::new(*vault_authority, false),
-        AccountMeta::new_readonly(*authority_program, false)
+        AccountMeta::new_readonly(*authority_program, true),
+        AccountMeta::new_readonly(program_config_address(program_id), false),
       ], 
       data
     }
   )
 }
 
-fn build_transfer_instruction(
+/// Caller-supplied accounts of `TransferCollateral`; the token program and
+/// program config are filled in by `build_transfer_instruction`
+// Outside tests only `CpiManager::transfer_collateral_vault` builds one, and nothing calls it yet
+#[allow(dead_code)]
+pub(crate) struct TransferAccounts<'a> {
+  pub from_vault : &'a Pubkey,
+  pub to_vault : &'a Pubkey,
+  pub from_token_account : &'a Pubkey,
+  pub to_token_account : &'a Pubkey,
+  pub vault_authority : &'a Pubkey,
+  pub authority_program : &'a Pubkey,
+}
+
+/// Accounts in `TransferCollateral` order
+pub(crate) fn build_transfer_instruction(
   program_id : &Pubkey,
-  from_vault : &Pubkey,
-  to_vault : &Pubkey,
-  from_token_account : &Pubkey,
-  to_token_account : &Pubkey,
-  vault_authority: &Pubkey,
-  authority_program: &Pubkey,
-  amount : u64
+  accounts : &TransferAccounts,
+  amount : u64,
+  nonce : u64
 ) -> Result<Instruction , CPIError>{
-  let discriminator = [0,1,2,3,4,5,6,9];
+  let discriminator = anchor_discriminator("transfer_collateral");
 
-  let mut data = Vec::with_capacity(16);
+  let mut data = Vec::with_capacity(24);
   data.extend_from_slice(&discriminator);
   data.extend_from_slice(&amount.to_le_bytes());
+  data.extend_from_slice(&nonce.to_le_bytes());
 
   Ok(
     Instruction { 
       program_id: *program_id,
       accounts: vec![
-        AccountMeta::new(*from_vault, false),
-        AccountMeta::new(*to_vault, false),
-        AccountMeta::new(*from_token_account, false),
-        AccountMeta::new(*to_token_account, false),
-        AccountMeta::new(*vault_authority, false),
-        AccountMeta::new_readonly(*authority_program, false),
-        AccountMeta::new_readonly(spl_token::id(), false)
+        AccountMeta::new(*accounts.from_vault, false),
+        AccountMeta::new(*accounts.to_vault, false),
+        AccountMeta::new(*accounts.from_token_account, false),
+        AccountMeta::new(*accounts.to_token_account, false),
+        AccountMeta::new_readonly(*accounts.vault_authority, false),
+        AccountMeta::new_readonly(*accounts.authority_program, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(program_config_address(program_id), false),
       ], 
       data 
     }
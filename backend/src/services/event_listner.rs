@@ -15,20 +15,36 @@
 //! - LockEvent
 //! - UnlockEvent
 //! - TransferEvent
+//! - LiquidationEvent
+//! - OwnershipTransferredEvent
+//! - VaultClosedEvent
+//! - PositionOpenedEvent
+//! - PositionClosedEvent
+//! - AuthorityAddedEvent
 
 use std::collections::HashMap;
 use std::str::FromStr;
 use std::time::Duration;
 use actix_web::web::Data;
 use borsh::BorshDeserialize;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Signature;
 use tokio::time;
 
-use crate::services::AppState;
+use shared::{FailedEvent, ReplaySummary, TransactionStatus, VaultPosition};
+
+use crate::config::Config;
+use crate::database::{BalanceUpdate, Database, TransactionEntry};
+use crate::services::{
+    history_commitment, retry_jitter, webhook_dispatcher, AppState, BackgroundService,
+};
 use crate::websocket::{
     broadcast_balance_update, broadcast_deposit, broadcast_lock, 
-    broadcast_unlock, broadcast_withdrawal, broadcast_tvl_update,
+    broadcast_alert, broadcast_unlock, broadcast_withdrawal, broadcast_tvl_update,
+    broadcast_vault_closed,
 };
 
 // ============================================================================
@@ -46,8 +62,10 @@ pub struct DepositEvent {
     pub user: [u8; 32],
     pub vault: [u8; 32],
     pub amount: u64,
-    pub new_balance: u64,
+    pub new_total_balance: u64,
+    pub new_available_balance: u64,
     pub timestamp: i64,
+    pub funder: [u8; 32],
 }
 
 impl DepositEvent {
@@ -57,6 +75,13 @@ impl DepositEvent {
     pub fn vault_pubkey(&self) -> String {
         pubkey_to_string(&self.vault)
     }
+    pub fn funder_pubkey(&self) -> String {
+        pubkey_to_string(&self.funder)
+    }
+    /// Whether someone other than the vault owner paid for the deposit (`deposit_for`)
+    pub fn is_sponsored(&self) -> bool {
+        self.funder != self.user
+    }
 }
 
 /// Withdrawal event emitted by the on-chain program
@@ -65,8 +90,10 @@ pub struct WithdrawEvent {
     pub user: [u8; 32],
     pub vault: [u8; 32],
     pub amount: u64,
-    pub new_balance: u64,
+    pub new_total_balance: u64,
+    pub new_available_balance: u64,
     pub timestamp: i64,
+    pub destination: [u8; 32],
 }
 
 impl WithdrawEvent {
@@ -76,6 +103,10 @@ impl WithdrawEvent {
     pub fn vault_pubkey(&self) -> String {
         pubkey_to_string(&self.vault)
     }
+    /// Token account that received the withdrawal
+    pub fn destination_pubkey(&self) -> String {
+        pubkey_to_string(&self.destination)
+    }
 }
 
 /// Lock collateral event
@@ -149,6 +180,124 @@ impl VaultInitializedEvent {
     }
 }
 
+/// Liquidation of locked collateral by an authorized program
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct LiquidationEvent {
+    pub vault: [u8; 32],
+    pub liquidator: [u8; 32],
+    pub amount: u64,
+    pub remaining_locked: u64,
+    pub timestamp: i64,
+}
+
+impl LiquidationEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+    pub fn liquidator_pubkey(&self) -> String {
+        pubkey_to_string(&self.liquidator)
+    }
+}
+
+/// Vault handed over to a new owner wallet
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct OwnershipTransferredEvent {
+    pub vault: [u8; 32],
+    pub old_owner: [u8; 32],
+    pub new_owner: [u8; 32],
+    pub timestamp: i64,
+}
+
+impl OwnershipTransferredEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+    pub fn old_owner_pubkey(&self) -> String {
+        pubkey_to_string(&self.old_owner)
+    }
+    pub fn new_owner_pubkey(&self) -> String {
+        pubkey_to_string(&self.new_owner)
+    }
+}
+
+/// Vault closed on-chain and its rent returned to the owner
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct VaultClosedEvent {
+    pub vault: [u8; 32],
+    pub owner: [u8; 32],
+    pub timestamp: i64,
+}
+
+impl VaultClosedEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+    pub fn owner_pubkey(&self) -> String {
+        pubkey_to_string(&self.owner)
+    }
+}
+
+/// Collateral locked against a new position by an authorized program
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct PositionOpenedEvent {
+    pub vault: [u8; 32],
+    pub position_id: u64,
+    pub authority: [u8; 32],
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+impl PositionOpenedEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+    pub fn authority_pubkey(&self) -> String {
+        pubkey_to_string(&self.authority)
+    }
+}
+
+/// Position closed, its collateral free to unlock
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct PositionClosedEvent {
+    pub vault: [u8; 32],
+    pub position_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+impl PositionClosedEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+}
+
+/// Program authorized to lock and unlock a vault's collateral
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AuthorityAddedEvent {
+    pub vault: [u8; 32],
+    pub program_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+impl AuthorityAddedEvent {
+    pub fn vault_pubkey(&self) -> String {
+        pubkey_to_string(&self.vault)
+    }
+    pub fn program_pubkey(&self) -> String {
+        pubkey_to_string(&self.program_id)
+    }
+}
+
+/// Anchor event discriminator: first 8 bytes of `sha256("event:<Name>")`
+pub fn event_discriminator(event_name: &str) -> [u8; 8] {
+    use sha2::{Digest, Sha256};
+
+    let hash = Sha256::digest(format!("event:{}", event_name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
 /// All possible vault events
 #[derive(Debug, Clone)]
 pub enum VaultEvent {
@@ -158,6 +307,39 @@ pub enum VaultEvent {
     Unlock(UnlockEvent),
     Transfer(TransferEvent),
     VaultInitialized(VaultInitializedEvent),
+    Liquidation(LiquidationEvent),
+    OwnershipTransferred(OwnershipTransferredEvent),
+    VaultClosed(VaultClosedEvent),
+    PositionOpened(PositionOpenedEvent),
+    PositionClosed(PositionClosedEvent),
+    AuthorityAdded(AuthorityAddedEvent),
+}
+
+impl VaultEvent {
+    /// Event name as emitted by the program
+    pub fn name(&self) -> &'static str {
+        match self {
+            VaultEvent::Deposit(_) => "DepositEvent",
+            VaultEvent::Withdraw(_) => "WithdrawEvent",
+            VaultEvent::Lock(_) => "LockEvent",
+            VaultEvent::Unlock(_) => "UnlockEvent",
+            VaultEvent::Transfer(_) => "TransferEvent",
+            VaultEvent::VaultInitialized(_) => "VaultInitializedEvent",
+            VaultEvent::Liquidation(_) => "LiquidationEvent",
+            VaultEvent::OwnershipTransferred(_) => "OwnershipTransferredEvent",
+            VaultEvent::VaultClosed(_) => "VaultClosedEvent",
+            VaultEvent::PositionOpened(_) => "PositionOpenedEvent",
+            VaultEvent::PositionClosed(_) => "PositionClosedEvent",
+            VaultEvent::AuthorityAdded(_) => "AuthorityAddedEvent",
+        }
+    }
+}
+
+/// Vault events from one fetched transaction, with where it landed
+struct ParsedTransaction {
+    events: Vec<(VaultEvent, Vec<u8>)>,
+    slot: u64,
+    block_time: Option<i64>,
 }
 
 // ============================================================================
@@ -176,6 +358,8 @@ pub struct EventListenerConfig {
     pub max_retries: u32,
     /// Retry delay in milliseconds
     pub retry_delay_ms: u64,
+    /// Signatures requested per `getSignaturesForAddress` page (the RPC caps it at 1000)
+    pub signatures_page_size: usize,
 }
 
 impl Default for EventListenerConfig {
@@ -186,6 +370,19 @@ impl Default for EventListenerConfig {
             use_websocket: false,    // Use polling by default (more reliable)
             max_retries: 3,
             retry_delay_ms: 500,
+            signatures_page_size: 1000,
+        }
+    }
+}
+
+impl EventListenerConfig {
+    /// Defaults with the polling and retry settings taken from `Config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            poll_interval_ms: config.event_poll_interval_ms,
+            use_websocket: config.event_use_websocket,
+            max_retries: config.event_max_retries,
+            ..Self::default()
         }
     }
 }
@@ -196,7 +393,7 @@ impl Default for EventListenerConfig {
 
 pub struct EventListener {
     state: Data<AppState>,
-    config: EventListenerConfig,
+    pub(crate) config: EventListenerConfig,
     processed_signatures: HashMap<String, i64>, // signature -> timestamp
 }
 
@@ -217,6 +414,9 @@ impl EventListener {
             self.config.poll_interval_ms,
             self.state.program_id
         );
+        if self.config.use_websocket {
+            tracing::warn!("Log subscriptions are not supported yet; falling back to polling");
+        }
 
         // Test RPC connection first
         match self.test_rpc_connection().await {
@@ -253,6 +453,7 @@ impl EventListener {
 
         loop {
             interval.tick().await;
+            self.state.heartbeats.beat(BackgroundService::EventListener);
 
             match self.poll_and_process_events().await {
                 Ok(events_processed) => {
@@ -287,82 +488,84 @@ impl EventListener {
 
     /// Poll for new program logs and process events
     /// Returns the number of events processed
-    async fn poll_and_process_events(&mut self) -> Result<usize, EventListenerError> {
+    ///
+    /// Pages back to the persisted cursor, so every signature since the last one handled
+    /// is processed however many arrived. Without a cursor only the newest page is read.
+    /// Signatures are handled oldest first and the cursor stops at the first one that
+    /// fails, so the next cycle picks up from there.
+    pub(crate) async fn poll_and_process_events(&mut self) -> Result<usize, EventListenerError> {
         let program_id = self.state.program_id;
+        let program_key = program_id.to_string();
 
-        // Get recent signatures for the program
-        let signatures = match self.state.solana_client
-            .get_signatures_for_address(&program_id)
-            .await 
+        let cursor = self.state.database
+            .get_listener_cursor(&program_key)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        let until = cursor
+            .as_deref()
+            .map(Signature::from_str)
+            .transpose()
+            .map_err(|e| EventListenerError::ParseError(e.to_string()))?;
+
+        // With no cursor there's no telling how far back to go, so start from the newest page
+        let mut remaining = self.config.signatures_page_size;
+        let signatures = match self
+            .fetch_signatures(&program_id, until, |_| {
+                if until.is_some() {
+                    return true;
+                }
+                let keep = remaining > 0;
+                remaining = remaining.saturating_sub(1);
+                keep
+            })
+            .await
         {
             Ok(sigs) => sigs,
-            Err(e) => {
+            Err(EventListenerError::RpcError(err_str)) => {
                 // Check if it's just "no signatures found" (not an error)
-                let err_str = e.to_string();
                 if err_str.contains("AccountNotFound") || err_str.contains("not found") {
                     tracing::trace!("No signatures found for program {} (this is normal for new programs)", program_id);
                     return Ok(0);
                 }
                 return Err(EventListenerError::RpcError(err_str));
             }
+            Err(e) => return Err(e),
         };
 
-        let mut new_events = Vec::new();
         let mut processed_count = 0;
-
-        for sig_info in signatures.iter().take(50) {  // Process last 50 transactions
-            let signature_str = sig_info.signature.clone();
-
-            // Skip if already processed
-            if self.processed_signatures.contains_key(&signature_str) {
-                continue;
-            }
-
-            // Skip failed transactions
-            if sig_info.err.is_some() {
-                self.processed_signatures.insert(signature_str.clone(), chrono::Utc::now().timestamp());
-                continue;
-            }
-
-            // Parse the signature
-            let signature = match Signature::from_str(&signature_str) {
-                Ok(sig) => sig,
-                Err(e) => {
-                    tracing::warn!("Failed to parse signature {}: {}", signature_str, e);
-                    self.processed_signatures.insert(signature_str, chrono::Utc::now().timestamp());
-                    continue;
-                }
-            };
-
-            // Fetch transaction details
-            match self.fetch_and_parse_transaction(&signature).await {
-                Ok(Some(events)) => {
-                    new_events.extend(events);
-                }
-                Ok(None) => {
-                    // No events in this transaction - that's fine
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to parse transaction {}: {}", signature_str, e);
+        let mut last_handled = None;
+
+        for sig_info in signatures.into_iter().rev() {
+            let signature_str = sig_info.signature;
+
+            // Failed transactions and ones already handled have nothing to apply
+            if sig_info.err.is_none() && !self.processed_signatures.contains_key(&signature_str) {
+                match self.process_signature(&signature_str).await {
+                    Ok(count) => processed_count += count,
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to process transaction {}, will refetch: {}",
+                            signature_str,
+                            e
+                        );
+                        break;
+                    }
                 }
             }
 
-            // Mark as processed
-            self.processed_signatures.insert(signature_str, chrono::Utc::now().timestamp());
+            self.processed_signatures.insert(signature_str.clone(), chrono::Utc::now().timestamp());
+            last_handled = Some(signature_str);
         }
 
-        // Process all new events
-        for (event, tx_signature) in new_events {
-            match self.process_event(event.clone(), &tx_signature).await {
-                Ok(_) => {
-                    processed_count += 1;
-                }
-                Err(e) => {
-                    tracing::error!("Failed to process event {:?}: {}", event, e);
-                }
-            }
+        if let Some(signature) = last_handled {
+            self.state.database
+                .set_listener_cursor(&program_key, &signature)
+                .await
+                .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
         }
 
+        processed_count += self.retry_failed_events().await?;
+
         // Cleanup old processed signatures (keep last hour)
         let cutoff = chrono::Utc::now().timestamp() - 3600;
         self.processed_signatures.retain(|_, ts| *ts > cutoff);
@@ -370,19 +573,92 @@ impl EventListener {
         Ok(processed_count)
     }
 
+    /// Fetch a transaction and apply its events; returns the events applied
+    ///
+    /// Errors only when the transaction could not be fetched or its failed events
+    /// could not be dead-lettered, i.e. when it must be fetched again.
+    async fn process_signature(&self, signature_str: &str) -> Result<usize, EventListenerError> {
+        let signature = match Signature::from_str(signature_str) {
+            Ok(sig) => sig,
+            Err(e) => {
+                tracing::warn!("Failed to parse signature {}: {}", signature_str, e);
+                return Ok(0);
+            }
+        };
+
+        let Some(parsed) = self.fetch_and_parse_transaction(&signature).await? else {
+            // No events in this transaction - that's fine
+            return Ok(0);
+        };
+
+        let processed = self.process_transaction_events(signature_str, parsed.events).await?;
+        // Stamp where the transaction landed on the row its events confirmed
+        self.confirm_transaction(signature_str, parsed.block_time, Some(parsed.slot as i64))
+            .await?;
+        Ok(processed)
+    }
+
+    /// Signatures touching `address` newer than `until`, newest first
+    ///
+    /// Pages back with `before` until the history runs out, `until` is reached, or
+    /// `keep` rejects a signature; that signature and everything older is left out.
+    async fn fetch_signatures<F>(
+        &self,
+        address: &Pubkey,
+        until: Option<Signature>,
+        mut keep: F,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, EventListenerError>
+    where
+        F: FnMut(&RpcConfirmedTransactionStatusWithSignature) -> bool,
+    {
+        let mut signatures = Vec::new();
+        let mut before = None;
+
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(self.config.signatures_page_size),
+                commitment: Some(history_commitment(&self.state.solana_client)),
+            };
+            let page = self.state.solana_client
+                .get_signatures_for_address_with_config(address, config)
+                .await
+                .map_err(|e| EventListenerError::RpcError(e.to_string()))?;
+
+            let last_page = page.len() < self.config.signatures_page_size;
+            for sig_info in page {
+                if !keep(&sig_info) {
+                    return Ok(signatures);
+                }
+                before = Some(
+                    Signature::from_str(&sig_info.signature)
+                        .map_err(|e| EventListenerError::ParseError(e.to_string()))?,
+                );
+                signatures.push(sig_info);
+            }
+
+            if last_page {
+                return Ok(signatures);
+            }
+        }
+    }
+
     /// Fetch and parse a transaction for events
     async fn fetch_and_parse_transaction(
         &self,
         signature: &Signature,
-    ) -> Result<Option<Vec<(VaultEvent, String)>>, EventListenerError> {
+    ) -> Result<Option<ParsedTransaction>, EventListenerError> {
+        let config = RpcTransactionConfig {
+            encoding: Some(solana_transaction_status::UiTransactionEncoding::Json),
+            commitment: Some(history_commitment(&self.state.solana_client)),
+            max_supported_transaction_version: None,
+        };
         let tx = self.state.solana_client
-            .get_transaction(
-                signature,
-                solana_transaction_status::UiTransactionEncoding::Json,
-            ).await
+            .get_transaction_with_config(signature, config)
+            .await
             .map_err(|e| EventListenerError::RpcError(e.to_string()))?;
 
-        let signature_str = signature.to_string();
         let mut events = Vec::new();
 
         // Parse transaction logs for events
@@ -395,7 +671,7 @@ impl EventListener {
                         
                         if let Ok(decoded) = bs58::decode(data).into_vec() {
                             if let Some(event) = self.parse_event_data(&decoded) {
-                                events.push((event, signature_str.clone()));
+                                events.push((event, decoded));
                             }
                         }
                     }
@@ -406,7 +682,157 @@ impl EventListener {
         if events.is_empty() {
             Ok(None)
         } else {
-            Ok(Some(events))
+            Ok(Some(ParsedTransaction { events, slot: tx.slot, block_time: tx.block_time }))
+        }
+    }
+
+    /// Re-apply the events of every transaction touching `vault` since `from_slot`
+    ///
+    /// Catch-up for events the listener missed while it was down. Transactions are
+    /// replayed oldest first so balances end at their latest on-chain values, and ones
+    /// already recorded are skipped, so replaying the same range twice is a no-op.
+    pub async fn replay_vault(
+        &self,
+        vault: &Pubkey,
+        from_slot: u64,
+    ) -> Result<ReplaySummary, EventListenerError> {
+        let signatures: Vec<String> = self
+            .fetch_signatures(vault, None, |sig_info| sig_info.slot >= from_slot)
+            .await?
+            .into_iter()
+            .filter(|sig_info| sig_info.err.is_none())
+            .map(|sig_info| sig_info.signature)
+            .collect();
+
+        let mut summary = ReplaySummary {
+            vault_pubkey: vault.to_string(),
+            from_slot,
+            signatures_found: signatures.len(),
+            signatures_skipped: 0,
+            events_applied: 0,
+        };
+
+        for tx_signature in signatures.into_iter().rev() {
+            let recorded = self.state.database
+                .get_transaction_by_signature(&tx_signature)
+                .await
+                .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+            if recorded.is_some() {
+                summary.signatures_skipped += 1;
+                continue;
+            }
+
+            summary.events_applied += self.process_signature(&tx_signature).await?;
+        }
+
+        tracing::info!(
+            "Replayed vault {} from slot {}: {} applied, {} of {} transactions already recorded",
+            summary.vault_pubkey,
+            from_slot,
+            summary.events_applied,
+            summary.signatures_skipped,
+            summary.signatures_found
+        );
+
+        Ok(summary)
+    }
+
+    /// Apply each event of a transaction, dead-lettering the ones that fail
+    ///
+    /// Errors only if a failed event could not be dead-lettered either, in which
+    /// case the signature must be fetched again. Returns the events applied.
+    pub(crate) async fn process_transaction_events(
+        &self,
+        tx_signature: &str,
+        events: Vec<(VaultEvent, Vec<u8>)>,
+    ) -> Result<usize, EventListenerError> {
+        let mut processed = 0;
+
+        for (event, data) in events {
+            let event_type = event.name();
+            match self.process_event(event, tx_signature).await {
+                Ok(_) => processed += 1,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to process {} from {}, dead-lettering: {}",
+                        event_type,
+                        tx_signature,
+                        e
+                    );
+                    self.state.database
+                        .record_failed_event(tx_signature, event_type, &data, &e.to_string())
+                        .await
+                        .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+                }
+            }
+        }
+
+        Ok(processed)
+    }
+
+    /// Reprocess dead-lettered events, abandoning and alerting after `max_retries`
+    ///
+    /// Returns the number of events that landed on this pass.
+    pub(crate) async fn retry_failed_events(&self) -> Result<usize, EventListenerError> {
+        let failed = self.state.database
+            .get_pending_failed_events(50)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        let mut recovered = 0;
+        for entry in failed {
+            let result = match self.parse_event_data(&entry.event_data) {
+                Some(event) => self.process_event(event, &entry.tx_signature).await,
+                None => Err(EventListenerError::ParseError(format!(
+                    "Stored {} could not be parsed",
+                    entry.event_type
+                ))),
+            };
+
+            match result {
+                Ok(_) => {
+                    tracing::info!(
+                        " Retried {} from {} after {} failed attempts",
+                        entry.event_type,
+                        entry.tx_signature,
+                        entry.retry_count + 1
+                    );
+                    self.state.database
+                        .resolve_failed_event(entry.id)
+                        .await
+                        .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+                    recovered += 1;
+                }
+                Err(e) => {
+                    let abandon = entry.retry_count + 1 >= self.config.max_retries as i32;
+                    self.state.database
+                        .record_failed_event_retry(entry.id, &e.to_string(), abandon)
+                        .await
+                        .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+                    if abandon {
+                        self.alert_abandoned_event(&entry, &e).await;
+                    }
+                }
+            }
+        }
+
+        Ok(recovered)
+    }
+
+    async fn alert_abandoned_event(&self, entry: &FailedEvent, error: &EventListenerError) {
+        let message = format!(
+            "Gave up on {} from {} after {} retries: {}",
+            entry.event_type, entry.tx_signature, self.config.max_retries, error
+        );
+        tracing::error!("{}", message);
+
+        match self.state.database
+            .create_alert("event_processing_failed", "critical", None, &message, None)
+            .await
+        {
+            Ok(_) => broadcast_alert("event_processing_failed", "critical", None, &message).await,
+            Err(e) => tracing::error!("Failed to persist event failure alert: {}", e),
         }
     }
 
@@ -419,17 +845,59 @@ impl EventListener {
         // Skip the 8-byte discriminator
         let event_data = &data[8..];
 
-        // Try parsing each event type
-        // Note: In production, you should check discriminators first
-        
-        if let Ok(event) = DepositEvent::try_from_slice(event_data) {
-            return Some(VaultEvent::Deposit(event));
+        // Same size as other events, so match it by discriminator
+        if data[..8] == event_discriminator("LiquidationEvent") {
+            return LiquidationEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::Liquidation);
+        }
+
+        if data[..8] == event_discriminator("OwnershipTransferredEvent") {
+            return OwnershipTransferredEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::OwnershipTransferred);
+        }
+
+        if data[..8] == event_discriminator("VaultClosedEvent") {
+            return VaultClosedEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::VaultClosed);
+        }
+
+        if data[..8] == event_discriminator("PositionOpenedEvent") {
+            return PositionOpenedEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::PositionOpened);
+        }
+
+        if data[..8] == event_discriminator("PositionClosedEvent") {
+            return PositionClosedEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::PositionClosed);
+        }
+
+        if data[..8] == event_discriminator("AuthorityAddedEvent") {
+            return AuthorityAddedEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::AuthorityAdded);
         }
 
-        if let Ok(event) = WithdrawEvent::try_from_slice(event_data) {
-            return Some(VaultEvent::Withdraw(event));
+        // Deposit and withdraw share a layout, so only the discriminator tells them apart
+        if data[..8] == event_discriminator("DepositEvent") {
+            return DepositEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::Deposit);
         }
 
+        if data[..8] == event_discriminator("WithdrawEvent") {
+            return WithdrawEvent::try_from_slice(event_data)
+                .ok()
+                .map(VaultEvent::Withdraw);
+        }
+
+        // Try parsing each event type
+        // Note: In production, you should check discriminators first
+
         if let Ok(event) = LockEvent::try_from_slice(event_data) {
             return Some(VaultEvent::Lock(event));
         }
@@ -476,51 +944,80 @@ impl EventListener {
             VaultEvent::VaultInitialized(e) => {
                 self.handle_vault_initialized_event(e, tx_signature).await?;
             }
+            VaultEvent::Liquidation(e) => {
+                self.handle_liquidation_event(e, tx_signature).await?;
+            }
+            VaultEvent::OwnershipTransferred(e) => {
+                self.handle_ownership_transferred_event(e).await?;
+            }
+            VaultEvent::VaultClosed(e) => {
+                self.handle_vault_closed_event(e, tx_signature).await?;
+            }
+            VaultEvent::PositionOpened(e) => {
+                self.handle_position_opened_event(e, tx_signature).await?;
+            }
+            VaultEvent::PositionClosed(e) => {
+                self.handle_position_closed_event(e).await?;
+            }
+            VaultEvent::AuthorityAdded(e) => {
+                self.handle_authority_added_event(e, tx_signature).await?;
+            }
         }
 
         Ok(())
     }
 
     /// Handle deposit event
-    async fn handle_deposit_event(
+    ///
+    /// The locked balance is taken from the event as `total - available`, so a deposit
+    /// never overwrites collateral locked since the database copy was last synced.
+    /// Sponsored deposits record their funder in the transaction's `meta`.
+    pub(crate) async fn handle_deposit_event(
         &self,
         event: DepositEvent,
         tx_signature: &str,
     ) -> Result<(), EventListenerError> {
         let vault_pubkey = event.vault_pubkey();
         let amount = event.amount as i64;
-        let new_balance = event.new_balance as i64;
+        let new_total = event.new_total_balance as i64;
+        let new_available = event.new_available_balance as i64;
 
         tracing::info!(
-            "💰 Deposit event: vault={}, amount={}, new_balance={}",
-            vault_pubkey, amount, new_balance
+            "💰 Deposit event: vault={}, amount={}, new_total={}, new_available={}",
+            vault_pubkey, amount, new_total, new_available
         );
 
-        // Update database with on-chain values
+        // Update balances and record the transaction atomically
         self.state.database
-            .update_vault_balances(
-                &vault_pubkey,
-                new_balance,
-                0,  // Locked balance unchanged for deposits
-                Some(amount), // Add to total deposited
-                None,
+            .apply_balance_change(
+                &BalanceUpdate {
+                    vault_pubkey: &vault_pubkey,
+                    total_balance: new_total,
+                    locked_balance: new_total - new_available,
+                    total_deposited: Some(amount), // Add to total deposited
+                    total_withdrawn: None,
+                },
+                &TransactionEntry {
+                    tx_signature,
+                    tx_type: "deposit",
+                    amount,
+                    from_vault: None,
+                    to_vault: None,
+                    status: "confirmed",
+                },
             )
             .await
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
-        // Record the transaction
-        self.state.database
-            .record_transaction(
-                &vault_pubkey,
-                tx_signature,
-                "deposit",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        if event.is_sponsored() {
+            let meta = serde_json::json!({ "funder": event.funder_pubkey() });
+            self.state.database
+                .merge_transaction_meta(tx_signature, &meta)
+                .await
+                .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        }
+
+        self.confirm_transaction(tx_signature, None, None).await?;
 
         // Invalidate cache for affected vault
         self.state.cache.invalidate_vault(&vault_pubkey).await;
@@ -530,7 +1027,7 @@ impl EventListener {
             &vault_pubkey,
             amount,
             tx_signature,
-            new_balance,
+            new_total,
         ).await;
 
         // Also broadcast balance update
@@ -546,6 +1043,15 @@ impl EventListener {
                 v.available_balance,
                 v.locked_balance,
             ).await;
+
+            let data = webhook_dispatcher::balance_event_data(
+                amount,
+                tx_signature,
+                v.total_balance,
+                v.available_balance,
+                v.locked_balance,
+            );
+            webhook_dispatcher::dispatch(&self.state, "deposit", &vault_pubkey, data).await;
         }
 
         // Update TVL
@@ -556,51 +1062,58 @@ impl EventListener {
     }
 
     /// Handle withdrawal event
-    async fn handle_withdraw_event(
+    ///
+    /// The receiving token account is recorded as `destination` in the transaction's `meta`.
+    pub(crate) async fn handle_withdraw_event(
         &self,
         event: WithdrawEvent,
         tx_signature: &str,
     ) -> Result<(), EventListenerError> {
         let vault_pubkey = event.vault_pubkey();
         let amount = event.amount as i64;
-        let new_balance = event.new_balance as i64;
+        let new_total = event.new_total_balance as i64;
+        let new_available = event.new_available_balance as i64;
 
         tracing::info!(
-            "💸 Withdraw event: vault={}, amount={}, new_balance={}",
-            vault_pubkey, amount, new_balance
+            "💸 Withdraw event: vault={}, amount={}, new_total={}, new_available={}",
+            vault_pubkey, amount, new_total, new_available
         );
 
-        // Update database
+        // Update balances and record the transaction atomically
         self.state.database
-            .update_vault_balances(
-                &vault_pubkey,
-                new_balance,
-                0,
-                None,
-                Some(amount), // Add to total withdrawn
+            .apply_balance_change(
+                &BalanceUpdate {
+                    vault_pubkey: &vault_pubkey,
+                    total_balance: new_total,
+                    locked_balance: new_total - new_available,
+                    total_deposited: None,
+                    total_withdrawn: Some(amount), // Add to total withdrawn
+                },
+                &TransactionEntry {
+                    tx_signature,
+                    tx_type: "withdraw",
+                    amount,
+                    from_vault: None,
+                    to_vault: None,
+                    status: "confirmed",
+                },
             )
             .await
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
-        // Record transaction
+        let meta = serde_json::json!({ "destination": event.destination_pubkey() });
         self.state.database
-            .record_transaction(
-                &vault_pubkey,
-                tx_signature,
-                "withdraw",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
+            .merge_transaction_meta(tx_signature, &meta)
             .await
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
+        self.confirm_transaction(tx_signature, None, None).await?;
+
         // Invalidate cache
         self.state.cache.invalidate_vault(&vault_pubkey).await;
 
         // Broadcast via WebSocket
-        broadcast_withdrawal(&vault_pubkey, amount, tx_signature, new_balance).await;
+        broadcast_withdrawal(&vault_pubkey, amount, tx_signature, new_total).await;
 
         // Broadcast balance update
         let vault = self.state.database
@@ -615,6 +1128,15 @@ impl EventListener {
                 v.available_balance,
                 v.locked_balance,
             ).await;
+
+            let data = webhook_dispatcher::balance_event_data(
+                amount,
+                tx_signature,
+                v.total_balance,
+                v.available_balance,
+                v.locked_balance,
+            );
+            webhook_dispatcher::dispatch(&self.state, "withdraw", &vault_pubkey, data).await;
         }
 
         // Update TVL
@@ -647,31 +1169,29 @@ impl EventListener {
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?
             .ok_or_else(|| EventListenerError::VaultNotFound(vault_pubkey.clone()))?;
 
-        // Update database
+        // Update balances and record the transaction atomically
         self.state.database
-            .update_vault_balances(
-                &vault_pubkey,
-                vault.total_balance,
-                new_locked,
-                None,
-                None,
+            .apply_balance_change(
+                &BalanceUpdate {
+                    vault_pubkey: &vault_pubkey,
+                    total_balance: vault.total_balance,
+                    locked_balance: new_locked,
+                    total_deposited: None,
+                    total_withdrawn: None,
+                },
+                &TransactionEntry {
+                    tx_signature,
+                    tx_type: "lock",
+                    amount,
+                    from_vault: None,
+                    to_vault: None,
+                    status: "confirmed",
+                },
             )
             .await
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
-        // Record transaction
-        self.state.database
-            .record_transaction(
-                &vault_pubkey,
-                tx_signature,
-                "lock",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        self.confirm_transaction(tx_signature, None, None).await?;
 
         // Invalidate cache
         self.state.cache.invalidate_vault(&vault_pubkey).await;
@@ -679,6 +1199,15 @@ impl EventListener {
         // Broadcast via WebSocket
         broadcast_lock(&vault_pubkey, amount, new_locked, new_available).await;
 
+        let data = webhook_dispatcher::balance_event_data(
+            amount,
+            tx_signature,
+            vault.total_balance,
+            new_available,
+            new_locked,
+        );
+        webhook_dispatcher::dispatch(&self.state, "lock", &vault_pubkey, data).await;
+
         tracing::info!(" Lock event processed successfully");
         Ok(())
     }
@@ -706,31 +1235,29 @@ impl EventListener {
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?
             .ok_or_else(|| EventListenerError::VaultNotFound(vault_pubkey.clone()))?;
 
-        // Update database
+        // Update balances and record the transaction atomically
         self.state.database
-            .update_vault_balances(
-                &vault_pubkey,
-                vault.total_balance,
-                new_locked,
-                None,
-                None,
+            .apply_balance_change(
+                &BalanceUpdate {
+                    vault_pubkey: &vault_pubkey,
+                    total_balance: vault.total_balance,
+                    locked_balance: new_locked,
+                    total_deposited: None,
+                    total_withdrawn: None,
+                },
+                &TransactionEntry {
+                    tx_signature,
+                    tx_type: "unlock",
+                    amount,
+                    from_vault: None,
+                    to_vault: None,
+                    status: "confirmed",
+                },
             )
             .await
             .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
 
-        // Record transaction
-        self.state.database
-            .record_transaction(
-                &vault_pubkey,
-                tx_signature,
-                "unlock",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        self.confirm_transaction(tx_signature, None, None).await?;
 
         // Invalidate cache
         self.state.cache.invalidate_vault(&vault_pubkey).await;
@@ -738,6 +1265,15 @@ impl EventListener {
         // Broadcast via WebSocket
         broadcast_unlock(&vault_pubkey, amount, new_locked, new_available).await;
 
+        let data = webhook_dispatcher::balance_event_data(
+            amount,
+            tx_signature,
+            vault.total_balance,
+            new_available,
+            new_locked,
+        );
+        webhook_dispatcher::dispatch(&self.state, "unlock", &vault_pubkey, data).await;
+
         tracing::info!(" Unlock event processed successfully");
         Ok(())
     }
@@ -783,10 +1319,290 @@ impl EventListener {
             tracing::warn!("Failed to sync to vault {}: {}", to_vault, e);
         }
 
+        let data = serde_json::json!({
+            "amount": amount,
+            "tx_signature": tx_signature,
+            "from_vault": from_vault,
+            "to_vault": to_vault,
+        });
+        webhook_dispatcher::dispatch(&self.state, "transfer", &from_vault, data.clone()).await;
+        webhook_dispatcher::dispatch(&self.state, "transfer", &to_vault, data).await;
+
         tracing::info!(" Transfer event processed successfully");
         Ok(())
     }
 
+    /// Handle liquidation event
+    ///
+    /// Applied once per signature: the signature is advisory-locked and an already
+    /// recorded liquidation is skipped, so a re-delivered or replayed event never
+    /// debits the vault twice.
+    pub(crate) async fn handle_liquidation_event(
+        &self,
+        event: LiquidationEvent,
+        tx_signature: &str,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+        let amount = event.amount as i64;
+        let remaining_locked = event.remaining_locked as i64;
+
+        tracing::info!(
+            "⚠️ Liquidation event: vault={}, liquidator={}, amount={}, remaining_locked={}, timestamp={}",
+            vault_pubkey, event.liquidator_pubkey(), amount, remaining_locked, event.timestamp
+        );
+
+        let db_error = |e: sqlx::Error| EventListenerError::DatabaseError(e.to_string());
+        let mut db_tx = self.state.database.begin().await.map_err(db_error)?;
+        Database::lock_tx_signature(&mut db_tx, tx_signature).await.map_err(db_error)?;
+
+        if Database::get_transaction_by_signature_in(&mut db_tx, tx_signature)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            db_tx.commit().await.map_err(db_error)?;
+            tracing::info!("Liquidation {} already processed, skipping", tx_signature);
+            return Ok(());
+        }
+
+        let vault = Database::get_vault_for_update(&mut db_tx, &vault_pubkey)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| EventListenerError::VaultNotFound(vault_pubkey.clone()))?;
+
+        // Seized tokens leave the vault, so total shrinks with locked
+        let new_total = vault.total_balance - amount;
+
+        // Update balances and record the transaction atomically
+        Database::apply_balance_change_in(
+            &mut db_tx,
+            &BalanceUpdate {
+                vault_pubkey: &vault_pubkey,
+                total_balance: new_total,
+                locked_balance: remaining_locked,
+                total_deposited: None,
+                total_withdrawn: None,
+            },
+            &TransactionEntry {
+                tx_signature,
+                tx_type: "liquidation",
+                amount,
+                from_vault: Some(vault_pubkey.as_str()),
+                to_vault: None,
+                status: "confirmed",
+            },
+        )
+        .await
+        .map_err(db_error)?;
+        db_tx.commit().await.map_err(db_error)?;
+
+        self.state.cache.invalidate_vault(&vault_pubkey).await;
+
+        let new_available = new_total - remaining_locked;
+        broadcast_balance_update(&vault_pubkey, new_total, new_available, remaining_locked).await;
+
+        let data = webhook_dispatcher::balance_event_data(
+            amount,
+            tx_signature,
+            new_total,
+            new_available,
+            remaining_locked,
+        );
+        webhook_dispatcher::dispatch(&self.state, "liquidation", &vault_pubkey, data).await;
+
+        self.update_tvl().await?;
+
+        tracing::info!(" Liquidation event processed successfully");
+        Ok(())
+    }
+
+    /// Handle ownership transfer event
+    async fn handle_ownership_transferred_event(
+        &self,
+        event: OwnershipTransferredEvent,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+        let old_owner = event.old_owner_pubkey();
+        let new_owner = event.new_owner_pubkey();
+
+        tracing::info!(
+            "🔑 Ownership transferred: vault={}, old_owner={}, new_owner={}, timestamp={}",
+            vault_pubkey, old_owner, new_owner, event.timestamp
+        );
+
+        self.state.database
+            .update_vault_owner(&vault_pubkey, &new_owner)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        // Drop both owners' vault lists; the next lookup repopulates them
+        self.state.cache.invalidate_owner(&old_owner).await;
+        self.state.cache.invalidate_owner(&new_owner).await;
+        self.state.cache.invalidate_vault(&vault_pubkey).await;
+
+        tracing::info!(" Ownership transfer event processed successfully");
+        Ok(())
+    }
+
+    /// Handle vault closed event
+    pub(crate) async fn handle_vault_closed_event(
+        &self,
+        event: VaultClosedEvent,
+        tx_signature: &str,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+        let owner_pubkey = event.owner_pubkey();
+
+        tracing::info!(
+            "🗑️ Vault closed event: vault={}, owner={}, timestamp={}",
+            vault_pubkey, owner_pubkey, event.timestamp
+        );
+
+        let closed = self.state.database
+            .mark_vault_closed(&vault_pubkey)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        if !closed {
+            tracing::warn!("Vault {} was unknown or already closed", vault_pubkey);
+        }
+
+        if let Err(e) = self.state.database
+            .create_audit_entry(
+                "vault_closed",
+                Some(&vault_pubkey),
+                Some(&owner_pubkey),
+                None,
+                Some(tx_signature),
+                serde_json::json!({ "timestamp": event.timestamp }),
+                None,
+                None,
+            )
+            .await
+        {
+            tracing::error!("Failed to audit close of vault {}: {}", vault_pubkey, e);
+        }
+
+        self.state.cache.invalidate_vault(&vault_pubkey).await;
+        self.state.cache.invalidate_owner(&owner_pubkey).await;
+
+        broadcast_vault_closed(&vault_pubkey, tx_signature).await;
+
+        let data = serde_json::json!({ "tx_signature": tx_signature, "owner": owner_pubkey });
+        webhook_dispatcher::dispatch(&self.state, "vault_closed", &vault_pubkey, data).await;
+
+        // Closed vaults drop out of TVL
+        self.update_tvl().await?;
+
+        tracing::info!(" Vault closed event processed successfully");
+        Ok(())
+    }
+
+    /// Handle position opened event
+    ///
+    /// The collateral itself moves with the `LockEvent` emitted alongside; this
+    /// only records the position.
+    async fn handle_position_opened_event(
+        &self,
+        event: PositionOpenedEvent,
+        tx_signature: &str,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+
+        tracing::info!(
+            "📌 Position opened event: vault={}, position_id={}, amount={}",
+            vault_pubkey, event.position_id, event.amount
+        );
+
+        let position = VaultPosition {
+            vault_pubkey: vault_pubkey.clone(),
+            position_id: event.position_id as i64,
+            amount: event.amount as i64,
+            authority_program: event.authority_pubkey(),
+            opened_at: chrono::DateTime::from_timestamp(event.timestamp, 0)
+                .unwrap_or_else(chrono::Utc::now),
+            unlock_after: None,
+            tx_signature: tx_signature.to_string(),
+        };
+
+        let inserted = self.state.database
+            .insert_position(&position)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        if !inserted {
+            tracing::debug!(
+                "Position {} of vault {} already recorded",
+                event.position_id, vault_pubkey
+            );
+        }
+
+        tracing::info!(" Position opened event processed successfully");
+        Ok(())
+    }
+
+    /// Handle position closed event
+    async fn handle_position_closed_event(
+        &self,
+        event: PositionClosedEvent,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+
+        tracing::info!(
+            "📍 Position closed event: vault={}, position_id={}, amount={}, timestamp={}",
+            vault_pubkey, event.position_id, event.amount, event.timestamp
+        );
+
+        let deleted = self.state.database
+            .delete_position(&vault_pubkey, event.position_id as i64)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            tracing::warn!(
+                "Position {} of vault {} was unknown or already closed",
+                event.position_id, vault_pubkey
+            );
+        }
+
+        tracing::info!(" Position closed event processed successfully");
+        Ok(())
+    }
+
+    /// Handle authority added event
+    async fn handle_authority_added_event(
+        &self,
+        event: AuthorityAddedEvent,
+        tx_signature: &str,
+    ) -> Result<(), EventListenerError> {
+        let vault_pubkey = event.vault_pubkey();
+        let program_id = event.program_pubkey();
+
+        tracing::info!(
+            "🛡️ Authority added event: vault={}, program={}, timestamp={}",
+            vault_pubkey, program_id, event.timestamp
+        );
+
+        let authorized_at = chrono::DateTime::from_timestamp(event.timestamp, 0)
+            .unwrap_or_else(chrono::Utc::now);
+        let inserted = self.state.database
+            .insert_authorized_program(&vault_pubkey, &program_id, authorized_at, tx_signature)
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+
+        if !inserted {
+            tracing::debug!(
+                "Program {} already recorded as authorized on vault {}",
+                program_id, vault_pubkey
+            );
+        }
+
+        self.state.cache.invalidate_authorized_programs(&vault_pubkey).await;
+
+        tracing::info!(" Authority added event processed successfully");
+        Ok(())
+    }
+
     /// Handle vault initialized event
     async fn handle_vault_initialized_event(
         &self,
@@ -838,6 +1654,28 @@ impl EventListener {
         Ok(())
     }
 
+    /// Flip a transaction the API recorded as `pending` to `confirmed` now that its event landed
+    ///
+    /// `block_time` and `slot` are only written when given, so a later call can fill them in.
+    async fn confirm_transaction(
+        &self,
+        tx_signature: &str,
+        block_time: Option<i64>,
+        slot: Option<i64>,
+    ) -> Result<(), EventListenerError> {
+        self.state.database
+            .update_transaction_status(
+                tx_signature,
+                TransactionStatus::Confirmed.as_str(),
+                block_time,
+                slot,
+            )
+            .await
+            .map_err(|e| EventListenerError::DatabaseError(e.to_string()))?;
+        self.state.cache.invalidate_transaction(tx_signature).await;
+        Ok(())
+    }
+
     /// Sync a vault from on-chain data
     async fn sync_vault(&self, vault_pubkey: &str) -> Result<(), EventListenerError> {
         if let Err(e) = crate::services::VaultManager::sync_vault_from_chain(&self.state, vault_pubkey).await {
@@ -889,20 +1727,6 @@ pub enum EventListenerError {
 // Public API for starting the event listener
 // ============================================================================
 
-/// Start the event listener as a background task
-pub async fn run_event_listener(state: Data<AppState>) {
-    tracing::info!(" Initializing Event Listener...");
-    
-    let config = EventListenerConfig::default();
-    let mut listener = EventListener::new(state, config);
-    
-    // This should never return under normal operation
-    listener.start().await;
-    
-    // If we get here, something went wrong
-    tracing::error!(" Event Listener unexpectedly exited!");
-}
-
 /// Start event listener with custom configuration
 pub async fn run_event_listener_with_config(
     state: Data<AppState>,
@@ -914,4 +1738,77 @@ pub async fn run_event_listener_with_config(
     listener.start().await;
     
     tracing::error!(" Event Listener unexpectedly exited!");
-}
\ No newline at end of file
+}
+/// Delay before the first restart of an exited event listener
+pub const LISTENER_RESTART_BASE_DELAY: Duration = Duration::from_secs(5);
+/// Longest the restart delay grows to
+pub const LISTENER_RESTART_MAX_DELAY: Duration = Duration::from_secs(60);
+/// A run lasting this long counts as healthy and resets the restart backoff
+pub const LISTENER_HEALTHY_RUN: Duration = Duration::from_secs(300);
+
+/// Restart delays for the event listener: doubled on each consecutive exit up to
+/// a cap, and back to the base once a run stays up for [`LISTENER_HEALTHY_RUN`]
+#[derive(Debug, Clone)]
+pub struct RestartBackoff {
+    base: Duration,
+    max: Duration,
+    healthy_run: Duration,
+    consecutive_restarts: u32,
+}
+
+impl Default for RestartBackoff {
+    fn default() -> Self {
+        Self::new(LISTENER_RESTART_BASE_DELAY, LISTENER_RESTART_MAX_DELAY, LISTENER_HEALTHY_RUN)
+    }
+}
+
+impl RestartBackoff {
+    pub fn new(base: Duration, max: Duration, healthy_run: Duration) -> Self {
+        Self { base, max, healthy_run, consecutive_restarts: 0 }
+    }
+
+    /// Restarts since the listener last ran healthily
+    pub fn consecutive_restarts(&self) -> u32 {
+        self.consecutive_restarts
+    }
+
+    /// Delay before restarting a listener that ran for `ran_for`
+    ///
+    /// `jitter` in `[0, 1)` shaves up to a fifth off the delay so restarts of
+    /// several instances against the same RPC node drift apart.
+    pub fn next_delay(&mut self, ran_for: Duration, jitter: f64) -> Duration {
+        if ran_for >= self.healthy_run {
+            self.consecutive_restarts = 0;
+        }
+
+        let factor = 2u32.saturating_pow(self.consecutive_restarts.min(16));
+        let delay = self.base.saturating_mul(factor).min(self.max);
+        self.consecutive_restarts = self.consecutive_restarts.saturating_add(1);
+
+        delay - delay.mul_f64(jitter.clamp(0.0, 1.0) * 0.2)
+    }
+}
+
+/// Run the event listener forever, restarting it with [`RestartBackoff`] whenever
+/// it exits or panics
+pub async fn supervise_event_listener(state: Data<AppState>, config: EventListenerConfig) {
+    let mut backoff = RestartBackoff::default();
+
+    loop {
+        let started = time::Instant::now();
+        let run_state = state.clone();
+        let run_config = config.clone();
+        match tokio::spawn(run_event_listener_with_config(run_state, run_config)).await {
+            Ok(_) => tracing::warn!("Event listener exited"),
+            Err(e) => tracing::error!("Event listener panicked: {:?}", e),
+        }
+
+        let delay = backoff.next_delay(started.elapsed(), retry_jitter());
+        tracing::warn!(
+            "Restarting event listener in {:?} (consecutive restarts: {})",
+            delay,
+            backoff.consecutive_restarts()
+        );
+        time::sleep(delay).await;
+    }
+}
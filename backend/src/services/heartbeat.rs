@@ -0,0 +1,100 @@
+//! Heartbeats from the long-running background services
+//!
+//! The vault monitor, balance reconciler and event listener each call
+//! [`Heartbeats::beat`] at the start of every cycle. `GET /health/ready` treats a
+//! service as stuck once it has gone `HEARTBEAT_MISSED_CYCLES` of its own intervals
+//! without one.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Config;
+
+/// Shortest window a heartbeat counts as recent, whatever the service's interval
+///
+/// Keeps a slow event listener poll, or its 30 second back-off after repeated errors,
+/// from being reported as a stuck service.
+pub const MIN_HEARTBEAT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Background service that reports heartbeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundService {
+    Monitor,
+    Reconciler,
+    EventListener,
+}
+
+impl BackgroundService {
+    pub const ALL: [BackgroundService; 3] = [
+        BackgroundService::Monitor,
+        BackgroundService::Reconciler,
+        BackgroundService::EventListener,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BackgroundService::Monitor => "vault_monitor",
+            BackgroundService::Reconciler => "balance_reconciler",
+            BackgroundService::EventListener => "event_listener",
+        }
+    }
+
+    /// How often the service runs a cycle under `config`
+    pub fn interval(self, config: &Config) -> Duration {
+        match self {
+            BackgroundService::Monitor => Duration::from_secs(config.monitoring_interval_seconds),
+            BackgroundService::Reconciler => {
+                Duration::from_secs(config.reconciliation_interval_seconds)
+            }
+            BackgroundService::EventListener => {
+                Duration::from_millis(config.event_poll_interval_ms)
+            }
+        }
+    }
+
+    /// How long after its last heartbeat the service counts as stale
+    pub fn stale_after(self, config: &Config) -> Duration {
+        (self.interval(config) * config.heartbeat_missed_cycles).max(MIN_HEARTBEAT_WINDOW)
+    }
+}
+
+/// Last heartbeat of each [`BackgroundService`], as Unix seconds (0 for never)
+///
+/// Clones share the same timestamps.
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeats {
+    beats: Arc<[AtomicI64; 3]>,
+}
+
+impl Heartbeats {
+    /// Record that `service` is starting a cycle now
+    pub fn beat(&self, service: BackgroundService) {
+        self.record(service, Utc::now());
+    }
+
+    /// Record a heartbeat for `service` at `at`
+    pub fn record(&self, service: BackgroundService, at: DateTime<Utc>) {
+        self.beats[service as usize].store(at.timestamp(), Ordering::Relaxed);
+    }
+
+    /// When `service` last reported, or `None` if it never has
+    pub fn last_beat(&self, service: BackgroundService) -> Option<DateTime<Utc>> {
+        match self.beats[service as usize].load(Ordering::Relaxed) {
+            0 => None,
+            seconds => DateTime::from_timestamp(seconds, 0),
+        }
+    }
+
+    /// Whether `service` reported within its [`BackgroundService::stale_after`] window
+    pub fn is_fresh(&self, service: BackgroundService, config: &Config) -> bool {
+        let Some(last) = self.last_beat(service) else {
+            return false;
+        };
+        let window = chrono::Duration::from_std(service.stale_after(config))
+            .unwrap_or(chrono::Duration::MAX);
+        Utc::now() - last <= window
+    }
+}
@@ -5,18 +5,37 @@ pub mod vault_manager;
 pub mod vault_moniter;
 pub mod cpi_manager;
 pub mod event_listner;
+pub mod tvl_recorder;
+pub mod auto_locker;
+pub mod cache_warmer;
+pub mod confirmation_checker;
+pub mod webhook_dispatcher;
+pub mod program_pause;
+pub mod token_program;
+pub mod heartbeat;
 
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub use balance_reconciler::*;
 pub use balance_tracker::*;
+use solana_client::client_error::{ClientError, ClientErrorKind};
 use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_request::RpcError;
+use solana_commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 pub use transaction_builder::*;
 pub use vault_manager::*;
 pub use vault_moniter::*;
 pub use cpi_manager::*;
 pub use event_listner::*;
+pub use token_program::TokenProgram;
+pub use heartbeat::{BackgroundService, Heartbeats};
 
 use crate::{cache::Cache, config::Config, database::Database};
 
@@ -27,4 +46,131 @@ pub struct AppState {
     pub config: Config,
     pub solana_client: Arc<AsyncRpcClient>,
     pub program_id: Pubkey,
+    pub heartbeats: Heartbeats,
+}
+
+/// Error from any backend service
+///
+/// Domain errors convert with `From`, as do raw `sqlx` and Solana client errors, so
+/// service code and handlers can use `?` and leave the HTTP status and error code
+/// to the `ApiError` impl in `api::error`.
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+    #[error(transparent)]
+    Vault(#[from] VaultError),
+    #[error(transparent)]
+    Balance(#[from] BalanceError),
+    #[error(transparent)]
+    Cpi(#[from] CPIError),
+    #[error(transparent)]
+    EventListener(#[from] EventListenerError),
+    #[error(transparent)]
+    Reconciler(#[from] ReconcilerError),
+    #[error(transparent)]
+    Monitor(#[from] MonitorError),
+    #[error(transparent)]
+    Builder(#[from] BuilderError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Solana RPC error: {0}")]
+    SolanaRpc(#[from] ClientError),
+}
+
+/// Commitment for transaction history reads (`getTransaction`, `getSignaturesForAddress`)
+///
+/// Those methods reject `processed`, so it is raised to `confirmed`.
+pub fn history_commitment(client: &AsyncRpcClient) -> CommitmentConfig {
+    let commitment = client.commitment();
+    if commitment.is_at_least_confirmed() {
+        commitment
+    } else {
+        CommitmentConfig::confirmed()
+    }
+}
+
+/// Attempts `rpc_retry` makes before returning the last error
+pub const RPC_MAX_ATTEMPTS: u32 = 4;
+/// Delay before the first retry; doubled on each further attempt
+const RPC_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Whether an RPC error is transient (rate limit, timeout, lagging node) and worth retrying
+pub fn is_retryable_rpc_error(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status()
+                    .is_some_and(|s| s.as_u16() == 429 || s.is_server_error())
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => matches!(
+            *code,
+            JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                | JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        ),
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(msg)) => is_transient_message(msg),
+        // The client folds transport failures on account fetches into
+        // `AccountNotFound: pubkey=<key>: <cause>`; a bare not-found has no cause
+        ClientErrorKind::RpcError(RpcError::ForUser(msg)) => msg
+            .strip_prefix("AccountNotFound: pubkey=")
+            .and_then(|rest| rest.split_once(": "))
+            .is_some_and(|(_, cause)| is_transient_message(cause)),
+        _ => false,
+    }
+}
+
+const TRANSIENT_MARKERS: &[&str] = &[
+    "429",
+    "too many requests",
+    "timed out",
+    "timeout",
+    "connection",
+    "502",
+    "503",
+];
+
+fn is_transient_message(msg: &str) -> bool {
+    let msg = msg.to_ascii_lowercase();
+    TRANSIENT_MARKERS.iter().any(|marker| msg.contains(marker))
+}
+
+/// Run an idempotent RPC call, retrying transient failures with jittered exponential backoff
+///
+/// Fatal errors such as a missing account are returned immediately.
+pub async fn rpc_retry<T, F, Fut>(operation: &str, mut call: F) -> Result<T, ClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match call().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < RPC_MAX_ATTEMPTS && is_retryable_rpc_error(&e) => {
+                let delay = RPC_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                let delay = delay + delay.mul_f64(retry_jitter() * 0.5);
+                tracing::warn!(
+                    "RPC {} failed (attempt {}/{}), retrying in {:?}: {}",
+                    operation,
+                    attempt,
+                    RPC_MAX_ATTEMPTS,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Cheap value in `[0, 1)` so concurrent callers don't retry in lockstep
+pub(crate) fn retry_jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1_000) as f64 / 1_000.0
 }
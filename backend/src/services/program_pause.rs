@@ -0,0 +1,99 @@
+//! Mirrors the on-chain `ProgramConfig.global_paused` flag into the cache
+//!
+//! Mutation endpoints check the cached flag so a paused program fails fast with
+//! `503 PROGRAM_PAUSED` instead of building transactions the program will reject.
+
+use std::time::Duration;
+
+use actix_web::web::Data;
+use tokio::time;
+
+use crate::services::{program_config_address, rpc_retry, AppState};
+use crate::websocket::broadcast_alert;
+
+/// Offset of `global_paused`: discriminator (8) + authority (32)
+const GLOBAL_PAUSED_OFFSET: usize = 40;
+
+pub async fn run_pause_watcher(state: Data<AppState>) {
+    let interval_secs = state.config.monitoring_interval_seconds.max(1);
+    let mut interval = time::interval(Duration::from_secs(interval_secs));
+
+    tracing::info!("Program pause watcher started (interval: {}s)", interval_secs);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = refresh_pause_state(&state).await {
+            tracing::error!("Program pause check error: {}", e);
+        }
+    }
+}
+
+/// Read the program config and update the cached pause flag
+///
+/// A program without a config account has never been paused. Raises a
+/// `program_paused` or `program_resumed` alert when the flag flips. Returns the
+/// current flag.
+pub async fn refresh_pause_state(state: &AppState) -> Result<bool, PauseWatcherError> {
+    let config_address = program_config_address(&state.program_id);
+    let account = rpc_retry("get_program_config", || {
+        state
+            .solana_client
+            .get_account_with_commitment(&config_address, state.solana_client.commitment())
+    })
+    .await
+    .map_err(|e| PauseWatcherError::RpcError(e.to_string()))?
+    .value;
+
+    let paused = match account {
+        Some(account) => parse_global_paused(&account.data)?,
+        None => false,
+    };
+
+    if state.cache.set_program_paused(paused) != paused {
+        raise_pause_alert(state, paused).await;
+    }
+
+    Ok(paused)
+}
+
+pub fn parse_global_paused(data: &[u8]) -> Result<bool, PauseWatcherError> {
+    data.get(GLOBAL_PAUSED_OFFSET)
+        .map(|flag| *flag != 0)
+        .ok_or_else(|| {
+            PauseWatcherError::DeserializationError(format!(
+                "program config is {} bytes",
+                data.len()
+            ))
+        })
+}
+
+async fn raise_pause_alert(state: &AppState, paused: bool) {
+    let (alert_type, severity, message) = if paused {
+        (
+            "program_paused",
+            "critical",
+            "Vault program globally paused; mutations are rejected until it resumes",
+        )
+    } else {
+        ("program_resumed", "warning", "Vault program resumed; mutations accepted again")
+    };
+    tracing::warn!("{}", message);
+
+    match state
+        .database
+        .create_alert(alert_type, severity, None, message, None)
+        .await
+    {
+        Ok(_) => broadcast_alert(alert_type, severity, None, message).await,
+        Err(e) => tracing::error!("Failed to persist {} alert: {}", alert_type, e),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PauseWatcherError {
+    #[error("RPC error: {0}")]
+    RpcError(String),
+    #[error("Deserialization error: {0}")]
+    DeserializationError(String),
+}
@@ -0,0 +1,117 @@
+//! Token programs a vault's collateral can be held under
+//!
+//! Legacy SPL Token and Token-2022 share the base token account layout, but
+//! Token-2022 accounts may carry extensions after it and each program only accepts
+//! instructions addressed to its own ID, so reads and transfers branch on the
+//! program that owns the account.
+
+use std::fmt;
+use std::str::FromStr;
+
+use solana_sdk::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022_interface::extension::StateWithExtensions;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenProgram {
+    #[default]
+    SplToken,
+    Token2022,
+}
+
+impl TokenProgram {
+    pub fn id(self) -> Pubkey {
+        match self {
+            TokenProgram::SplToken => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022_interface::id(),
+        }
+    }
+
+    /// The program with `program_id`, or `None` if it isn't a token program
+    pub fn from_id(program_id: &Pubkey) -> Option<Self> {
+        if *program_id == spl_token::id() {
+            Some(TokenProgram::SplToken)
+        } else if *program_id == spl_token_2022_interface::id() {
+            Some(TokenProgram::Token2022)
+        } else {
+            None
+        }
+    }
+
+    /// Token amount held by a token account owned by this program
+    pub fn unpack_amount(self, data: &[u8]) -> Result<u64, TokenProgramError> {
+        let amount = match self {
+            TokenProgram::SplToken => spl_token::state::Account::unpack(data)
+                .map_err(|e| TokenProgramError::InvalidAccount(e.to_string()))?
+                .amount,
+            TokenProgram::Token2022 => {
+                StateWithExtensions::<spl_token_2022_interface::state::Account>::unpack(data)
+                    .map_err(|e| TokenProgramError::InvalidAccount(e.to_string()))?
+                    .base
+                    .amount
+            }
+        };
+        Ok(amount)
+    }
+
+    /// Unchecked `transfer` of `amount` from `source` to `destination`
+    ///
+    /// Token-2022 mints whose extensions require `transfer_checked` (transfer fees,
+    /// transfer hooks) will reject it.
+    pub fn transfer(
+        self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, TokenProgramError> {
+        #[allow(deprecated)]
+        let instruction = match self {
+            TokenProgram::SplToken => spl_token::instruction::transfer(
+                &spl_token::id(),
+                source,
+                destination,
+                authority,
+                &[],
+                amount,
+            ),
+            TokenProgram::Token2022 => spl_token_2022_interface::instruction::transfer(
+                &spl_token_2022_interface::id(),
+                source,
+                destination,
+                authority,
+                &[],
+                amount,
+            ),
+        };
+        instruction.map_err(|e| TokenProgramError::InvalidInstruction(e.to_string()))
+    }
+}
+
+impl fmt::Display for TokenProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// Parses a program ID as stored in `vaults.token_program`
+impl FromStr for TokenProgram {
+    type Err = TokenProgramError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let program_id =
+            Pubkey::from_str(s).map_err(|_| TokenProgramError::Unsupported(s.to_string()))?;
+        Self::from_id(&program_id).ok_or_else(|| TokenProgramError::Unsupported(s.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenProgramError {
+    #[error("{0} is not a supported token program")]
+    Unsupported(String),
+
+    #[error("Invalid token account: {0}")]
+    InvalidAccount(String),
+
+    #[error("Invalid token instruction: {0}")]
+    InvalidInstruction(String),
+}
@@ -1,31 +1,69 @@
-use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::Transaction};
-use spl_token::instruction as token_instruction;
+use sha2::{Digest, Sha256};
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account,
+};
+
+use crate::services::{rpc_retry, TokenProgram};
+
+/// Anchor instruction discriminator: first 8 bytes of `sha256("global:<name>")`
+pub fn anchor_discriminator(instruction_name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{}", instruction_name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// Anchor account discriminator: first 8 bytes of `sha256("account:<Name>")`
+pub fn account_discriminator(account_name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{}", account_name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+/// The singleton `ProgramConfig` PDA the program checks for a global pause
+pub fn program_config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"program_config"], program_id).0
+}
+
+/// The native System Program, which `initialize_vault` allocates accounts through
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+
+/// Mirrors the on-chain `MAX_BATCH_SIZE` for `batch_lock`
+pub const MAX_BATCH_LOCK_SIZE: usize = 10;
+
+/// Percentile of recent prioritization fees suggested as a transaction's priority fee
+pub const PRIORITY_FEE_PERCENTILE: u64 = 50;
 
 pub struct TransactionBuilder;
 
 impl TransactionBuilder {
     pub fn build_deposit_tx(
+        token_program: TokenProgram,
         user_pubkey: &Pubkey,
         user_token_account: &Pubkey,
         vault_token_account: &Pubkey,
         amount: u64,
         recent_blockhash: Hash,
     ) -> Result<Transaction, BuilderError> {
-        let transfer_ix = token_instruction::transfer(
-            &spl_token::id(),
-            user_token_account,
-            vault_token_account,
-            user_pubkey,
-            &[],
-            amount,
-        )
-        .map_err(|e| BuilderError::BuildFailed(e.to_string()))?;
+        let transfer_ix = token_program
+            .transfer(user_token_account, vault_token_account, user_pubkey, amount)
+            .map_err(|e| BuilderError::BuildFailed(e.to_string()))?;
 
         let mut transaction = Transaction::new_with_payer(&[transfer_ix], Some(user_pubkey));
         transaction.message.recent_blockhash = recent_blockhash;
         Ok(transaction)
     }
     pub fn build_withdraw_tx(
+        token_program: TokenProgram,
         user_pubkey: &Pubkey,
         vault_pubkey: &Pubkey,
         vault_token_account: &Pubkey,
@@ -35,20 +73,276 @@ impl TransactionBuilder {
     ) -> Result<Transaction, BuilderError> {
         // For withdrawal, the vault PDA must sign
         // This is typically done through the program
-        let transfer_ix = token_instruction::transfer(
-            &spl_token::id(),
-            vault_token_account,
-            user_token_account,
-            vault_pubkey, // Vault PDA is authority
-            &[],
+        let transfer_ix = token_program
+            .transfer(
+                vault_token_account,
+                user_token_account,
+                vault_pubkey, // Vault PDA is authority
+                amount,
+            )
+            .map_err(|e| BuilderError::BuildFailed(e.to_string()))?;
+
+        let mut transaction = Transaction::new_with_payer(&[transfer_ix], Some(user_pubkey));
+
+        transaction.message.recent_blockhash = recent_blockhash;
+
+        Ok(transaction)
+    }
+
+    /// Build a `withdraw_all` program call; the vault PDA signs inside the program
+    ///
+    /// `vault_pubkey` is passed in rather than derived from `user_pubkey`: the PDA is seeded
+    /// by the vault's creator, who is no longer the owner after `transfer_ownership`.
+    pub fn build_withdraw_all_tx(
+        program_id: &Pubkey,
+        token_program: TokenProgram,
+        user_pubkey: &Pubkey,
+        vault_pubkey: &Pubkey,
+        vault_token_account: &Pubkey,
+        user_token_account: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        ensure_program_token(token_program)?;
+
+        let withdraw_all_ix = Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*user_pubkey, true),
+                AccountMeta::new(*vault_pubkey, false),
+                AccountMeta::new(*vault_token_account, false),
+                AccountMeta::new(*user_token_account, false),
+                AccountMeta::new_readonly(token_program.id(), false),
+                AccountMeta::new_readonly(program_config_address(program_id), false),
+            ],
+            data: anchor_discriminator("withdraw_all").to_vec(),
+        };
+
+        let mut transaction = Transaction::new_with_payer(&[withdraw_all_ix], Some(user_pubkey));
+        transaction.message.recent_blockhash = recent_blockhash;
+        Ok(transaction)
+    }
+
+    /// Build an `initialize_vault` program call creating the vault, its ATA and authority PDAs
+    pub fn build_initialize_vault_tx(
+        program_id: &Pubkey,
+        token_program: TokenProgram,
+        user_pubkey: &Pubkey,
+        mint: &Pubkey,
+        min_deposit: u64,
+        max_balance: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        ensure_program_token(token_program)?;
+        let (vault_pda, _bump) =
+            Pubkey::find_program_address(&[b"vault", user_pubkey.as_ref()], program_id);
+        let (vault_authority, _bump) =
+            Pubkey::find_program_address(&[b"vault_authority", vault_pda.as_ref()], program_id);
+        let vault_ata =
+            get_associated_token_address_with_program_id(&vault_pda, mint, &token_program.id());
+
+        let mut data = anchor_discriminator("initialize_vault").to_vec();
+        data.extend_from_slice(&min_deposit.to_le_bytes());
+        data.extend_from_slice(&max_balance.to_le_bytes());
+
+        let initialize_ix = Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*user_pubkey, true),
+                AccountMeta::new(vault_pda, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(vault_ata, false),
+                AccountMeta::new(vault_authority, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                AccountMeta::new_readonly(token_program.id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(program_config_address(program_id), false),
+            ],
+            data,
+        };
+
+        let mut transaction = Transaction::new_with_payer(&[initialize_ix], Some(user_pubkey));
+        transaction.message.recent_blockhash = recent_blockhash;
+        Ok(transaction)
+    }
+
+    /// Build the creation of `owner`'s associated token account for `mint`, paid by `owner`
+    pub fn build_create_ata_tx(
+        token_program: TokenProgram,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        let create_ix = create_associated_token_account(owner, owner, mint, &token_program.id());
+
+        let mut transaction = Transaction::new_with_payer(&[create_ix], Some(owner));
+        transaction.message.recent_blockhash = recent_blockhash;
+        Ok(transaction)
+    }
+
+    /// Build a `batch_lock` program call locking `amounts[i]` in `vaults[i]`;
+    /// `authority_program` must co-sign
+    ///
+    /// `nonces[i]` must be the current nonce of `vaults[i]`. Each vault costs roughly
+    /// 10-15k compute units on-chain, so a full batch of `MAX_BATCH_LOCK_SIZE` stays
+    /// within the default 200k unit limit.
+    pub fn build_batch_lock_tx(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        authority_program: &Pubkey,
+        vaults: &[Pubkey],
+        amounts: &[u64],
+        nonces: &[u64],
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        if vaults.is_empty() || vaults.len() != amounts.len() || vaults.len() != nonces.len() {
+            return Err(BuilderError::BuildFailed(
+                "Each vault needs exactly one amount and one nonce".to_string(),
+            ));
+        }
+        if vaults.len() > MAX_BATCH_LOCK_SIZE {
+            return Err(BuilderError::BatchTooLarge(vaults.len()));
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*authority_program, true),
+            AccountMeta::new_readonly(program_config_address(program_id), false),
+        ];
+        for vault in vaults {
+            let (vault_authority, _bump) =
+                Pubkey::find_program_address(&[b"vault_authority", vault.as_ref()], program_id);
+            accounts.push(AccountMeta::new(*vault, false));
+            accounts.push(AccountMeta::new_readonly(vault_authority, false));
+        }
+
+        // Two Borsh `Vec<u64>`s: a u32 length prefix followed by the values
+        let mut data = anchor_discriminator("batch_lock").to_vec();
+        for values in [amounts, nonces] {
+            data.extend_from_slice(&(values.len() as u32).to_le_bytes());
+            for value in values {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        let batch_lock_ix = Instruction {
+            program_id: *program_id,
+            accounts,
+            data,
+        };
+
+        let mut transaction = Transaction::new_with_payer(&[batch_lock_ix], Some(payer));
+        transaction.message.recent_blockhash = recent_blockhash;
+        Ok(transaction)
+    }
+
+    /// Build a `lock_collateral` program call; `authority_program` must co-sign
+    pub fn build_lock_tx(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        authority_program: &Pubkey,
+        vault: &Pubkey,
+        amount: u64,
+        nonce: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        Self::build_collateral_tx(
+            "lock_collateral",
+            program_id,
+            payer,
+            authority_program,
+            vault,
             amount,
+            nonce,
+            recent_blockhash,
         )
-        .map_err(|e| BuilderError::BuildFailed(e.to_string()))?;
+    }
 
-        let mut transaction = Transaction::new_with_payer(&[transfer_ix], Some(user_pubkey));
+    /// Build an `unlock_collateral` program call; `authority_program` must co-sign
+    pub fn build_unlock_tx(
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        authority_program: &Pubkey,
+        vault: &Pubkey,
+        amount: u64,
+        nonce: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        Self::build_collateral_tx(
+            "unlock_collateral",
+            program_id,
+            payer,
+            authority_program,
+            vault,
+            amount,
+            nonce,
+            recent_blockhash,
+        )
+    }
+
+    /// `lock_collateral` and `unlock_collateral` share their accounts and
+    /// `amount, nonce` arguments; `nonce` must be the vault's current one
+    #[allow(clippy::too_many_arguments)]
+    fn build_collateral_tx(
+        instruction_name: &str,
+        program_id: &Pubkey,
+        payer: &Pubkey,
+        authority_program: &Pubkey,
+        vault: &Pubkey,
+        amount: u64,
+        nonce: u64,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        let (vault_authority, _bump) =
+            Pubkey::find_program_address(&[b"vault_authority", vault.as_ref()], program_id);
 
+        let mut data = anchor_discriminator(instruction_name).to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&nonce.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*vault, false),
+                AccountMeta::new(vault_authority, false),
+                AccountMeta::new_readonly(*authority_program, true),
+                AccountMeta::new_readonly(program_config_address(program_id), false),
+            ],
+            data,
+        };
+
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(payer));
         transaction.message.recent_blockhash = recent_blockhash;
+        Ok(transaction)
+    }
+
+    /// Build an `authority_to_add` program call; the vault owner signs as `admin`
+    ///
+    /// Adding a program already in the vault's `VaultAuthority` list is a no-op on-chain.
+    pub fn build_authorize_program_tx(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        vault: &Pubkey,
+        authorized_program: &Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<Transaction, BuilderError> {
+        let (vault_authority, _bump) =
+            Pubkey::find_program_address(&[b"vault_authority", vault.as_ref()], program_id);
+
+        let mut data = anchor_discriminator("authority_to_add").to_vec();
+        data.extend_from_slice(authorized_program.as_ref());
+
+        let instruction = Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(vault_authority, false),
+                AccountMeta::new(*vault, false),
+                AccountMeta::new_readonly(*admin, true),
+            ],
+            data,
+        };
 
+        let mut transaction = Transaction::new_with_payer(&[instruction], Some(admin));
+        transaction.message.recent_blockhash = recent_blockhash;
         Ok(transaction)
     }
 
@@ -65,6 +359,46 @@ impl TransactionBuilder {
         let num_signatures = transaction.message.header.num_required_signatures as u64;
         num_signatures * lamports_per_signature
     }
+
+    /// Suggested compute unit price in micro-lamports for `transaction`
+    ///
+    /// Asks `getRecentPrioritizationFees` about the accounts the transaction writes,
+    /// since those are the ones it contends on, and takes the
+    /// `PRIORITY_FEE_PERCENTILE` of the per-slot fees returned.
+    pub async fn estimate_priority_fee(
+        client: &AsyncRpcClient,
+        transaction: &Transaction,
+    ) -> Result<u64, BuilderError> {
+        let message = &transaction.message;
+        let writable: Vec<Pubkey> = message
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| message.is_maybe_writable(*i, None))
+            .map(|(_, key)| *key)
+            .collect();
+
+        let fees = rpc_retry("get_recent_prioritization_fees", || {
+            client.get_recent_prioritization_fees(&writable)
+        })
+        .await
+        .map_err(|e| BuilderError::RpcFailed(e.to_string()))?;
+
+        let fees: Vec<u64> = fees.iter().map(|f| f.prioritization_fee).collect();
+        Ok(fee_percentile(&fees, PRIORITY_FEE_PERCENTILE))
+    }
+}
+
+/// Nearest-rank `percentile` of `fees`, 0 when there are none
+pub fn fee_percentile(fees: &[u64], percentile: u64) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    let mut sorted = fees.to_vec();
+    sorted.sort_unstable();
+    let rank = (percentile.min(100) as usize * sorted.len()).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -74,4 +408,22 @@ pub enum BuilderError {
 
     #[error("Transaction build failed: {0}")]
     BuildFailed(String),
+
+    #[error("Batch of {0} vaults exceeds the maximum of {MAX_BATCH_LOCK_SIZE}")]
+    BatchTooLarge(usize),
+
+    #[error("RPC request failed: {0}")]
+    RpcFailed(String),
+
+    #[error("Token program {0} is not supported by the vault program")]
+    UnsupportedTokenProgram(TokenProgram),
+}
+
+/// The vault program's instructions take legacy SPL Token accounts only, so program calls
+/// moving tokens can't be built for Token-2022 vaults
+fn ensure_program_token(token_program: TokenProgram) -> Result<(), BuilderError> {
+    match token_program {
+        TokenProgram::SplToken => Ok(()),
+        TokenProgram::Token2022 => Err(BuilderError::UnsupportedTokenProgram(token_program)),
+    }
 }
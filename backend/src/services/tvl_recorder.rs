@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use actix_web::web::Data;
+use tokio::time;
+
+use shared::TvlStats;
+
+use crate::monitering::metrics;
+use crate::services::AppState;
+
+pub async fn run_tvl_recorder(state: Data<AppState>) {
+    let interval_mins = state.config.tvl_sample_interval_minutes.max(1);
+    let mut interval = time::interval(Duration::from_secs(interval_mins * 60));
+
+    tracing::info!("TVL Recorder started (interval: {}m)", interval_mins);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = recompute_tvl(&state).await {
+            tracing::error!("TVL sample error: {}", e);
+        }
+    }
+}
+
+/// Recompute TVL from the `vaults` table, record it as a sample, and refresh the
+/// cache and Prometheus gauges with it
+pub async fn recompute_tvl(state: &AppState) -> Result<TvlStats, TvlRecorderError> {
+    let stats = state
+        .database
+        .get_tvl_stats()
+        .await
+        .map_err(|e| TvlRecorderError::DatabaseError(e.to_string()))?;
+
+    state
+        .database
+        .record_tvl_sample(&stats)
+        .await
+        .map_err(|e| TvlRecorderError::DatabaseError(e.to_string()))?;
+
+    // Keep the cached snapshot in step with the recorded sample
+    state.cache.set_tvl_stats(stats.clone()).await;
+    metrics::set_tvl(stats.total_value_locked as f64);
+    metrics::set_vault_count(stats.total_vaults as f64);
+
+    tracing::debug!(
+        "Recorded TVL sample: {} vaults, {} TVL",
+        stats.total_vaults,
+        stats.total_value_locked
+    );
+    Ok(stats)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TvlRecorderError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
@@ -1,21 +1,39 @@
 use chrono::Utc;
-use shared::Vault;
-use solana_sdk::pubkey::Pubkey;
+use shared::{
+    OnChainVaultComparison, TransactionStatus, TransactionType, Vault, VaultFieldDiff,
+    USDT_DECIMALS,
+};
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedTransaction, UiMessage, UiTransactionEncoding, UiTransactionTokenBalance,
+};
+use sqlx::PgTransaction;
 use std::str::FromStr;
 
-use crate::services::AppState;
+use crate::config::Config;
+use crate::database::{BalanceUpdate, Database, TransactionEntry};
+use crate::services::{
+    account_discriminator, history_commitment, rpc_retry, webhook_dispatcher, AppState,
+    BackendError, TokenProgram,
+};
 use crate::websocket::{
-    broadcast_balance_update, broadcast_deposit, broadcast_lock, 
+    broadcast_alert, broadcast_balance_update, broadcast_deposit, broadcast_lock, 
     broadcast_unlock, broadcast_withdrawal
 };
 
+/// Length of the rolling window daily deposit and withdrawal limits apply to
+pub const DAILY_LIMIT_WINDOW_HOURS: i64 = 24;
+
 pub struct VaultManager;
 
 impl VaultManager {
     pub async fn get_vault(
         state: &AppState,
         vault_pubkey: &str,
-    ) -> Result<Option<Vault>, VaultError> {
+    ) -> Result<Option<Vault>, BackendError> {
         if let Some(vault) = state.cache.get_vault(vault_pubkey).await {
             tracing::debug!("Cache HIT for vault {}", vault_pubkey);
             return Ok(Some(vault));
@@ -23,11 +41,7 @@ impl VaultManager {
 
         tracing::debug!("Cache MISS for vault {}", vault_pubkey);
 
-        let vault = state
-            .database
-            .get_vault(vault_pubkey)
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        let vault = state.database.get_vault(vault_pubkey).await?;
         if let Some(ref v) = vault {
             state.cache.set_vault(v.clone()).await;
         }
@@ -35,45 +49,188 @@ impl VaultManager {
         Ok(vault)
     }
 
+    /// The owner's oldest vault
     pub async fn get_vault_by_owner(
         state: &AppState,
         owner_pubkey: &str,
-    ) -> Result<Option<Vault>, VaultError> {
+    ) -> Result<Option<Vault>, BackendError> {
         if let Some(vault_pubkey) = state.cache.get_vault_by_owner(owner_pubkey).await {
             return Self::get_vault(state, &vault_pubkey).await;
         }
 
-        let vault = state
-            .database
-            .get_vault_by_owner(owner_pubkey)
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        Ok(Self::get_vaults_by_owner(state, owner_pubkey).await?.into_iter().next())
+    }
 
-        if let Some(ref v) = vault {
-            state.cache.set_vault(v.clone()).await;
+    /// Every vault held by the owner, oldest first
+    pub async fn get_vaults_by_owner(
+        state: &AppState,
+        owner_pubkey: &str,
+    ) -> Result<Vec<Vault>, BackendError> {
+        if let Some(vault_pubkeys) = state.cache.get_owner_vaults(owner_pubkey).await {
+            let mut vaults = Vec::with_capacity(vault_pubkeys.len());
+            for vault_pubkey in vault_pubkeys {
+                if let Some(vault) = Self::get_vault(state, &vault_pubkey).await? {
+                    vaults.push(vault);
+                }
+            }
+            return Ok(vaults);
         }
 
-        Ok(vault)
+        let vaults = state.database.get_vaults_by_owner(owner_pubkey).await?;
+
+        for vault in &vaults {
+            state.cache.set_vault(vault.clone()).await;
+        }
+        let vault_pubkeys = vaults.iter().map(|v| v.vault_pubkey.clone()).collect();
+        state.cache.set_owner_vaults(owner_pubkey, vault_pubkeys).await;
+
+        Ok(vaults)
     }
 
-    pub async fn sync_vault_from_chain(
+    /// Fetch and parse the vault account without touching the DB or cache
+    async fn fetch_onchain_vault(
         state: &AppState,
         vault_pubkey: &str,
-    ) -> Result<Vault, VaultError> {
+    ) -> Result<Vault, BackendError> {
         let pubkey = Pubkey::from_str(vault_pubkey).map_err(|_| VaultError::InvalidPubkey)?;
-        let account = state
-            .solana_client
-            .get_account(&pubkey)
-            .await
-            .map_err(|e| VaultError::SolanaRpcError(e.to_string()))?;
+        let account =
+            rpc_retry("get_account", || state.solana_client.get_account(&pubkey)).await?;
+
+        Ok(Self::parse_vault_account(&account.data, vault_pubkey)?)
+    }
+
+    /// Programs the vault's on-chain `VaultAuthority` allows to lock and unlock its collateral
+    pub async fn fetch_authorized_programs(
+        state: &AppState,
+        vault_pubkey: &Pubkey,
+    ) -> Result<Vec<Pubkey>, BackendError> {
+        let (vault_authority, _bump) = Pubkey::find_program_address(
+            &[b"vault_authority", vault_pubkey.as_ref()],
+            &state.program_id,
+        );
+        let account = rpc_retry("get_account", || {
+            state
+                .solana_client
+                .get_account_with_commitment(&vault_authority, state.solana_client.commitment())
+        })
+        .await?
+        .value
+        .ok_or(VaultError::VaultNotFound)?;
+
+        Ok(Self::parse_vault_authority_account(&account.data)?)
+    }
+
+    /// Nonce the next lock, unlock or transfer out of `vault_pubkey` must carry
+    pub async fn fetch_vault_nonce(
+        state: &AppState,
+        vault_pubkey: &Pubkey,
+    ) -> Result<u64, BackendError> {
+        let account = rpc_retry("get_account", || {
+            state
+                .solana_client
+                .get_account_with_commitment(vault_pubkey, state.solana_client.commitment())
+        })
+        .await?
+        .value
+        .ok_or(VaultError::VaultNotFound)?;
+
+        Ok(Self::parse_vault_nonce(&account.data)?)
+    }
+
+    /// Read `nonce` from a vault account; vaults not yet migrated to v3 have none
+    /// and their collateral instructions fail until they are
+    pub(crate) fn parse_vault_nonce(data: &[u8]) -> Result<u64, VaultError> {
+        Self::ensure_collateral_vault(data)?;
+        let account_data = data.get(8..).unwrap_or_default();
+        if account_data.len() < VAULT_V3_LEN || account_data[0] < 3 {
+            return Err(VaultError::DeserializationError(format!(
+                "Vault account has no nonce: expected a v3 layout of {} bytes, got {}",
+                VAULT_V3_LEN,
+                account_data.len()
+            )));
+        }
+
+        let offset = VAULT_V2_LEN;
+        Ok(u64::from_le_bytes(
+            account_data[offset..offset + 8].try_into().unwrap(),
+        ))
+    }
 
-        let vault_data = Self::parse_vault_account(&account.data, vault_pubkey)?;
-        state
+    /// Fail with `ProgramNotAuthorized` unless `authority_program` may lock `vault_pubkey`
+    pub async fn ensure_program_authorized(
+        state: &AppState,
+        vault_pubkey: &Pubkey,
+        authority_program: &Pubkey,
+    ) -> Result<(), BackendError> {
+        let authorized = Self::fetch_authorized_programs(state, vault_pubkey).await?;
+        if !authorized.contains(authority_program) {
+            return Err(VaultError::ProgramNotAuthorized(authority_program.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Fail with `ProgramPaused` while the on-chain circuit breaker is engaged
+    ///
+    /// Reads the flag the pause watcher caches, so it costs no RPC call.
+    pub fn ensure_not_paused(state: &AppState) -> Result<(), BackendError> {
+        if state.cache.is_program_paused() {
+            return Err(VaultError::ProgramPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Fail with `VaultFrozen` while an operator holds the vault's withdrawals
+    ///
+    /// Unknown vaults pass; the caller reports those with its own lookup.
+    pub async fn ensure_not_frozen(state: &AppState, vault_pubkey: &str) -> Result<(), BackendError> {
+        let frozen = state.database.is_vault_frozen(vault_pubkey).await?;
+        if frozen == Some(true) {
+            return Err(VaultError::VaultFrozen.into());
+        }
+        Ok(())
+    }
+
+    /// Read-only comparison of the on-chain vault with the database copy
+    pub async fn compare_with_chain(
+        state: &AppState,
+        vault_pubkey: &str,
+    ) -> Result<OnChainVaultComparison, BackendError> {
+        let onchain = Self::fetch_onchain_vault(state, vault_pubkey).await?;
+        let database = state.database.get_vault(vault_pubkey).await?;
+
+        let diff = database
+            .as_ref()
+            .map(|db| diff_vaults(&onchain, db))
+            .unwrap_or_default();
+        let in_sync = database.is_some() && diff.is_empty();
+
+        Ok(OnChainVaultComparison {
+            vault_pubkey: vault_pubkey.to_string(),
+            onchain,
+            database,
+            diff,
+            in_sync,
+        })
+    }
+
+    pub async fn sync_vault_from_chain(
+        state: &AppState,
+        vault_pubkey: &str,
+    ) -> Result<Vault, BackendError> {
+        let mut vault_data = Self::fetch_onchain_vault(state, vault_pubkey).await?;
+        // The account doesn't record mint decimals or the token program; keep the ones
+        // the vault was created with
+        if let Some(existing) = state
             .database
-            .upsert_vault(&vault_data)
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
-        state.cache.set_vault(vault_data.clone()).await;
+            .get_vault(vault_pubkey)
+            .await?
+        {
+            vault_data.decimals = existing.decimals;
+            vault_data.token_program = existing.token_program;
+        }
+        state.database.upsert_vault(&vault_data).await?;
+        // Dropped rather than overwritten so other instances drop their copy too
+        state.cache.invalidate_vault(vault_pubkey).await;
         
         
         broadcast_balance_update(
@@ -87,12 +244,18 @@ impl VaultManager {
         Ok(vault_data)
     }
 
+    /// Register a zero-balance vault, or return the stored one if it already exists
+    ///
+    /// The boolean is `true` when the vault already existed; its balances and
+    /// settings are left untouched.
     pub async fn initialize_vault(
         state: &AppState,
         vault_pubkey: &str,
         owner_pubkey: &str,
         token_account: &str,
-    ) -> Result<Vault, VaultError> {
+        decimals: u8,
+        token_program: TokenProgram,
+    ) -> Result<(Vault, bool), BackendError> {
         let vault = Vault {
             vault_pubkey: vault_pubkey.to_string(),
             owner_pubkey: owner_pubkey.to_string(),
@@ -104,13 +267,26 @@ impl VaultManager {
             total_withdrawn: 0,
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            min_deposit: 0,
+            max_balance: 0,
+            decimals: decimals as i16,
+            token_program: token_program.to_string(),
+            last_activity: Utc::now(),
         };
 
-        state
-            .database
-            .upsert_vault(&vault)
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        let inserted = state.database.insert_vault_if_absent(&vault).await?;
+
+        if !inserted {
+            let existing = state
+                .database
+                .get_vault(vault_pubkey)
+                .await?
+                .ok_or(VaultError::VaultNotFound)?;
+            state.cache.set_vault(existing.clone()).await;
+
+            tracing::info!("Vault {} already initialized, returning it unchanged", vault_pubkey);
+            return Ok((existing, true));
+        }
 
         state.cache.set_vault(vault.clone()).await;
 
@@ -120,51 +296,195 @@ impl VaultManager {
             owner_pubkey
         );
 
-        Ok(vault)
+        Ok((vault, false))
     }
 
-    pub async fn process_deposit(
+    /// Apply a balance change for `tx_signature` exactly once
+    ///
+    /// Runs in a single DB transaction: the signature is advisory-locked, and if it
+    /// was already recorded the vault is returned unchanged with `applied == false`.
+    /// Otherwise the vault row is locked, `apply` mutates it and returns the amount
+    /// to record, and the balance update and transaction row (with `status`) commit together.
+    /// Deposits and withdrawals that would breach the vault's daily limit are rolled back.
+    async fn apply_once<F>(
         state: &AppState,
         vault_pubkey: &str,
-        amount: i64,
         tx_signature: &str,
-    ) -> Result<Vault, VaultError> {
-        let mut vault = Self::get_vault(state, vault_pubkey)
+        tx_type: &str,
+        status: TransactionStatus,
+        apply: F,
+    ) -> Result<(Vault, i64, bool), BackendError>
+    where
+        F: FnOnce(&mut Vault) -> Result<i64, VaultError>,
+    {
+        let mut db_tx = state.database.begin().await?;
+        Database::lock_tx_signature(&mut db_tx, tx_signature).await?;
+
+        if let Some(existing) = Database::get_transaction_by_signature_in(&mut db_tx, tx_signature)
+            .await?
+        {
+            db_tx.commit().await?;
+            tracing::info!(
+                "Transaction {} already processed, skipping {}",
+                tx_signature,
+                tx_type
+            );
+
+            let vault = Self::get_vault(state, vault_pubkey)
+                .await?
+                .ok_or(VaultError::VaultNotFound)?;
+            return Ok((vault, existing.amount, false));
+        }
+
+        let mut vault = Database::get_vault_for_update(&mut db_tx, vault_pubkey)
             .await?
             .ok_or(VaultError::VaultNotFound)?;
 
-        vault.total_balance += amount;
-        vault.available_balance += amount;
-        vault.total_deposited += amount;
+        let amount = apply(&mut vault)?;
+        vault.last_activity = Utc::now();
 
-        state
-            .database
-            .update_vault_balances(
-                vault_pubkey,
-                vault.total_balance,
-                vault.locked_balance,
-                Some(vault.total_deposited),
-                None,
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        if let Err(e) =
+            Self::check_daily_limit(&mut db_tx, &state.config, vault_pubkey, tx_type, amount).await
+        {
+            if matches!(e, BackendError::Vault(VaultError::DailyLimitExceeded { .. })) {
+                db_tx.rollback().await?;
+                Self::raise_daily_limit_alert(state, vault_pubkey, &e).await;
+            }
+            return Err(e);
+        }
 
-        state.cache.set_vault(vault.clone()).await;
-        
-        state
-            .database
-            .record_transaction(
+        Database::apply_balance_change_in(
+            &mut db_tx,
+            &BalanceUpdate {
                 vault_pubkey,
+                total_balance: vault.total_balance,
+                locked_balance: vault.locked_balance,
+                total_deposited: Some(vault.total_deposited),
+                total_withdrawn: Some(vault.total_withdrawn),
+            },
+            &TransactionEntry {
                 tx_signature,
-                "deposit",
+                tx_type,
                 amount,
-                None,
-                None,
-                "confirmed",
-            )
+                from_vault: None,
+                to_vault: None,
+                status: status.as_str(),
+            },
+        )
+        .await?;
+
+        db_tx.commit().await?;
+
+        state.cache.invalidate_vault(vault_pubkey).await;
+
+        Ok((vault, amount, true))
+    }
+
+    /// Reject `amount` if it would take the vault's `tx_type` volume over the last
+    /// [`DAILY_LIMIT_WINDOW_HOURS`] past its limit
+    ///
+    /// Only deposits and withdrawals are limited; a vault limit of 0 falls back to the
+    /// configured default, and a default of 0 means unlimited. The vault row must already be
+    /// locked so concurrent requests can't both fit under the limit.
+    async fn check_daily_limit(
+        db_tx: &mut PgTransaction<'_>,
+        config: &Config,
+        vault_pubkey: &str,
+        tx_type: &str,
+        amount: i64,
+    ) -> Result<(), BackendError> {
+        let settings = Database::get_daily_limit_settings_in(&mut **db_tx, vault_pubkey)
+            .await?
+            .ok_or(VaultError::VaultNotFound)?;
+
+        let (vault_limit, default_limit) = match tx_type {
+            "deposit" => (settings.daily_deposit_limit, config.daily_deposit_limit),
+            "withdraw" => (settings.daily_withdraw_limit, config.daily_withdraw_limit),
+            _ => return Ok(()),
+        };
+        let limit = if vault_limit > 0 { vault_limit } else { default_limit };
+        if limit <= 0 {
+            return Ok(());
+        }
+
+        let since = Utc::now() - chrono::Duration::hours(DAILY_LIMIT_WINDOW_HOURS);
+        let used = Database::get_volume_since_in(db_tx, vault_pubkey, tx_type, since).await?;
+
+        if used.saturating_add(amount) > limit {
+            return Err(VaultError::DailyLimitExceeded {
+                tx_type: tx_type.to_string(),
+                limit,
+                used,
+                amount,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn raise_daily_limit_alert(state: &AppState, vault_pubkey: &str, error: &BackendError) {
+        let message = format!("Vault {}: {}", vault_pubkey, error);
+
+        match state
+            .database
+            .create_alert("daily_limit_exceeded", "warning", Some(vault_pubkey), &message, None)
             .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
-        
+        {
+            Ok(_) => {
+                broadcast_alert("daily_limit_exceeded", "warning", Some(vault_pubkey), &message)
+                    .await;
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to persist daily limit alert for vault {}: {}",
+                    vault_pubkey,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Credit a deposit
+    ///
+    /// With `strict_deposit_verification` the transaction is fetched from chain first and
+    /// must have succeeded and moved exactly `amount` into the vault's token account; it is
+    /// then recorded `confirmed`. Without it the deposit is credited unverified as `pending`.
+    pub async fn process_deposit(
+        state: &AppState,
+        vault_pubkey: &str,
+        amount: i64,
+        tx_signature: &str,
+    ) -> Result<Vault, BackendError> {
+        let already_recorded = state
+            .database
+            .get_transaction_by_signature(tx_signature)
+            .await?
+            .is_some();
+
+        let status = if !state.config.strict_deposit_verification {
+            TransactionStatus::Pending
+        } else {
+            // Replays are no-ops in `apply_once`, so only new signatures hit the RPC
+            if !already_recorded {
+                let vault = Self::get_vault(state, vault_pubkey)
+                    .await?
+                    .ok_or(VaultError::VaultNotFound)?;
+                Self::verify_deposit(state, &vault.token_account, amount, tx_signature).await?;
+            }
+            TransactionStatus::Confirmed
+        };
+
+        let (vault, _, applied) =
+            Self::apply_once(state, vault_pubkey, tx_signature, "deposit", status, |vault| {
+                Self::apply_deposit(vault, amount)
+            })
+            .await?;
+
+        if !applied {
+            return Ok(vault);
+        }
+
         broadcast_deposit(
             vault_pubkey,
             amount,
@@ -179,57 +499,150 @@ impl VaultManager {
             vault.available_balance,
             vault.locked_balance,
         ).await;
+
+        Self::dispatch_webhooks(state, "deposit", &vault, amount, tx_signature).await;
         
         tracing::info!("Processed deposit of {} to vault {}", amount, vault_pubkey);
 
         Ok(vault)
     }
 
-    pub async fn process_withdrawal(
+    /// Check that `tx_signature` succeeded on-chain and moved exactly `amount` into `token_account`
+    async fn verify_deposit(
         state: &AppState,
-        vault_pubkey: &str,
+        token_account: &str,
         amount: i64,
         tx_signature: &str,
-    ) -> Result<Vault, VaultError> {
-        let mut vault = Self::get_vault(state, vault_pubkey)
-            .await?
-            .ok_or(VaultError::VaultNotFound)?;
+    ) -> Result<(), BackendError> {
+        let not_verified =
+            |reason: String| BackendError::from(VaultError::DepositNotVerified(reason));
+
+        let signature = Signature::from_str(tx_signature)
+            .map_err(|_| not_verified("malformed transaction signature".to_string()))?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(history_commitment(&state.solana_client)),
+            max_supported_transaction_version: Some(0),
+        };
 
-        if vault.available_balance < amount {
-            return Err(VaultError::InsufficientBalance);
+        let tx = match rpc_retry("get_transaction", || {
+            state.solana_client.get_transaction_with_config(&signature, config)
+        })
+        .await
+        {
+            Ok(tx) => tx,
+            // The RPC answers `null` for signatures it has no confirmed transaction for
+            Err(e) if matches!(e.kind(), ClientErrorKind::SerdeJson(_)) => {
+                return Err(not_verified(format!("transaction {} not found", tx_signature)));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let meta = tx
+            .transaction
+            .meta
+            .ok_or_else(|| not_verified("transaction has no status metadata".to_string()))?;
+        if let Some(err) = meta.err {
+            return Err(not_verified(format!("transaction failed on-chain: {:?}", err)));
         }
 
-        vault.total_balance -= amount;
-        vault.available_balance -= amount;
-        vault.total_withdrawn += amount;
+        let mut account_keys = match tx.transaction.transaction {
+            EncodedTransaction::Json(ui_tx) => match ui_tx.message {
+                UiMessage::Raw(message) => message.account_keys,
+                UiMessage::Parsed(message) => {
+                    message.account_keys.into_iter().map(|key| key.pubkey).collect()
+                }
+            },
+            _ => return Err(not_verified("unexpected transaction encoding".to_string())),
+        };
+        if let OptionSerializer::Some(loaded) = meta.loaded_addresses {
+            account_keys.extend(loaded.writable);
+            account_keys.extend(loaded.readonly);
+        }
 
-        state
-            .database
-            .update_vault_balances(
-                vault_pubkey,
-                vault.total_balance,
-                vault.locked_balance,
-                None,
-                Some(vault.total_withdrawn),
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        let index = account_keys
+            .iter()
+            .position(|key| key == token_account)
+            .ok_or_else(|| {
+                not_verified("transaction does not touch the vault token account".to_string())
+            })?;
 
-        state.cache.set_vault(vault.clone()).await;
+        let balance_at = |balances: OptionSerializer<Vec<UiTransactionTokenBalance>>| {
+            Option::<Vec<_>>::from(balances)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|b| usize::from(b.account_index) == index)
+                .and_then(|b| b.ui_token_amount.amount.parse::<i128>().ok())
+                .unwrap_or(0)
+        };
+        let received = balance_at(meta.post_token_balances) - balance_at(meta.pre_token_balances);
 
-        state
-            .database
-            .record_transaction(
-                vault_pubkey,
-                tx_signature,
-                "withdraw",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        if received != i128::from(amount) {
+            return Err(not_verified(format!(
+                "vault token account received {}, expected {}",
+                received, amount
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn process_withdrawal(
+        state: &AppState,
+        vault_pubkey: &str,
+        amount: i64,
+        tx_signature: &str,
+    ) -> Result<Vault, BackendError> {
+        Self::process_withdrawal_with(state, vault_pubkey, tx_signature, |_| Ok(amount)).await
+    }
+
+    /// Record a `withdraw_all`, withdrawing the vault's entire available balance
+    pub async fn process_withdraw_all(
+        state: &AppState,
+        vault_pubkey: &str,
+        tx_signature: &str,
+    ) -> Result<Vault, BackendError> {
+        Self::process_withdrawal_with(state, vault_pubkey, tx_signature, |vault| {
+            if vault.available_balance <= 0 {
+                return Err(VaultError::InsufficientBalance);
+            }
+            let max = state.config.max_operation_amount;
+            if max != 0 && vault.available_balance as u64 > max {
+                return Err(VaultError::AmountExceedsMax { amount: vault.available_balance, max });
+            }
+            Ok(vault.available_balance)
+        })
+        .await
+    }
+
+    /// Shared withdrawal path; `amount_for` picks the amount from the locked vault row
+    async fn process_withdrawal_with<F>(
+        state: &AppState,
+        vault_pubkey: &str,
+        tx_signature: &str,
+        amount_for: F,
+    ) -> Result<Vault, BackendError>
+    where
+        F: FnOnce(&Vault) -> Result<i64, VaultError>,
+    {
+        Self::ensure_not_frozen(state, vault_pubkey).await?;
+
+        let (vault, amount, applied) = Self::apply_once(
+            state,
+            vault_pubkey,
+            tx_signature,
+            "withdraw",
+            TransactionStatus::Pending,
+            |vault| {
+                let amount = amount_for(vault)?;
+                Self::apply_withdrawal(vault, amount)
+            },
+        )
+        .await?;
+
+        if !applied {
+            return Ok(vault);
+        }
 
         broadcast_withdrawal(
             vault_pubkey,
@@ -246,6 +659,8 @@ impl VaultManager {
             vault.locked_balance,
         ).await;
 
+        Self::dispatch_webhooks(state, "withdraw", &vault, amount, tx_signature).await;
+
         tracing::info!(
             "Processed withdrawal of {} from vault {}",
             amount,
@@ -260,47 +675,21 @@ impl VaultManager {
         vault_pubkey: &str,
         amount: i64,
         tx_signature: &str,
-    ) -> Result<Vault, VaultError> {
-        let mut vault = Self::get_vault(state, vault_pubkey)
-            .await?
-            .ok_or(VaultError::VaultNotFound)?;
-            
-        if vault.available_balance < amount {
-            return Err(VaultError::InsufficientBalance);
+    ) -> Result<Vault, BackendError> {
+        let (vault, _, applied) = Self::apply_once(
+            state,
+            vault_pubkey,
+            tx_signature,
+            "lock",
+            TransactionStatus::Pending,
+            |vault| Self::apply_lock(vault, amount),
+        )
+        .await?;
+
+        if !applied {
+            return Ok(vault);
         }
 
-        vault.locked_balance += amount;
-        vault.available_balance -= amount;
-
-        state
-            .database
-            .update_vault_balances(
-                vault_pubkey,
-                vault.total_balance,
-                vault.locked_balance,
-                None,
-                None,
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
-            
-        state.cache.set_vault(vault.clone()).await;
-
-        state
-            .database
-            .record_transaction(
-                vault_pubkey,
-                tx_signature,
-                "lock",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
-
-
         broadcast_lock(
             vault_pubkey,
             amount,
@@ -308,6 +697,8 @@ impl VaultManager {
             vault.available_balance,
         ).await;
 
+        Self::dispatch_webhooks(state, "lock", &vault, amount, tx_signature).await;
+
         tracing::info!("Locked {} collateral in vault {}", amount, vault_pubkey);
 
         Ok(vault)
@@ -318,75 +709,225 @@ impl VaultManager {
         vault_pubkey: &str,
         amount: i64,
         tx_signature: &str,
-    ) -> Result<Vault, VaultError> {
-        let mut vault = Self::get_vault(state, vault_pubkey)
-            .await?
-            .ok_or(VaultError::VaultNotFound)?;
+    ) -> Result<Vault, BackendError> {
+        let (vault, _, applied) = Self::apply_once(
+            state,
+            vault_pubkey,
+            tx_signature,
+            "unlock",
+            TransactionStatus::Pending,
+            |vault| Self::apply_unlock(vault, amount),
+        )
+        .await?;
+
+        if !applied {
+            return Ok(vault);
+        }
+
+        broadcast_unlock(
+            vault_pubkey,
+            amount,
+            vault.locked_balance,
+            vault.available_balance,
+        ).await;
+
+        Self::dispatch_webhooks(state, "unlock", &vault, amount, tx_signature).await;
+
+        tracing::info!("Unlocked {} collateral in vault {}", amount, vault_pubkey);
+
+        Ok(vault)
+    }
+
+    fn apply_deposit(vault: &mut Vault, amount: i64) -> Result<i64, VaultError> {
+        vault.total_balance += amount;
+        vault.available_balance += amount;
+        vault.total_deposited += amount;
+        Ok(amount)
+    }
 
+    fn apply_withdrawal(vault: &mut Vault, amount: i64) -> Result<i64, VaultError> {
+        if vault.available_balance < amount {
+            return Err(VaultError::InsufficientBalance);
+        }
+
+        vault.total_balance -= amount;
+        vault.available_balance -= amount;
+        vault.total_withdrawn += amount;
+        Ok(amount)
+    }
+
+    fn apply_lock(vault: &mut Vault, amount: i64) -> Result<i64, VaultError> {
+        if vault.available_balance < amount {
+            return Err(VaultError::InsufficientBalance);
+        }
+
+        vault.locked_balance += amount;
+        vault.available_balance -= amount;
+        Ok(amount)
+    }
+
+    fn apply_unlock(vault: &mut Vault, amount: i64) -> Result<i64, VaultError> {
         if vault.locked_balance < amount {
             return Err(VaultError::InsufficientLockedBalance);
         }
 
         vault.locked_balance -= amount;
         vault.available_balance += amount;
+        Ok(amount)
+    }
 
-        state
-            .database
-            .update_vault_balances(
-                vault_pubkey,
-                vault.total_balance,
-                vault.locked_balance,
-                None,
-                None,
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
-
-        state.cache.set_vault(vault.clone()).await;
+    /// Project `operation` of `amount` onto `vault` without recording anything
+    ///
+    /// Runs the checks the `process_*` path would (pause, freeze, balances, daily limit) and
+    /// returns the vault as it would be afterwards, or the error the real operation would hit.
+    pub async fn simulate(
+        state: &AppState,
+        vault: &Vault,
+        operation: &TransactionType,
+        amount: i64,
+    ) -> Result<Vault, BackendError> {
+        Self::ensure_not_paused(state)?;
+
+        let mut projected = vault.clone();
+        match operation {
+            TransactionType::Deposit => Self::apply_deposit(&mut projected, amount)?,
+            TransactionType::Withdraw => {
+                Self::ensure_not_frozen(state, &vault.vault_pubkey).await?;
+                Self::apply_withdrawal(&mut projected, amount)?
+            }
+            TransactionType::Lock => Self::apply_lock(&mut projected, amount)?,
+            TransactionType::Unlock => Self::apply_unlock(&mut projected, amount)?,
+            other => {
+                return Err(VaultError::NotImplemented(format!(
+                    "simulating {}",
+                    other.as_str()
+                ))
+                .into())
+            }
+        };
 
-        state
-            .database
-            .record_transaction(
-                vault_pubkey,
-                tx_signature,
-                "unlock",
-                amount,
-                None,
-                None,
-                "confirmed",
-            )
-            .await
-            .map_err(|e| VaultError::DatabaseError(e.to_string()))?;
+        // Nothing is written, so the transaction only scopes the daily volume read
+        let mut db_tx = state.database.begin().await?;
+        let limit = Self::check_daily_limit(
+            &mut db_tx,
+            &state.config,
+            &vault.vault_pubkey,
+            operation.as_str(),
+            amount,
+        )
+        .await;
+        db_tx.rollback().await?;
+        limit?;
 
+        Ok(projected)
+    }
 
-        broadcast_unlock(
-            vault_pubkey,
+    async fn dispatch_webhooks(
+        state: &AppState,
+        event_type: &str,
+        vault: &Vault,
+        amount: i64,
+        tx_signature: &str,
+    ) {
+        let data = webhook_dispatcher::balance_event_data(
             amount,
-            vault.locked_balance,
+            tx_signature,
+            vault.total_balance,
             vault.available_balance,
-        ).await;
-
-        tracing::info!("Unlocked {} collateral in vault {}", amount, vault_pubkey);
-
-        Ok(vault)
+            vault.locked_balance,
+        );
+        webhook_dispatcher::dispatch(state, event_type, &vault.vault_pubkey, data).await;
     }
     
-    fn parse_vault_account(data: &[u8], vault_pubkey: &str) -> Result<Vault, VaultError> {
+    /// Decode a `VaultAuthority` account: discriminator, Borsh `Vec<Pubkey>`, bump
+    pub(crate) fn parse_vault_authority_account(data: &[u8]) -> Result<Vec<Pubkey>, VaultError> {
+        let too_short = || VaultError::DeserializationError("VaultAuthority data too short".into());
+
+        let len_bytes: [u8; 4] = data.get(8..12).ok_or_else(too_short)?.try_into().unwrap();
+        let count = u32::from_le_bytes(len_bytes) as usize;
+        let programs = data
+            .get(12..12 + count * 32)
+            .ok_or_else(too_short)?
+            .chunks_exact(32)
+            .map(|bytes| Pubkey::try_from(bytes).unwrap())
+            .collect();
+
+        Ok(programs)
+    }
+
+    /// Fail unless `data` opens with the `CollateralVault` discriminator, so another
+    /// account type is never read at vault offsets
+    fn ensure_collateral_vault(data: &[u8]) -> Result<(), VaultError> {
+        if data.get(..8) != Some(&account_discriminator(COLLATERAL_VAULT_ACCOUNT)[..]) {
+            return Err(VaultError::DeserializationError(format!(
+                "Account is not a {}: discriminator mismatch",
+                COLLATERAL_VAULT_ACCOUNT
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decode a `CollateralVault` account, branching on its layout version
+    ///
+    /// v0 is the unversioned layout (`owner` at offset 0, 113+ bytes); v1 adds a
+    /// leading `version` byte and a trailing `creator` (146 bytes); v2 appends
+    /// `min_deposit` and `max_balance` (162 bytes); v3 appends `nonce` (170
+    /// bytes, see `parse_vault_nonce`); v4 appends `position_locked` (178 bytes);
+    /// v5 appends `last_activity` (186 bytes), which older layouts fall back to
+    /// `created_at` for. v0 is told apart by length since it has no version byte to read.
+    pub(crate) fn parse_vault_account(data: &[u8], vault_pubkey: &str) -> Result<Vault, VaultError> {
         if data.len() < 8 {
             return Err(VaultError::DeserializationError(
                 "Account data too short".to_string(),
             ));
         }
+        Self::ensure_collateral_vault(data)?;
 
-        let vault_data = &data[8..];
+        let account_data = &data[8..];
 
-        if vault_data.len() < 113 {
-            // 32 + 32 + 8*5 + 8 + 1 = 113
-            return Err(VaultError::DeserializationError(format!(
-                "Vault data too short: expected 113 bytes, got {}",
-                vault_data.len()
-            )));
-        }
+        let version = if account_data.len() >= VAULT_V1_LEN {
+            account_data[0]
+        } else {
+            0
+        };
+
+        let vault_data = match version {
+            0 => {
+                if account_data.len() < VAULT_V0_LEN {
+                    // 32 + 32 + 8*5 + 8 + 1 = 113
+                    return Err(VaultError::DeserializationError(format!(
+                        "Vault data too short: expected {} bytes, got {}",
+                        VAULT_V0_LEN,
+                        account_data.len()
+                    )));
+                }
+                account_data
+            }
+            // v1+ prepend the version byte; the remaining fields keep their order
+            1 => &account_data[1..],
+            2..=5 => {
+                let expected = match version {
+                    2 => VAULT_V2_LEN,
+                    3 => VAULT_V3_LEN,
+                    4 => VAULT_V4_LEN,
+                    _ => VAULT_V5_LEN,
+                };
+                if account_data.len() < expected {
+                    return Err(VaultError::DeserializationError(format!(
+                        "Vault data too short: expected {} bytes, got {}",
+                        expected,
+                        account_data.len()
+                    )));
+                }
+                &account_data[1..]
+            }
+            v => {
+                return Err(VaultError::DeserializationError(format!(
+                    "Unsupported vault layout version {}",
+                    v
+                )));
+            }
+        };
 
         fn read_pubkey(data: &[u8], offset: usize) -> Result<String, VaultError> {
             if offset + 32 > data.len() {
@@ -449,9 +990,30 @@ impl VaultManager {
 
         let created_at_unix = read_i64(vault_data, offset)?;
 
-        let created_at = chrono::DateTime::from_timestamp(created_at_unix, 0).ok_or(
-            VaultError::DeserializationError("Invalid timestamp".to_string()),
-        )?;
+        // v2 limits follow bump (1) and creator (32)
+        let (min_deposit, max_balance) = if version >= 2 {
+            let limits_offset = VAULT_V0_LEN + 32;
+            (
+                read_u64(vault_data, limits_offset)? as i64,
+                read_u64(vault_data, limits_offset + 8)? as i64,
+            )
+        } else {
+            (0, 0)
+        };
+
+        // v5 `last_activity` follows the limits, nonce and position_locked
+        let last_activity_unix = if version >= 5 {
+            read_i64(vault_data, VAULT_V0_LEN + 32 + 8 * 4)?
+        } else {
+            created_at_unix
+        };
+
+        let timestamp = |unix: i64| {
+            chrono::DateTime::from_timestamp(unix, 0)
+                .ok_or(VaultError::DeserializationError("Invalid timestamp".to_string()))
+        };
+        let created_at = timestamp(created_at_unix)?;
+        let last_activity = timestamp(last_activity_unix)?;
 
         if total_balance != (available_balance + locked_balance) {
             tracing::warn!(
@@ -474,16 +1036,69 @@ impl VaultManager {
             total_withdrawn,
             created_at,
             updated_at: Utc::now(),
+            min_deposit,
+            max_balance,
+            decimals: USDT_DECIMALS as i16,
+            token_program: TokenProgram::default().to_string(),
+            last_activity,
         })
     }
 }
 
+/// Anchor account name of the vault, whose discriminator prefixes its data
+pub const COLLATERAL_VAULT_ACCOUNT: &str = "CollateralVault";
+
+/// Unversioned vault layout: 32 + 32 + 8*5 + 8 + 1
+pub const VAULT_V0_LEN: usize = 113;
+/// Versioned vault layout: version + v0 fields + creator
+pub const VAULT_V1_LEN: usize = 1 + VAULT_V0_LEN + 32;
+/// v1 + min_deposit + max_balance
+pub const VAULT_V2_LEN: usize = VAULT_V1_LEN + 8 + 8;
+/// v2 + nonce
+pub const VAULT_V3_LEN: usize = VAULT_V2_LEN + 8;
+/// v3 + position_locked
+pub const VAULT_V4_LEN: usize = VAULT_V3_LEN + 8;
+/// v4 + last_activity
+pub const VAULT_V5_LEN: usize = VAULT_V4_LEN + 8;
+
+/// Fields mirrored from the vault account whose values differ; `updated_at` is off-chain only
+fn diff_vaults(onchain: &Vault, database: &Vault) -> Vec<VaultFieldDiff> {
+    fn field<T: PartialEq + serde::Serialize>(
+        diff: &mut Vec<VaultFieldDiff>,
+        name: &str,
+        onchain: &T,
+        database: &T,
+    ) {
+        if onchain != database {
+            diff.push(VaultFieldDiff {
+                field: name.to_string(),
+                onchain: serde_json::json!(onchain),
+                database: serde_json::json!(database),
+            });
+        }
+    }
+
+    let mut diff = Vec::new();
+    field(&mut diff, "owner_pubkey", &onchain.owner_pubkey, &database.owner_pubkey);
+    field(&mut diff, "token_account", &onchain.token_account, &database.token_account);
+    field(&mut diff, "total_balance", &onchain.total_balance, &database.total_balance);
+    field(&mut diff, "locked_balance", &onchain.locked_balance, &database.locked_balance);
+    field(&mut diff, "available_balance", &onchain.available_balance, &database.available_balance);
+    field(&mut diff, "total_deposited", &onchain.total_deposited, &database.total_deposited);
+    field(&mut diff, "total_withdrawn", &onchain.total_withdrawn, &database.total_withdrawn);
+    field(
+        &mut diff,
+        "created_at",
+        &onchain.created_at.timestamp(),
+        &database.created_at.timestamp(),
+    );
+    field(&mut diff, "min_deposit", &onchain.min_deposit, &database.min_deposit);
+    field(&mut diff, "max_balance", &onchain.max_balance, &database.max_balance);
+    diff
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum VaultError {
-    #[error("Database error: {0}")]
-    DatabaseError(String),
-    #[error("Solana RPC Error: {0}")]
-    SolanaRpcError(String),
     #[error("Invalid Pubkey Format")]
     InvalidPubkey,
     #[error("Vault not found")]
@@ -496,4 +1111,21 @@ pub enum VaultError {
     NotImplemented(String),
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
+    #[error("Deposit not verified on-chain: {0}")]
+    DepositNotVerified(String),
+    #[error("Program {0} is not authorized for this vault")]
+    ProgramNotAuthorized(String),
+    #[error("Vault program is globally paused")]
+    ProgramPaused,
+    #[error("Vault is frozen: withdrawals are disabled")]
+    VaultFrozen,
+    #[error("Amount {amount} exceeds the per-operation maximum of {max}")]
+    AmountExceedsMax { amount: i64, max: u64 },
+    #[error("Daily {tx_type} limit exceeded: {used} of {limit} used, {amount} requested")]
+    DailyLimitExceeded {
+        tx_type: String,
+        limit: i64,
+        used: i64,
+        amount: i64,
+    },
 }
\ No newline at end of file
@@ -1,30 +1,133 @@
 use std::time::Duration;
 
 use actix_web::web::Data;
+use shared::{AlertSeverity, VaultListParams};
 use tokio::time;
 
-use crate::services::{AppState, BalanceTracker};
+use crate::services::{AppState, BackgroundService, BalanceTracker};
+use crate::websocket::broadcast_alert;
+
+/// How often closed alerts past their retention are pruned
+pub const ALERT_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
 
 pub async fn run_monitor(state: Data<AppState>) {
     let interval_secs = state.config.monitoring_interval_seconds;
     let mut interval = time::interval(Duration::from_secs(interval_secs));
+    let mut prune_interval = time::interval(ALERT_PRUNE_INTERVAL);
     tracing::info!("Vault Monitor started (interval: {}s)", interval_secs);
     loop {
-        interval.tick().await;
-        if let Err(e) = monitor_cycle(&state).await {
-            tracing::error!("Monitor cycle error: {}", e);
+        tokio::select! {
+            _ = interval.tick() => {
+                state.heartbeats.beat(BackgroundService::Monitor);
+                if let Err(e) = monitor_cycle(&state).await {
+                    tracing::error!("Monitor cycle error: {}", e);
+                }
+            }
+            _ = prune_interval.tick() => {
+                if let Err(e) = prune_expired_alerts(&state).await {
+                    tracing::error!("Alert pruning failed: {}", e);
+                }
+            }
         }
     }
 }
 
+/// Delete resolved and acknowledged alerts older than `ALERT_RETENTION_DAYS`, keeping
+/// critical ones until `CRITICAL_ALERT_RETENTION_DAYS`
+///
+/// Returns the number of non-critical and critical alerts pruned. An
+/// `ALERT_RETENTION_DAYS` of 0 disables pruning.
+pub async fn prune_expired_alerts(state: &AppState) -> Result<(u64, u64), MonitorError> {
+    let retention_days = state.config.alert_retention_days;
+    if retention_days == 0 {
+        return Ok((0, 0));
+    }
+    let critical_days = state.config.critical_alert_retention_days.max(retention_days);
+    let now = chrono::Utc::now();
+
+    let pruned = state
+        .database
+        .prune_alerts(now - chrono::Duration::days(retention_days as i64), true)
+        .await
+        .map_err(|e| MonitorError::DatabaseError(e.to_string()))?;
+    let critical_pruned = state
+        .database
+        .prune_alerts(now - chrono::Duration::days(critical_days as i64), false)
+        .await
+        .map_err(|e| MonitorError::DatabaseError(e.to_string()))?;
+
+    if pruned + critical_pruned > 0 {
+        tracing::info!(
+            "Pruned {} alerts older than {} days and {} critical alerts older than {} days",
+            pruned,
+            retention_days,
+            critical_pruned,
+            critical_days
+        );
+    }
+    Ok((pruned, critical_pruned))
+}
+
+/// Raise a `dormant_vault` alert for every active vault whose `last_activity` is older
+/// than `DORMANT_VAULT_DAYS`
+///
+/// A vault is flagged at most once per window. Returns the vaults flagged; a
+/// `DORMANT_VAULT_DAYS` of 0 disables the check.
+pub async fn flag_dormant_vaults(state: &AppState) -> Result<Vec<String>, MonitorError> {
+    let dormant_days = state.config.dormant_vault_days;
+    if dormant_days == 0 {
+        return Ok(Vec::new());
+    }
+    let now = chrono::Utc::now();
+
+    let dormant = state
+        .database
+        .get_dormant_vaults(now - chrono::Duration::days(dormant_days as i64))
+        .await
+        .map_err(|e| MonitorError::DatabaseError(e.to_string()))?;
+
+    let severity = AlertSeverity::Info.as_str();
+    let mut flagged = Vec::with_capacity(dormant.len());
+    for (vault_pubkey, last_activity) in dormant {
+        let message = format!(
+            "No activity for {} days (last activity {})",
+            (now - last_activity).num_days(),
+            last_activity.to_rfc3339()
+        );
+        state
+            .database
+            .create_alert("dormant_vault", severity, Some(&vault_pubkey), &message, None)
+            .await
+            .map_err(|e| MonitorError::DatabaseError(e.to_string()))?;
+        broadcast_alert("dormant_vault", severity, Some(&vault_pubkey), &message).await;
+
+        flagged.push(vault_pubkey);
+    }
+
+    if !flagged.is_empty() {
+        tracing::info!(
+            "Flagged {} vaults dormant for over {} days",
+            flagged.len(),
+            dormant_days
+        );
+    }
+    Ok(flagged)
+}
+
 async fn monitor_cycle(state: &AppState) -> Result<(), MonitorError> {
     tracing::debug!("Running monitoring cycle...");
     let vaults = state
         .database
-        .get_all_vaults(10000, 0)
+        .get_all_vaults(&VaultListParams {
+            limit: 10000,
+            ..Default::default()
+        })
         .await
         .map_err(|e| MonitorError::DatabaseError(e.to_string()))?;
     tracing::debug!("Monitoring {} vaults", vaults.len());
+    if let Err(e) = flag_dormant_vaults(state).await {
+        tracing::error!("Dormant vault check failed: {}", e);
+    }
     for vault in vaults {
         if vault.vault_pubkey.len() < 32 || vault.vault_pubkey.len() > 44 {
             tracing::debug!("Skipping vault with invalid pubkey: {}", vault.vault_pubkey);
@@ -37,17 +140,12 @@ async fn monitor_cycle(state: &AppState) -> Result<(), MonitorError> {
                 e
             );
         }
-        let threshold = (vault.total_balance as f64 * 0.1) as i64;
-        if threshold > 0 {
-            if let Err(e) =
-                BalanceTracker::check_low_balances(state, &vault.vault_pubkey, threshold).await
-            {
-                tracing::error!(
-                    "Low balance check failed for vault {}: {}",
-                    vault.vault_pubkey,
-                    e
-                );
-            }
+        if let Err(e) = BalanceTracker::check_low_balances(state, &vault.vault_pubkey).await {
+            tracing::error!(
+                "Low balance check failed for vault {}: {}",
+                vault.vault_pubkey,
+                e
+            );
         }
         match BalanceTracker::calculate_utilization(state, &vault.vault_pubkey).await {
             Ok(utilization) if utilization > 90.0 => {
@@ -57,16 +155,35 @@ async fn monitor_cycle(state: &AppState) -> Result<(), MonitorError> {
                     utilization
                 );
 
-                let _ = state
+                let message = format!("Vault utilization at {:.2}%", utilization);
+                match state
                     .database
                     .create_alert(
                         "high_utilization",
                         "warning",
                         Some(&vault.vault_pubkey),
-                        &format!("Vault utilization at {:.2}%", utilization),
+                        &message,
                         None,
                     )
-                    .await;
+                    .await
+                {
+                    Ok(_) => {
+                        broadcast_alert(
+                            "high_utilization",
+                            "warning",
+                            Some(&vault.vault_pubkey),
+                            &message,
+                        )
+                        .await;
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to persist high utilization alert for vault {}: {}",
+                            vault.vault_pubkey,
+                            e
+                        );
+                    }
+                }
             }
             Err(e) => {
                 tracing::error!(
@@ -0,0 +1,160 @@
+//! Server-to-server HTTP callbacks for vault events
+//!
+//! Every active webhook subscribed to an event gets a JSON POST signed with its
+//! secret: `X-Signature` holds the hex HMAC-SHA256 of the raw body. Deliveries
+//! run in the background and retry failures with backoff, so a slow receiver
+//! never holds up a vault operation.
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::Value as JsonValue;
+use sha2::Sha256;
+
+use crate::services::AppState;
+
+/// Event types a webhook can subscribe to
+pub const WEBHOOK_EVENT_TYPES: &[&str] = &[
+    "deposit",
+    "withdraw",
+    "lock",
+    "unlock",
+    "transfer",
+    "liquidation",
+    "vault_closed",
+];
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Attempts per delivery before it is dropped
+pub const WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubled on each further attempt
+const WEBHOOK_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .expect("Failed to build webhook HTTP client")
+});
+
+/// Hex HMAC-SHA256 of `body` keyed by `secret`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// `data` for events that move a vault's balances
+pub fn balance_event_data(
+    amount: i64,
+    tx_signature: &str,
+    total_balance: i64,
+    available_balance: i64,
+    locked_balance: i64,
+) -> JsonValue {
+    serde_json::json!({
+        "amount": amount,
+        "tx_signature": tx_signature,
+        "total_balance": total_balance,
+        "available_balance": available_balance,
+        "locked_balance": locked_balance,
+    })
+}
+
+/// Notify every webhook subscribed to `event_type` on `vault_pubkey`
+///
+/// Only the webhook lookup is awaited; deliveries are spawned. Failures are
+/// logged, never returned, since the event itself has already been applied.
+pub async fn dispatch(state: &AppState, event_type: &str, vault_pubkey: &str, data: JsonValue) {
+    let webhooks = match state
+        .database
+        .get_webhooks_for_event(event_type, vault_pubkey)
+        .await
+    {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            tracing::error!("Failed to load webhooks for {} on {}: {}", event_type, vault_pubkey, e);
+            return;
+        }
+    };
+
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let body = serde_json::json!({
+        "event_type": event_type,
+        "vault_pubkey": vault_pubkey,
+        "timestamp": chrono::Utc::now().timestamp(),
+        "data": data,
+    })
+    .to_string();
+
+    for webhook in webhooks {
+        let body = body.clone();
+        let event_type = event_type.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = deliver(&webhook.url, &webhook.secret, body).await {
+                tracing::error!(
+                    "Dropped {} delivery to webhook {} after {} attempts: {}",
+                    event_type,
+                    webhook.id,
+                    WEBHOOK_MAX_ATTEMPTS,
+                    e
+                );
+            }
+        });
+    }
+}
+
+/// POST `body` to `url`, retrying errors and non-2xx responses with exponential backoff
+pub(crate) async fn deliver(url: &str, secret: &str, body: String) -> Result<(), WebhookError> {
+    let signature = sign(secret, body.as_bytes());
+
+    let mut attempt = 1;
+    loop {
+        let result = HTTP_CLIENT
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.clone())
+            .send()
+            .await;
+
+        let error = match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => WebhookError::Rejected(response.status().as_u16()),
+            Err(e) => WebhookError::RequestFailed(e.to_string()),
+        };
+
+        if attempt >= WEBHOOK_MAX_ATTEMPTS {
+            return Err(error);
+        }
+
+        let delay = WEBHOOK_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+        tracing::warn!(
+            "Webhook delivery to {} failed (attempt {}/{}), retrying in {:?}: {}",
+            url,
+            attempt,
+            WEBHOOK_MAX_ATTEMPTS,
+            delay,
+            error
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Receiver responded with status {0}")]
+    Rejected(u16),
+
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+}
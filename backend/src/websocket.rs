@@ -4,6 +4,10 @@ use dashmap::DashMap;
 use futures_util::StreamExt;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use bytes::Bytes;
@@ -11,9 +15,38 @@ use tokio::sync::broadcast;
 use tokio::time::interval;
 use uuid::Uuid;
 
+use crate::monitering::{add_ws_dropped_messages, set_ws_clients};
+use crate::services::{AppState, VaultManager};
+
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
-const BROADCAST_CHANNEL_SIZE: usize = 1000;
+/// Close frame description sent to a client that stopped answering pings
+pub const HEARTBEAT_TIMEOUT_REASON: &str = "heartbeat timeout";
+/// First reconnect delay suggested to clients in `Connected`
+pub const RECONNECT_BASE_DELAY_MS: u64 = 1_000;
+/// Ceiling for the doubling reconnect delay suggested in `Connected`
+pub const RECONNECT_MAX_DELAY_MS: u64 = 30_000;
+/// Messages buffered per client before the oldest are dropped
+pub const BROADCAST_CHANNEL_SIZE: usize = 1000;
+
+/// Sustained client message rate (tokens refilled per second)
+pub const RATE_LIMIT_PER_SEC: f64 = 20.0;
+/// Maximum burst of client messages
+pub const RATE_LIMIT_BURST: f64 = 40.0;
+/// Rate-limited messages tolerated before the client is disconnected
+pub const RATE_LIMIT_MAX_VIOLATIONS: u32 = 100;
+/// Quiet period after which the violation count resets
+const RATE_LIMIT_VIOLATION_WINDOW: Duration = Duration::from_secs(10);
+/// Error code sent to every client when the server begins shutting down
+pub const SHUTDOWN_CODE: &str = "SERVER_SHUTDOWN";
+/// How long shutdown waits for WebSocket clients to disconnect
+pub const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Vaults listed in `WebSocketStats::top_vaults`
+pub const STATS_TOP_VAULTS: usize = 10;
+/// Close frame description sent to a connection refused because `WS_MAX_CLIENTS` is reached
+pub const TOO_MANY_CLIENTS_REASON: &str = "too many clients";
+/// Error code sent for a subscribe beyond `WS_MAX_SUBS_PER_CLIENT`
+pub const TOO_MANY_SUBSCRIPTIONS_CODE: &str = "TOO_MANY_SUBSCRIPTIONS";
 
 
 pub static WS_REGISTRY: Lazy<WebSocketRegistry> = Lazy::new(WebSocketRegistry::new);
@@ -33,6 +66,21 @@ pub struct WebSocketRegistry {
     vault_subscriptions: DashMap<String, DashMap<String, ()>>,
     
     global_broadcast: broadcast::Sender<WsMessage>,
+
+    /// Connected clients allowed at once (0 for no limit)
+    max_clients: AtomicUsize,
+
+    /// Vaults a single client may subscribe to (0 for no limit)
+    max_subs_per_client: AtomicUsize,
+}
+
+/// A connection or subscription refused by the registry's limits
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WsLimitError {
+    #[error("Server has reached its limit of {0} WebSocket clients")]
+    TooManyClients(usize),
+    #[error("Client has reached its limit of {0} vault subscriptions")]
+    TooManySubscriptions(usize),
 }
 
 impl WebSocketRegistry {
@@ -42,11 +90,28 @@ impl WebSocketRegistry {
             clients: DashMap::new(),
             vault_subscriptions: DashMap::new(),
             global_broadcast,
+            max_clients: AtomicUsize::new(0),
+            max_subs_per_client: AtomicUsize::new(0),
         }
     }
 
+    /// Apply `WS_MAX_CLIENTS` and `WS_MAX_SUBS_PER_CLIENT`; 0 lifts a limit
+    ///
+    /// Only new connections and subscriptions are checked, existing ones are kept.
+    pub fn set_limits(&self, max_clients: usize, max_subs_per_client: usize) {
+        self.max_clients.store(max_clients, Ordering::Relaxed);
+        self.max_subs_per_client.store(max_subs_per_client, Ordering::Relaxed);
+    }
+
+
+    pub fn register_client(
+        &self,
+    ) -> Result<(String, broadcast::Receiver<WsMessage>), WsLimitError> {
+        let max_clients = self.max_clients.load(Ordering::Relaxed);
+        if max_clients > 0 && self.clients.len() >= max_clients {
+            return Err(WsLimitError::TooManyClients(max_clients));
+        }
 
-    pub fn register_client(&self) -> (String, broadcast::Receiver<WsMessage>) {
         let client_id = Uuid::new_v4().to_string();
         let (sender, receiver) = broadcast::channel(BROADCAST_CHANNEL_SIZE);
         
@@ -58,9 +123,10 @@ impl WebSocketRegistry {
         };
         
         self.clients.insert(client_id.clone(), connection);
+        set_ws_clients(self.clients.len() as f64);
         tracing::info!("Registered new WebSocket client: {}", client_id);
         
-        (client_id, receiver)
+        Ok((client_id, receiver))
     }
 
 
@@ -73,6 +139,7 @@ impl WebSocketRegistry {
                     subscribers.remove(client_id);
                 }
             }
+            set_ws_clients(self.clients.len() as f64);
             tracing::info!(
                 "Unregistered WebSocket client: {} (was connected for {:?})",
                 client_id,
@@ -81,9 +148,23 @@ impl WebSocketRegistry {
         }
     }
 
-    
-    pub fn subscribe_to_vault(&self, client_id: &str, vault_pubkey: &str) -> bool {
+    /// Subscribe a client to a vault, `Ok(false)` if the client isn't registered
+    ///
+    /// Re-subscribing to a vault the client already follows doesn't count
+    /// against `WS_MAX_SUBS_PER_CLIENT`.
+    pub fn subscribe_to_vault(
+        &self,
+        client_id: &str,
+        vault_pubkey: &str,
+    ) -> Result<bool, WsLimitError> {
         if let Some(connection) = self.clients.get(client_id) {
+            let max_subs = self.max_subs_per_client.load(Ordering::Relaxed);
+            if max_subs > 0
+                && !connection.subscribed_vaults.contains_key(vault_pubkey)
+                && connection.subscribed_vaults.len() >= max_subs
+            {
+                return Err(WsLimitError::TooManySubscriptions(max_subs));
+            }
 
             connection.subscribed_vaults.insert(vault_pubkey.to_string(), ());
             
@@ -94,9 +175,9 @@ impl WebSocketRegistry {
                 .insert(client_id.to_string(), ());
             
             tracing::debug!("Client {} subscribed to vault {}", client_id, vault_pubkey);
-            return true;
+            return Ok(true);
         }
-        false
+        Ok(false)
     }
 
     
@@ -174,6 +255,57 @@ impl WebSocketRegistry {
     pub fn get_client_sender(&self, client_id: &str) -> Option<broadcast::Sender<WsMessage>> {
         self.clients.get(client_id).map(|c| c.sender.clone())
     }
+
+    /// Snapshot of connection load, listing the `top` most subscribed vaults
+    pub fn stats(&self, top: usize) -> WebSocketStats {
+        let mut vault_counts: Vec<VaultSubscriberCount> = self
+            .vault_subscriptions
+            .iter()
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| VaultSubscriberCount {
+                vault_pubkey: entry.key().clone(),
+                subscribers: entry.value().len(),
+            })
+            .collect();
+
+        let total_vault_subscriptions = vault_counts.iter().map(|v| v.subscribers).sum();
+
+        vault_counts.sort_by(|a, b| {
+            b.subscribers
+                .cmp(&a.subscribers)
+                .then_with(|| a.vault_pubkey.cmp(&b.vault_pubkey))
+        });
+        vault_counts.truncate(top);
+
+        let ages: Vec<f64> = self
+            .clients
+            .iter()
+            .map(|c| c.connected_at.elapsed().as_secs_f64())
+            .collect();
+        let avg_connection_age_secs = if ages.is_empty() {
+            0.0
+        } else {
+            ages.iter().sum::<f64>() / ages.len() as f64
+        };
+
+        WebSocketStats {
+            total_clients: ages.len(),
+            total_vault_subscriptions,
+            top_vaults: vault_counts,
+            avg_connection_age_secs,
+        }
+    }
+
+    /// Wait until every client has disconnected or `timeout` elapses
+    ///
+    /// Returns the number of clients still connected when the wait ended.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+        while self.client_count() > 0 && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        self.client_count()
+    }
 }
 
 impl Default for WebSocketRegistry {
@@ -182,6 +314,24 @@ impl Default for WebSocketRegistry {
     }
 }
 
+/// Backoff a client should use when reconnecting after the socket closes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconnectHint {
+    /// Delay before the first reconnect attempt
+    pub base_delay_ms: u64,
+    /// Longest delay once it has been doubled on each failed attempt
+    pub max_delay_ms: u64,
+}
+
+impl Default for ReconnectHint {
+    fn default() -> Self {
+        Self {
+            base_delay_ms: RECONNECT_BASE_DELAY_MS,
+            max_delay_ms: RECONNECT_MAX_DELAY_MS,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum WsMessage {
@@ -189,12 +339,19 @@ pub enum WsMessage {
     Subscribe { vault_pubkey: String },
     Unsubscribe { vault_pubkey: String },
     Ping,
+    /// Signature (base58) over the `nonce` sent in `Connected`, made by `pubkey`
+    Auth { pubkey: String, signature: String },
 
     
     Connected { 
         message: String,
         client_id: String,
+        nonce: String,
+        auth_required: bool,
+        reconnect: ReconnectHint,
     },
+    AuthAck { pubkey: String, success: bool },
+    Unauthorized { message: String },
     SubscribeAck { vault_pubkey: String, success: bool },
     UnsubscribeAck { vault_pubkey: String, success: bool },
     Pong,
@@ -240,6 +397,12 @@ pub enum WsMessage {
         timestamp: i64,
     },
 
+    VaultClosed {
+        vault_pubkey: String,
+        tx_signature: String,
+        timestamp: i64,
+    },
+
     TvlUpdate {
         total_vaults: i64,
         total_value_locked: i64,
@@ -258,24 +421,153 @@ pub enum WsMessage {
         message: String,
         code: Option<String>,
     },
+
+    /// Sent instead of the `missed` broadcasts a lagging client lost; balances it
+    /// holds may be stale and should be re-fetched over REST
+    Resync {
+        missed: u64,
+        message: String,
+    },
+}
+
+
+/// Verify that `signature` is `pubkey`'s ed25519 signature over the connection nonce
+pub fn verify_auth_signature(pubkey: &str, signature: &str, nonce: &str) -> bool {
+    let (Ok(pubkey), Ok(signature)) = (Pubkey::from_str(pubkey), Signature::from_str(signature))
+    else {
+        return false;
+    };
+
+    signature.verify(pubkey.as_ref(), nonce.as_bytes())
+}
+
+/// Whether an authenticated client may subscribe to a vault with the given owner
+pub fn can_subscribe(authenticated_pubkey: Option<&str>, vault_owner: Option<&str>) -> bool {
+    matches!((authenticated_pubkey, vault_owner), (Some(user), Some(owner)) if user == owner)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    Limited,
+    Disconnect,
+}
+
+/// Token bucket limiting how fast a single client may send messages
+#[derive(Debug)]
+pub struct WsRateLimiter {
+    tokens: f64,
+    last_refill: Instant,
+    violations: u32,
+    last_violation: Option<Instant>,
+}
+
+impl WsRateLimiter {
+    pub fn new() -> Self {
+        Self::new_at(Instant::now())
+    }
+
+    pub fn new_at(now: Instant) -> Self {
+        Self {
+            tokens: RATE_LIMIT_BURST,
+            last_refill: now,
+            violations: 0,
+            last_violation: None,
+        }
+    }
+
+    pub fn check(&mut self) -> RateLimitDecision {
+        self.check_at(Instant::now())
+    }
+
+    /// Consume a token for a message received at `now`
+    pub fn check_at(&mut self, now: Instant) -> RateLimitDecision {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * RATE_LIMIT_PER_SEC).min(RATE_LIMIT_BURST);
+        self.last_refill = now;
+
+        if let Some(last) = self.last_violation {
+            if now.saturating_duration_since(last) > RATE_LIMIT_VIOLATION_WINDOW {
+                self.violations = 0;
+            }
+        }
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return RateLimitDecision::Allowed;
+        }
+
+        self.violations += 1;
+        self.last_violation = Some(now);
+
+        if self.violations >= RATE_LIMIT_MAX_VIOLATIONS {
+            RateLimitDecision::Disconnect
+        } else {
+            RateLimitDecision::Limited
+        }
+    }
+}
+
+impl Default for WsRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 struct WsConnection {
     client_id: String,
     session: Session,
     last_heartbeat: Instant,
     receiver: broadcast::Receiver<WsMessage>,
+    state: web::Data<AppState>,
+    nonce: String,
+    authenticated_pubkey: Option<String>,
+    rate_limiter: WsRateLimiter,
 }
 
 impl WsConnection {
-    fn new(session: Session, client_id: String, receiver: broadcast::Receiver<WsMessage>) -> Self {
+    fn new(
+        session: Session,
+        client_id: String,
+        receiver: broadcast::Receiver<WsMessage>,
+        state: web::Data<AppState>,
+    ) -> Self {
         Self {
             client_id,
             session,
             last_heartbeat: Instant::now(),
             receiver,
+            state,
+            nonce: Uuid::new_v4().to_string(),
+            authenticated_pubkey: None,
+            rate_limiter: WsRateLimiter::new(),
+        }
+    }
+
+    fn auth_required(&self) -> bool {
+        self.state.config.ws_auth_required
+    }
+
+    async fn send_unauthorized(&mut self, message: String) -> Result<(), Error> {
+        tracing::warn!("Unauthorized WebSocket request from {}: {}", self.client_id, message);
+        self.send_message(&WsMessage::Unauthorized { message }).await
+    }
+
+    async fn handle_auth(&mut self, pubkey: String, signature: String) -> Result<(), Error> {
+        let success = verify_auth_signature(&pubkey, &signature, &self.nonce);
+
+        tracing::info!(
+            "Client {} authentication as {}: {}",
+            self.client_id,
+            pubkey,
+            success
+        );
+
+        if success {
+            self.authenticated_pubkey = Some(pubkey.clone());
         }
+
+        self.send_message(&WsMessage::AuthAck { pubkey, success }).await
     }
 
     async fn send_message(&mut self, msg: &WsMessage) -> Result<(), Error> {
@@ -289,7 +581,38 @@ impl WsConnection {
     }
 
     async fn handle_subscribe(&mut self, vault_pubkey: String) -> Result<(), Error> {
-        let success = WS_REGISTRY.subscribe_to_vault(&self.client_id, &vault_pubkey);
+        if self.auth_required() {
+            let vault_owner = match VaultManager::get_vault(&self.state, &vault_pubkey).await {
+                Ok(vault) => vault.map(|v| v.owner_pubkey),
+                Err(e) => {
+                    tracing::error!("Failed to load vault {} for subscription: {}", vault_pubkey, e);
+                    None
+                }
+            };
+
+            if !can_subscribe(self.authenticated_pubkey.as_deref(), vault_owner.as_deref()) {
+                return self
+                    .send_unauthorized(format!("Not authorized to subscribe to vault {}", vault_pubkey))
+                    .await;
+            }
+        }
+
+        let success = match WS_REGISTRY.subscribe_to_vault(&self.client_id, &vault_pubkey) {
+            Ok(success) => success,
+            Err(e) => {
+                tracing::warn!(
+                    "Client {} refused subscription to {}: {}",
+                    self.client_id,
+                    vault_pubkey,
+                    e
+                );
+                let error = WsMessage::Error {
+                    message: e.to_string(),
+                    code: Some(TOO_MANY_SUBSCRIPTIONS_CODE.to_string()),
+                };
+                return self.send_message(&error).await;
+            }
+        };
 
         tracing::info!(
             "Client {} subscribed to vault {}: {}",
@@ -330,8 +653,37 @@ impl WsConnection {
 
         tracing::debug!("Received WebSocket message from {}: {}", self.client_id, text_str);
 
+        match self.rate_limiter.check() {
+            RateLimitDecision::Allowed => {}
+            RateLimitDecision::Limited => {
+                let error = WsMessage::Error {
+                    message: "Too many messages, slow down".to_string(),
+                    code: Some("RATE_LIMITED".to_string()),
+                };
+                return self.send_message(&error).await;
+            }
+            RateLimitDecision::Disconnect => {
+                tracing::warn!("Client {} exceeded rate limit repeatedly, disconnecting", self.client_id);
+                let reason = actix_ws::CloseReason {
+                    code: actix_ws::CloseCode::Policy,
+                    description: Some("rate limit exceeded".to_string()),
+                };
+                let _ = self.session.clone().close(Some(reason)).await;
+                return Err(actix_web::error::ErrorTooManyRequests("rate limit exceeded"));
+            }
+        }
+
         match serde_json::from_str::<WsMessage>(text_str) {
             Ok(msg) => match msg {
+                WsMessage::Auth { pubkey, signature } => {
+                    self.handle_auth(pubkey, signature).await?;
+                }
+                WsMessage::Subscribe { .. } | WsMessage::Unsubscribe { .. }
+                    if self.auth_required() && self.authenticated_pubkey.is_none() =>
+                {
+                    self.send_unauthorized("Authenticate before subscribing".to_string())
+                        .await?;
+                }
                 WsMessage::Subscribe { vault_pubkey } => {
                     self.handle_subscribe(vault_pubkey).await?;
                 }
@@ -366,7 +718,11 @@ impl WsConnection {
     }
 }
 
-pub async fn ws_handler(req: HttpRequest, stream: web::Payload) -> Result<HttpResponse, Error> {
+pub async fn ws_handler(
+    req: HttpRequest,
+    stream: web::Payload,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, Error> {
     let (res, mut session, mut msg_stream) = actix_ws::handle(&req, stream)?;
 
     tracing::info!(
@@ -375,10 +731,25 @@ pub async fn ws_handler(req: HttpRequest, stream: web::Payload) -> Result<HttpRe
     );
 
     // Register client and get ID + receiver
-    let (client_id, receiver) = WS_REGISTRY.register_client();
+    let (client_id, receiver) = match WS_REGISTRY.register_client() {
+        Ok(registered) => registered,
+        Err(e) => {
+            tracing::warn!("Refusing WebSocket connection from {:?}: {}", req.peer_addr(), e);
+            // 1013 (try again later) tells the client to back off before reconnecting
+            let reason = actix_ws::CloseReason {
+                code: actix_ws::CloseCode::Again,
+                description: Some(TOO_MANY_CLIENTS_REASON.to_string()),
+            };
+            actix_rt::spawn(async move {
+                let _ = session.close(Some(reason)).await;
+            });
+            return Ok(res);
+        }
+    };
 
     actix_rt::spawn(async move {
-        if let Err(e) = handle_connection(&mut session, &mut msg_stream, client_id.clone(), receiver).await
+        if let Err(e) =
+            handle_connection(&mut session, &mut msg_stream, client_id.clone(), receiver, state).await
         {
             tracing::error!("WebSocket connection error for client {}: {}", client_id, e);
         }
@@ -394,17 +765,22 @@ async fn handle_connection(
     msg_stream: &mut MessageStream,
     client_id: String,
     receiver: broadcast::Receiver<WsMessage>,
+    state: web::Data<AppState>,
 ) -> Result<(), Error> {
-    let mut conn = WsConnection::new(session.clone(), client_id.clone(), receiver);
+    let mut conn = WsConnection::new(session.clone(), client_id.clone(), receiver, state);
 
-    // Send welcome message with client ID
+    // Send welcome message with client ID and the nonce to sign for authentication
     let welcome = WsMessage::Connected {
         message: "Connected to Vault Management System".to_string(),
         client_id: client_id.clone(),
+        nonce: conn.nonce.clone(),
+        auth_required: conn.auth_required(),
+        reconnect: ReconnectHint::default(),
     };
     conn.send_message(&welcome).await?;
 
     let mut heartbeat_interval = interval(HEARTBEAT_INTERVAL);
+    let resync = conn.state.config.ws_resync_lagging_clients;
 
     loop {
         tokio::select! {
@@ -447,17 +823,23 @@ async fn handle_connection(
             }
 
             // Handle broadcast messages from the registry
-            Ok(broadcast_msg) = conn.receiver.recv() => {
+            Some(broadcast_msg) = recv_broadcast(&mut conn.receiver, &client_id, resync) => {
                 if let Err(e) = conn.send_message(&broadcast_msg).await {
                     tracing::error!("Error sending broadcast message: {}", e);
                     break;
                 }
+
+                if is_shutdown_notice(&broadcast_msg) {
+                    close_session(&conn.session, "server shutting down").await;
+                    break;
+                }
             }
 
             // Heartbeat tick
             _ = heartbeat_interval.tick() => {
                 if Instant::now().duration_since(conn.last_heartbeat) > CLIENT_TIMEOUT {
                     tracing::warn!("Client {} heartbeat timeout, closing connection", client_id);
+                    close_session(&conn.session, HEARTBEAT_TIMEOUT_REASON).await;
                     break;
                 }
 
@@ -476,6 +858,63 @@ async fn handle_connection(
     Ok(())
 }
 
+/// Next broadcast for a client, or `None` once its channel is closed
+///
+/// `broadcast::Sender::send` never waits on slow receivers: once a client's
+/// channel is full, the oldest messages are overwritten. The loss
+/// surfaces here as `Lagged` and is counted in `websocket_dropped_messages_total`.
+/// With `resync` set the client is then sent a `Resync` directive; otherwise it
+/// just carries on with the messages still buffered.
+pub async fn recv_broadcast(
+    receiver: &mut broadcast::Receiver<WsMessage>,
+    client_id: &str,
+    resync: bool,
+) -> Option<WsMessage> {
+    loop {
+        match receiver.recv().await {
+            Ok(message) => return Some(message),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                add_ws_dropped_messages(missed);
+                tracing::warn!("Client {} lagged and missed {} broadcasts", client_id, missed);
+
+                if resync {
+                    return Some(WsMessage::Resync {
+                        missed,
+                        message: "Missed updates, re-fetch vault balances".to_string(),
+                    });
+                }
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Send a `1001` (going away) close frame so the client's reconnect logic fires
+/// straight away rather than waiting for the TCP connection to drop
+async fn close_session(session: &Session, description: &str) {
+    let reason = actix_ws::CloseReason {
+        code: actix_ws::CloseCode::Away,
+        description: Some(description.to_string()),
+    };
+    let _ = session.clone().close(Some(reason)).await;
+}
+
+fn is_shutdown_notice(message: &WsMessage) -> bool {
+    matches!(message, WsMessage::Error { code: Some(code), .. } if code == SHUTDOWN_CODE)
+}
+
+/// Tell every connected client the server is going away; each session closes
+/// itself after forwarding the notice
+pub async fn broadcast_shutdown() {
+    let notice = WsMessage::Error {
+        message: "Server is shutting down".to_string(),
+        code: Some(SHUTDOWN_CODE.to_string()),
+    };
+
+    tracing::info!("Broadcasting shutdown to {} WebSocket clients", WS_REGISTRY.client_count());
+    WS_REGISTRY.broadcast_to_all(notice).await;
+}
+
 pub async fn broadcast_balance_update(
     vault_pubkey: &str,
     total_balance: i64,
@@ -568,6 +1007,17 @@ pub async fn broadcast_unlock(
     WS_REGISTRY.broadcast_to_vault(vault_pubkey, notification).await;
 }
 
+pub async fn broadcast_vault_closed(vault_pubkey: &str, tx_signature: &str) {
+    let notification = WsMessage::VaultClosed {
+        vault_pubkey: vault_pubkey.to_string(),
+        tx_signature: tx_signature.to_string(),
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+
+    tracing::debug!("Broadcasting close of vault {}", vault_pubkey);
+    WS_REGISTRY.broadcast_to_vault(vault_pubkey, notification).await;
+}
+
 pub async fn broadcast_tvl_update(total_vaults: i64, total_value_locked: i64) {
     let update = WsMessage::TvlUpdate {
         total_vaults,
@@ -601,21 +1051,27 @@ pub async fn broadcast_alert(
         WS_REGISTRY.broadcast_to_all(notification).await;
     }
 }
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VaultSubscriberCount {
+    pub vault_pubkey: String,
+    pub subscribers: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct WebSocketStats {
     pub total_clients: usize,
     pub total_vault_subscriptions: usize,
+    /// Most subscribed vaults, busiest first
+    pub top_vaults: Vec<VaultSubscriberCount>,
+    /// Mean time the current clients have been connected
+    pub avg_connection_age_secs: f64,
 }
 
 pub fn get_websocket_stats() -> WebSocketStats {
-    let total_vault_subscriptions: usize = WS_REGISTRY
-        .vault_subscriptions
-        .iter()
-        .map(|v| v.len())
-        .sum();
-
-    WebSocketStats {
-        total_clients: WS_REGISTRY.client_count(),
-        total_vault_subscriptions,
-    }
+    WS_REGISTRY.stats(STATS_TOP_VAULTS)
+}
+
+/// `GET /ws/stats`
+pub async fn ws_stats() -> HttpResponse {
+    HttpResponse::Ok().json(get_websocket_stats())
 }
\ No newline at end of file
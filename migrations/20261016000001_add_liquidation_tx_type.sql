@@ -0,0 +1,3 @@
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS valid_tx_type;
+ALTER TABLE transactions ADD CONSTRAINT valid_tx_type
+  CHECK (tx_type IN ('deposit' , 'withdraw' , 'lock' , 'unlock' , 'transfer' , 'liquidation'));
@@ -0,0 +1,2 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS min_deposit BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS max_balance BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS tvl_history(
+  id BIGSERIAL PRIMARY KEY,
+  total_vaults BIGINT NOT NULL,
+  total_value_locked BIGINT NOT NULL,
+  total_locked BIGINT NOT NULL,
+  total_available BIGINT NOT NULL,
+  recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tvl_history_recorded_at ON tvl_history(recorded_at);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys(
+  idempotency_key TEXT PRIMARY KEY,
+  request_hash TEXT NOT NULL,
+  -- NULL until the first request finishes
+  status_code INTEGER,
+  response JSONB,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created ON idempotency_keys(created_at);
@@ -0,0 +1,4 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS auto_lock_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS auto_lock_buffer BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_vaults_auto_lock ON vaults(auto_lock_enabled) WHERE auto_lock_enabled;
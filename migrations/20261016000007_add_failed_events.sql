@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS failed_events(
+  id BIGSERIAL PRIMARY KEY,
+  tx_signature TEXT NOT NULL,
+  event_type VARCHAR(50) NOT NULL,
+  -- Raw Anchor event bytes (discriminator + payload) so the event can be re-parsed
+  event_data BYTEA NOT NULL,
+  error TEXT NOT NULL,
+  retry_count INTEGER NOT NULL DEFAULT 0,
+  status VARCHAR(20) NOT NULL DEFAULT 'pending',
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  CONSTRAINT valid_failed_event_status CHECK (status IN ('pending', 'resolved', 'abandoned'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_failed_events_pending ON failed_events(created_at) WHERE status = 'pending';
@@ -0,0 +1 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS decimals SMALLINT NOT NULL DEFAULT 6;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS webhooks(
+  id BIGSERIAL PRIMARY KEY,
+  url TEXT NOT NULL,
+  -- HMAC-SHA256 key for the X-Signature header
+  secret TEXT NOT NULL,
+  -- Empty means every event type
+  event_types TEXT[] NOT NULL DEFAULT '{}',
+  -- NULL means every vault
+  vault_pubkey TEXT,
+  active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhooks_active ON webhooks(vault_pubkey) WHERE active;
@@ -0,0 +1,3 @@
+-- Prefix search (`LIKE 'abc%'`) can only use a B-tree index built with pattern ops
+CREATE INDEX IF NOT EXISTS idx_vaults_pubkey_prefix ON vaults(vault_pubkey text_pattern_ops);
+CREATE INDEX IF NOT EXISTS idx_vaults_owner_prefix ON vaults(owner_pubkey text_pattern_ops);
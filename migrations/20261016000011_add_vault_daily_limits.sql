@@ -0,0 +1,5 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS daily_deposit_limit BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS daily_withdraw_limit BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_vault_type_created
+  ON transactions(vault_pubkey, tx_type, created_at);
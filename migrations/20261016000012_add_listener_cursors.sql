@@ -0,0 +1,6 @@
+-- Newest signature the event listener has handled, per program
+CREATE TABLE IF NOT EXISTS listener_cursors(
+  program_id TEXT PRIMARY KEY,
+  last_signature TEXT NOT NULL,
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+-- The confirmation checker scans for stale pending rows
+CREATE INDEX IF NOT EXISTS idx_transactions_pending ON transactions(created_at) WHERE status = 'pending';
@@ -0,0 +1 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS frozen BOOLEAN NOT NULL DEFAULT FALSE;
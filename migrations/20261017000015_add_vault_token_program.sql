@@ -0,0 +1,3 @@
+-- Program that owns the vault's token account; existing vaults use legacy SPL Token
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS token_program TEXT NOT NULL
+    DEFAULT 'TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA';
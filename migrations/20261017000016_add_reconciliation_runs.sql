@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS reconciliation_runs(
+  id BIGSERIAL PRIMARY KEY,
+  started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  total_vaults BIGINT NOT NULL,
+  mismatches BIGINT NOT NULL,
+  repaired BIGINT NOT NULL,
+  errors BIGINT NOT NULL,
+  duration_ms BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliation_runs_started_at ON reconciliation_runs(started_at DESC);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS positions(
+  vault_pubkey TEXT NOT NULL REFERENCES vaults(vault_pubkey),
+  position_id BIGINT NOT NULL,
+  amount BIGINT NOT NULL,
+  authority_program TEXT NOT NULL,
+  opened_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  unlock_after TIMESTAMP WITH TIME ZONE,
+  tx_signature TEXT NOT NULL,
+  PRIMARY KEY (vault_pubkey, position_id)
+);
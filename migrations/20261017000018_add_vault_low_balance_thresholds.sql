@@ -0,0 +1,4 @@
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS low_balance_floor BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS low_balance_percent DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_alerts_vault_type_created ON alerts(vault_pubkey, alert_type, created_at);
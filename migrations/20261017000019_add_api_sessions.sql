@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS api_sessions(
+  session_id UUID PRIMARY KEY,
+  owner_pubkey VARCHAR(44) NOT NULL,
+  -- HMAC key for request signatures; only ever returned when the session is issued
+  secret TEXT NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_sessions_expires ON api_sessions(expires_at);
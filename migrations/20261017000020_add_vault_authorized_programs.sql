@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS vault_authorized_programs(
+  vault_pubkey TEXT NOT NULL REFERENCES vaults(vault_pubkey),
+  program_id TEXT NOT NULL,
+  authorized_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  tx_signature TEXT NOT NULL,
+  PRIMARY KEY (vault_pubkey, program_id)
+);
@@ -0,0 +1,9 @@
+-- Last deposit, withdrawal, lock, unlock or transfer; mirrors the on-chain field.
+-- Existing vaults start from their last update.
+ALTER TABLE vaults ADD COLUMN IF NOT EXISTS last_activity TIMESTAMPTZ;
+UPDATE vaults SET last_activity = updated_at WHERE last_activity IS NULL;
+ALTER TABLE vaults
+    ALTER COLUMN last_activity SET DEFAULT NOW(),
+    ALTER COLUMN last_activity SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_vaults_last_activity ON vaults (last_activity);
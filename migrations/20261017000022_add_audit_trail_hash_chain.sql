@@ -0,0 +1,8 @@
+-- Hash chain making the audit trail tamper-evident: each entry written through the
+-- backend stores the previous entry's hash for its vault and its own
+-- sha256(prev_hash || entry). Earlier rows and rows from the vaults trigger stay NULL.
+ALTER TABLE audit_trail ADD COLUMN IF NOT EXISTS prev_hash TEXT;
+ALTER TABLE audit_trail ADD COLUMN IF NOT EXISTS entry_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_audit_chain ON audit_trail (vault_pubkey, id)
+    WHERE entry_hash IS NOT NULL;
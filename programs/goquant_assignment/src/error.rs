@@ -22,4 +22,32 @@ pub enum VaultError {
     BumpNotFound,
     #[msg("Vault has Open Positions - cannot withdraw locked collateral")]
     HasOpenPositions,
+    #[msg("Vault is already at the current layout version")]
+    AlreadyMigrated,
+    #[msg("Vault account data does not match a known layout")]
+    InvalidVaultData,
+    #[msg("Batch exceeds the maximum number of vaults per instruction")]
+    BatchTooLarge,
+    #[msg("Deposit is below the vault's minimum deposit")]
+    BelowMinimumDeposit,
+    #[msg("Deposit would exceed the vault's maximum balance")]
+    ExceedsMaxBalance,
+    #[msg("Invalid vault limits: min_deposit must not exceed max_balance")]
+    InvalidVaultLimits,
+    #[msg("Balance invariant violated: total must equal available + locked")]
+    BalanceInvariantViolation,
+    #[msg("Program is paused: collateral operations are disabled")]
+    ProgramPaused,
+    #[msg("Nonce does not match the vault's current nonce")]
+    InvalidNonce,
+    #[msg("Vault already authorizes the maximum number of programs")]
+    TooManyAuthorizedPrograms,
+    #[msg("Mint is not on the program's allowlist")]
+    MintNotAllowed,
+    #[msg("Allowlist exceeds the maximum number of mints")]
+    TooManyAllowedMints,
+    #[msg("Token account mint does not match the vault's token account")]
+    MintMismatch,
+    #[msg("Destination token account belongs to another wallet and was not confirmed")]
+    ForeignDestinationNotConfirmed,
 }
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, VaultAuthority},
+    states::{AuthorityAddedEvent, CollateralVault, VaultAuthority, MAX_AUTHORIZED_PROGRAMS},
 };
 
 #[derive(Accounts)]
@@ -14,7 +14,12 @@ pub struct AddAuthorizedProgram<'info> {
   )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
-    #[account(mut, seeds = [b"vault", admin.key().as_ref()], bump = vault.bump)]
+    #[account(
+    mut,
+    seeds = [b"vault", vault.creator.as_ref()],
+    bump = vault.bump,
+    constraint = vault.owner == admin.key() @ VaultError::UnAuthorized
+  )]
     pub vault: Account<'info, CollateralVault>,
 
     pub admin: Signer<'info>,
@@ -34,7 +39,17 @@ pub fn add_authorized_program_handler(
         .iter()
         .any(|p| p == &program_id)
     {
+        require!(
+            vault_authority.authorized_programs.len() < MAX_AUTHORIZED_PROGRAMS,
+            VaultError::TooManyAuthorizedPrograms
+        );
         vault_authority.authorized_programs.push(program_id);
+
+        emit!(AuthorityAddedEvent {
+            vault: ctx.accounts.vault.key(),
+            program_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
     }
     Ok(())
 }
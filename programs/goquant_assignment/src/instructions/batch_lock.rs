@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    instructions::lock_collateral::apply_lock,
+    states::{CollateralVault, ProgramConfig, VaultAuthority},
+};
+
+/// Maximum number of vaults locked in a single `batch_lock`
+pub const MAX_BATCH_SIZE: usize = 10;
+
+/// Remaining accounts are `(vault, vault_authority)` pairs, one per amount.
+#[derive(Accounts)]
+pub struct BatchLock<'info> {
+    /// Signing authority of an authorized program, checked against each `vault_authority`
+    pub authority_program: Signer<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn batch_lock_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchLock<'info>>,
+    amounts: Vec<u64>,
+    nonces: Vec<u64>,
+) -> Result<()> {
+    require!(!amounts.is_empty(), VaultError::InvalidAmount);
+    require!(amounts.len() <= MAX_BATCH_SIZE, VaultError::BatchTooLarge);
+    require!(nonces.len() == amounts.len(), VaultError::InvalidAmount);
+    require!(
+        ctx.remaining_accounts.len() == amounts.len() * 2,
+        VaultError::InvalidAmount
+    );
+
+    let authority_program = ctx.accounts.authority_program.key();
+
+    // Any failure returns early, rolling back every lock in the batch
+    for ((pair, &amount), &nonce) in ctx
+        .remaining_accounts
+        .chunks(2)
+        .zip(amounts.iter())
+        .zip(nonces.iter())
+    {
+        require!(amount > 0, VaultError::InvalidAmount);
+
+        let mut vault: Account<'info, CollateralVault> = Account::try_from(&pair[0])?;
+        let vault_authority: Account<'info, VaultAuthority> = Account::try_from(&pair[1])?;
+
+        let expected_authority = Pubkey::create_program_address(
+            &[
+                b"vault_authority",
+                vault.key().as_ref(),
+                &[vault_authority.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| VaultError::ProgramNotAuthorized)?;
+        require_keys_eq!(
+            vault_authority.key(),
+            expected_authority,
+            VaultError::ProgramNotAuthorized
+        );
+        require!(
+            vault_authority.is_program_authorized(&authority_program),
+            VaultError::ProgramNotAuthorized
+        );
+
+        vault.consume_nonce(nonce)?;
+        apply_lock(&mut vault, amount)?;
+
+        // Remaining accounts are not persisted automatically
+        vault.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    states::{CollateralVault, Position, PositionClosedEvent, ProgramConfig, VaultAuthority},
+};
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+    seeds = [b"vault_authority" , vault.key().as_ref()],
+    bump
+  )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
+
+    #[account(
+    mut,
+    close = payer,
+    has_one = vault,
+    constraint = position.authority == authority_program.key() @ VaultError::UnAuthorized,
+    seeds = [Position::SEED, vault.key().as_ref(), &position.position_id.to_le_bytes()],
+    bump = position.bump
+  )]
+    pub position: Account<'info, Position>,
+
+    /// Rent refund destination, the account that opened the position
+    #[account(mut, address = position.payer)]
+    pub payer: SystemAccount<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn close_position_handler(ctx: Context<ClosePosition>) -> Result<()> {
+    require!(
+        ctx.accounts
+            .vault_authority
+            .is_program_authorized(&ctx.accounts.authority_program.key()),
+        VaultError::ProgramNotAuthorized
+    );
+
+    // Liquidation may already have seized part of the position's collateral
+    let position = &ctx.accounts.position;
+    let vault = &mut ctx.accounts.vault;
+    vault.position_locked = vault.position_locked.saturating_sub(position.amount);
+    vault.record_activity()?;
+
+    emit!(PositionClosedEvent {
+        vault: vault.key(),
+        position_id: position.position_id,
+        amount: position.amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, DepositEvent},
+    states::{CollateralVault, DepositEvent, ProgramConfig},
 };
 
 #[derive(Accounts)]
@@ -12,9 +12,10 @@ pub struct Deposit<'info> {
     pub user: Signer<'info>,
     #[account(
     mut,
-    seeds = [b"vault" , user.key().as_ref()],
-    bump,
-    has_one = owner @ VaultError::UnAuthorized
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
+    has_one = owner @ VaultError::UnAuthorized,
+    constraint = vault.owner == user.key() @ VaultError::UnAuthorized
   )]
     pub vault: Account<'info, CollateralVault>,
     // USers USDT tokenAccount (source for funds)
@@ -33,10 +34,22 @@ pub struct Deposit<'info> {
     pub token_program: Program<'info, Token>,
     ///CHECK: This is Validated by the has_one constraint
     pub owner: UncheckedAccount<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
+    require!(
+        ctx.accounts.user_token_account.mint == ctx.accounts.vault_ata.mint,
+        VaultError::MintMismatch
+    );
+    ctx.accounts.vault.check_deposit_limits(amount)?;
 
     let cpi_accounts = Transfer {
         from: ctx.accounts.user_token_account.to_account_info(),
@@ -48,18 +61,8 @@ pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
 
     let vault = &mut ctx.accounts.vault;
-    vault.total_balance = vault
-        .total_balance
-        .checked_add(amount)
-        .ok_or(VaultError::OverFlow)?;
-    vault.available_balance = vault
-        .available_balance
-        .checked_add(amount)
-        .ok_or(VaultError::OverFlow)?;
-    vault.total_deposited = vault
-        .total_deposited
-        .checked_add(amount)
-        .ok_or(VaultError::OverFlow)?;
+    vault.credit_deposit(amount)?;
+    vault.record_activity()?;
 
     emit!(DepositEvent {
         user: ctx.accounts.user.key(),
@@ -68,6 +71,7 @@ pub fn deposit_handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         new_available_balance: vault.available_balance,
         new_total_balance: vault.total_balance,
         timestamp: Clock::get()?.unix_timestamp,
+        funder: ctx.accounts.user.key(),
     });
 
     Ok(())
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::{
+    error::VaultError,
+    states::{CollateralVault, DepositEvent, ProgramConfig},
+};
+
+#[derive(Accounts)]
+pub struct DepositFor<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    // Any vault can be funded; only its owner can withdraw
+    #[account(
+    mut,
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
+  )]
+    pub vault: Account<'info, CollateralVault>,
+    // Funder's tokenAccount (source for funds)
+    #[account(
+    mut,
+    constraint = funder_token_account.owner == funder.key() @VaultError::InvalidTokenAccount
+  )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    //Vault tokenAccount (destination for funds)
+    #[account(
+    mut,
+    constraint = vault_ata.key() == vault.token_account @VaultError::InvalidTokenAccount
+  )]
+    pub vault_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn deposit_for_handler(ctx: Context<DepositFor>, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::InvalidAmount);
+    require!(
+        ctx.accounts.funder_token_account.mint == ctx.accounts.vault_ata.mint,
+        VaultError::MintMismatch
+    );
+    ctx.accounts.vault.check_deposit_limits(amount)?;
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.vault_ata.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    transfer(CpiContext::new(cpi_program, cpi_accounts), amount)?;
+
+    let vault = &mut ctx.accounts.vault;
+    vault.credit_deposit(amount)?;
+    vault.record_activity()?;
+
+    emit!(DepositEvent {
+        user: vault.owner,
+        vault: vault.key(),
+        amount,
+        new_available_balance: vault.available_balance,
+        new_total_balance: vault.total_balance,
+        timestamp: Clock::get()?.unix_timestamp,
+        funder: ctx.accounts.funder.key(),
+    });
+
+    Ok(())
+}
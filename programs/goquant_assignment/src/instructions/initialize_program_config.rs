@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::VaultError, program::GoquantAssignment, states::ProgramConfig};
+
+#[derive(Accounts)]
+pub struct InitializeProgramConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+    init,
+    payer = authority,
+    space = 8 + ProgramConfig::LEN,
+    seeds = [ProgramConfig::SEED],
+    bump
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, GoquantAssignment>,
+
+    /// Only the upgrade authority may claim the config
+    #[account(
+    constraint = program_data.upgrade_authority_address == Some(authority.key()) @ VaultError::UnAuthorized
+  )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_program_config_handler(ctx: Context<InitializeProgramConfig>) -> Result<()> {
+    let program_config = &mut ctx.accounts.program_config;
+    program_config.authority = ctx.accounts.authority.key();
+    program_config.global_paused = false;
+    program_config.bump = ctx.bumps.program_config;
+    program_config.allowed_mints = Vec::new();
+
+    Ok(())
+}
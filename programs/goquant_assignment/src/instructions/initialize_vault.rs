@@ -4,7 +4,10 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::states::{CollateralVault, VaultAuthority, VaultInitializeEvent};
+use crate::{
+    error::VaultError,
+    states::{CollateralVault, ProgramConfig, VaultAuthority, VaultInitializeEvent},
+};
 
 #[derive(Accounts)]
 
@@ -19,6 +22,9 @@ pub struct InitializeVault<'info> {
     bump
   )]
     pub vault: Account<'info, CollateralVault>,
+    #[account(
+    constraint = program_config.is_mint_allowed(&mint.key()) @ VaultError::MintNotAllowed
+  )]
     pub mint: Account<'info, Mint>,
     //vault_ata
     #[account(
@@ -40,11 +46,23 @@ pub struct InitializeVault<'info> {
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
+    #[account(seeds = [ProgramConfig::SEED], bump = program_config.bump)]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
-pub fn initialize_vault_handler(ctx: Context<InitializeVault>) -> Result<()> {
+pub fn initialize_vault_handler(
+    ctx: Context<InitializeVault>,
+    min_deposit: u64,
+    max_balance: u64,
+) -> Result<()> {
+    require!(
+        max_balance == 0 || min_deposit <= max_balance,
+        VaultError::InvalidVaultLimits
+    );
+
     let vault = &mut ctx.accounts.vault;
     let clock = Clock::get()?;
+    vault.version = CollateralVault::CURRENT_VERSION;
     vault.owner = ctx.accounts.user.key();
     vault.token_account = ctx.accounts.vault_ata.key();
     vault.total_balance = 0;
@@ -54,6 +72,12 @@ pub fn initialize_vault_handler(ctx: Context<InitializeVault>) -> Result<()> {
     vault.total_withdrawn = 0;
     vault.created_at = clock.unix_timestamp;
     vault.bump = ctx.bumps.vault;
+    vault.creator = ctx.accounts.user.key();
+    vault.min_deposit = min_deposit;
+    vault.max_balance = max_balance;
+    vault.nonce = 0;
+    vault.position_locked = 0;
+    vault.last_activity = clock.unix_timestamp;
 
     {
         let va = &mut ctx.accounts.vault_authority;
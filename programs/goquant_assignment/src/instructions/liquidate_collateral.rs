@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
+
+use crate::{
+    error::VaultError,
+    states::{CollateralVault, LiquidationEvent, ProgramConfig, VaultAuthority},
+};
+
+#[derive(Accounts)]
+pub struct LiquidateCollateral<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+    mut,
+    constraint = vault_ata.key() == vault.token_account @ VaultError::InvalidTokenAccount
+  )]
+    pub vault_ata: Account<'info, TokenAccount>,
+
+    #[account(
+    seeds = [b"vault_authority" , vault.key().as_ref()],
+    bump
+  )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    //destination for the seized collateral
+    #[account(
+    mut,
+    constraint = liquidator_token_account.mint == vault_ata.mint @ VaultError::InvalidTokenAccount
+  )]
+    pub liquidator_token_account: Account<'info, TokenAccount>,
+
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn liquidate_collateral_handler(
+    ctx: Context<LiquidateCollateral>,
+    amount: u64,
+    nonce: u64,
+) -> Result<()> {
+    require!(amount > 0, VaultError::InvalidAmount);
+    require!(
+        ctx.accounts
+            .vault_authority
+            .is_program_authorized(&ctx.accounts.authority_program.key()),
+        VaultError::ProgramNotAuthorized
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    vault.consume_nonce(nonce)?;
+    require!(
+        vault.locked_balance >= amount,
+        VaultError::InsufficientLockedBalance
+    );
+
+    let seeds = vault.signer_seeds();
+    let signer: &[&[&[u8]]] = &[&seeds];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault_ata.to_account_info(),
+        to: ctx.accounts.liquidator_token_account.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+
+    transfer(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+        amount,
+    )?;
+
+    vault.locked_balance = vault
+        .locked_balance
+        .checked_sub(amount)
+        .ok_or(VaultError::UnderFlow)?;
+    // Seized collateral no longer backs any position
+    vault.position_locked = vault.position_locked.min(vault.locked_balance);
+    vault.total_balance = vault
+        .total_balance
+        .checked_sub(amount)
+        .ok_or(VaultError::UnderFlow)?;
+    vault.check_balance_invariant()?;
+    vault.record_activity()?;
+
+    emit!(LiquidationEvent {
+        vault: vault.key(),
+        liquidator: ctx.accounts.liquidator_token_account.owner,
+        amount,
+        remaining_locked: vault.locked_balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
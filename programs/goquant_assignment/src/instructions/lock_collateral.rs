@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, LockEvent, VaultAuthority},
+    states::{CollateralVault, LockEvent, ProgramConfig, VaultAuthority},
 };
 
 #[derive(Accounts)]
@@ -17,11 +17,22 @@ pub struct LockCollateral<'info> {
   )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
-    ///CHECK: will be check later
-    pub authority_program: UncheckedAccount<'info>,
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
-pub fn lock_collateral_handler(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
+pub fn lock_collateral_handler(
+    ctx: Context<LockCollateral>,
+    amount: u64,
+    nonce: u64,
+) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
 
     let authorized_accounts = &ctx.accounts.vault_authority;
@@ -32,7 +43,12 @@ pub fn lock_collateral_handler(ctx: Context<LockCollateral>, amount: u64) -> Res
         VaultError::ProgramNotAuthorized
     );
 
-    let vault = &mut ctx.accounts.vault;
+    ctx.accounts.vault.consume_nonce(nonce)?;
+    apply_lock(&mut ctx.accounts.vault, amount)
+}
+
+/// Move `amount` from available to locked and emit a `LockEvent`
+pub(crate) fn apply_lock(vault: &mut Account<CollateralVault>, amount: u64) -> Result<()> {
     require!(
         vault.available_balance >= amount,
         VaultError::InsufficientBalance
@@ -45,6 +61,8 @@ pub fn lock_collateral_handler(ctx: Context<LockCollateral>, amount: u64) -> Res
         .available_balance
         .checked_sub(amount)
         .ok_or(VaultError::UnderFlow)?;
+    vault.check_balance_invariant()?;
+    vault.record_activity()?;
 
     emit!(LockEvent {
         vault: vault.key(),
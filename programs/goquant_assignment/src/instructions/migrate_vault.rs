@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::{error::VaultError, states::CollateralVault};
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    ///CHECK: legacy layouts cannot be deserialized as `CollateralVault`,
+    /// so the discriminator and owner are validated in the handler
+    #[account(mut, owner = crate::ID)]
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// Version of a versioned pre-current layout (v1 through v4)
+///
+/// Matches the layout at its original size, or grown to `CollateralVault::LEN` by
+/// `realloc_vault`. The unversioned v0 layout has no version byte and never matches.
+pub(crate) fn legacy_version(data: &[u8]) -> Option<u8> {
+    let version = *data.first()?;
+    let original_len = match version {
+        1 => CollateralVault::V1_LEN,
+        2 => CollateralVault::V2_LEN,
+        3 => CollateralVault::V3_LEN,
+        4 => CollateralVault::V4_LEN,
+        _ => return None,
+    };
+    (data.len() == original_len || data.len() == CollateralVault::LEN).then_some(version)
+}
+
+/// Grow the vault account to `new_len`, the owner topping up rent first
+pub(crate) fn grow_vault_account<'info>(
+    vault_info: &AccountInfo<'info>,
+    owner: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    let shortfall = required_lamports.saturating_sub(vault_info.lamports());
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: owner.to_account_info(),
+                    to: vault_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    vault_info.resize(new_len)?;
+    Ok(())
+}
+
+/// Decode a pre-current layout: v4 (with `position_locked`), v3 (with `nonce`),
+/// v2 (with limits), v1 (versioned, with `creator`) or the unversioned v0 layout
+/// (optionally followed by `creator`)
+fn parse_legacy_vault(data: &[u8]) -> Result<CollateralVault> {
+    let version = legacy_version(data);
+    let is_v4 = version == Some(4);
+    let is_v3 = version == Some(3);
+    let is_v2 = version == Some(2);
+    let is_v1 = version == Some(1);
+
+    let (fields, creator_offset) = if is_v4 || is_v3 || is_v2 || is_v1 {
+        (&data[1..], Some(CollateralVault::LEGACY_LEN))
+    } else if data.len() >= CollateralVault::LEGACY_LEN + 32 {
+        (data, Some(CollateralVault::LEGACY_LEN))
+    } else {
+        (data, None)
+    };
+
+    require!(
+        fields.len() >= CollateralVault::LEGACY_LEN,
+        VaultError::InvalidVaultData
+    );
+
+    let owner = read_pubkey(fields, 0);
+    // Ownership could not change before `creator` existed
+    let creator = creator_offset.map_or(owner, |offset| read_pubkey(fields, offset));
+    // v2 limits follow `creator`, the v3 nonce follows them and v4's
+    // `position_locked` follows the nonce
+    let limits_offset = CollateralVault::LEGACY_LEN + 32;
+    let (min_deposit, max_balance) = if is_v4 || is_v3 || is_v2 {
+        (
+            read_u64(fields, limits_offset),
+            read_u64(fields, limits_offset + 8),
+        )
+    } else {
+        (0, 0)
+    };
+    let nonce = if is_v4 || is_v3 {
+        read_u64(fields, limits_offset + 16)
+    } else {
+        0
+    };
+    let position_locked = if is_v4 {
+        read_u64(fields, limits_offset + 24)
+    } else {
+        0
+    };
+    let created_at = read_u64(fields, 104) as i64;
+
+    Ok(CollateralVault {
+        version: CollateralVault::CURRENT_VERSION,
+        owner,
+        token_account: read_pubkey(fields, 32),
+        total_balance: read_u64(fields, 64),
+        locked_balance: read_u64(fields, 72),
+        available_balance: read_u64(fields, 80),
+        total_deposited: read_u64(fields, 88),
+        total_withdrawn: read_u64(fields, 96),
+        created_at,
+        bump: fields[112],
+        creator,
+        min_deposit,
+        max_balance,
+        nonce,
+        // No positions could be opened before v4
+        position_locked,
+        // Activity wasn't recorded before v5; the migration itself stamps it
+        last_activity: created_at,
+    })
+}
+
+pub fn migrate_vault_handler(ctx: Context<MigrateVault>) -> Result<()> {
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let new_len = 8 + CollateralVault::LEN;
+
+    let mut migrated = {
+        let data = vault_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *CollateralVault::DISCRIMINATOR,
+            VaultError::InvalidVaultData
+        );
+        require!(
+            !(data.len() == new_len && data[8] == CollateralVault::CURRENT_VERSION),
+            VaultError::AlreadyMigrated
+        );
+
+        parse_legacy_vault(&data[8..])?
+    };
+
+    require!(
+        migrated.owner == ctx.accounts.owner.key(),
+        VaultError::UnAuthorized
+    );
+    migrated.record_activity()?;
+
+    grow_vault_account(
+        &vault_info,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        new_len,
+    )?;
+
+    let mut data = vault_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    migrated.try_serialize(&mut writer)?;
+
+    Ok(())
+}
@@ -1,15 +1,39 @@
 pub mod add_authorized_program;
+pub mod batch_lock;
+pub mod close_position;
 pub mod deposit;
+pub mod deposit_for;
+pub mod initialize_program_config;
 pub mod initialize_vault;
+pub mod liquidate_collateral;
 pub mod lock_collateral;
+pub mod migrate_vault;
+pub mod open_position;
+pub mod realloc_vault;
+pub mod set_allowed_mints;
+pub mod set_global_pause;
+pub mod set_vault_limits;
 pub mod transfer_collateral;
+pub mod transfer_ownership;
 pub mod unlock_collateral;
 pub mod withdraw;
 
 pub use add_authorized_program::*;
+pub use batch_lock::*;
+pub use close_position::*;
 pub use deposit::*;
+pub use deposit_for::*;
+pub use initialize_program_config::*;
 pub use initialize_vault::*;
+pub use liquidate_collateral::*;
 pub use lock_collateral::*;
+pub use migrate_vault::*;
+pub use open_position::*;
+pub use realloc_vault::*;
+pub use set_allowed_mints::*;
+pub use set_global_pause::*;
+pub use set_vault_limits::*;
 pub use transfer_collateral::*;
+pub use transfer_ownership::*;
 pub use unlock_collateral::*;
 pub use withdraw::*;
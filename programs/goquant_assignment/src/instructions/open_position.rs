@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    instructions::lock_collateral::apply_lock,
+    states::{CollateralVault, Position, PositionOpenedEvent, ProgramConfig, VaultAuthority},
+};
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, CollateralVault>,
+
+    #[account(
+    seeds = [b"vault_authority" , vault.key().as_ref()],
+    bump
+  )]
+    pub vault_authority: Account<'info, VaultAuthority>,
+
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
+
+    #[account(
+    init,
+    payer = payer,
+    space = 8 + Position::LEN,
+    seeds = [Position::SEED, vault.key().as_ref(), &position_id.to_le_bytes()],
+    bump
+  )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn open_position_handler(
+    ctx: Context<OpenPosition>,
+    position_id: u64,
+    amount: u64,
+    nonce: u64,
+) -> Result<()> {
+    require!(amount > 0, VaultError::InvalidAmount);
+    require!(
+        ctx.accounts
+            .vault_authority
+            .is_program_authorized(&ctx.accounts.authority_program.key()),
+        VaultError::ProgramNotAuthorized
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    vault.consume_nonce(nonce)?;
+    apply_lock(vault, amount)?;
+    vault.position_locked = vault
+        .position_locked
+        .checked_add(amount)
+        .ok_or(VaultError::OverFlow)?;
+
+    let position = &mut ctx.accounts.position;
+    position.vault = vault.key();
+    position.authority = ctx.accounts.authority_program.key();
+    position.position_id = position_id;
+    position.amount = amount;
+    position.payer = ctx.accounts.payer.key();
+    position.bump = ctx.bumps.position;
+
+    emit!(PositionOpenedEvent {
+        vault: position.vault,
+        position_id,
+        authority: position.authority,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
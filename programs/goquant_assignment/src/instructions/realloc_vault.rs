@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::{
+    error::VaultError,
+    instructions::migrate_vault::{grow_vault_account, legacy_version, read_pubkey},
+    states::CollateralVault,
+};
+
+#[derive(Accounts)]
+pub struct ReallocVault<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    ///CHECK: legacy layouts cannot be deserialized as `CollateralVault`,
+    /// so the discriminator and owner are validated in the handler
+    #[account(mut, owner = crate::ID)]
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a versioned legacy vault to the current size, leaving its data as it was
+pub fn realloc_vault_handler(ctx: Context<ReallocVault>) -> Result<()> {
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let new_len = 8 + CollateralVault::LEN;
+
+    {
+        let data = vault_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *CollateralVault::DISCRIMINATOR,
+            VaultError::InvalidVaultData
+        );
+        // Only ever grows: a vault at (or past) the current size has nothing to gain
+        require!(data.len() < new_len, VaultError::AlreadyMigrated);
+
+        let fields = &data[8..];
+        require!(
+            legacy_version(fields).is_some(),
+            VaultError::InvalidVaultData
+        );
+        // Versioned layouts keep `owner` right after the version byte
+        require!(
+            read_pubkey(fields, 1) == ctx.accounts.owner.key(),
+            VaultError::UnAuthorized
+        );
+    }
+
+    grow_vault_account(
+        &vault_info,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+        new_len,
+    )
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    states::{AllowedMintsUpdatedEvent, ProgramConfig, MAX_ALLOWED_MINTS},
+};
+
+#[derive(Accounts)]
+pub struct SetAllowedMints<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    has_one = authority @ VaultError::UnAuthorized
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn set_allowed_mints_handler(ctx: Context<SetAllowedMints>, mints: Vec<Pubkey>) -> Result<()> {
+    require!(mints.len() <= MAX_ALLOWED_MINTS, VaultError::TooManyAllowedMints);
+
+    let mut allowed_mints = mints;
+    allowed_mints.sort();
+    allowed_mints.dedup();
+    ctx.accounts.program_config.allowed_mints = allowed_mints.clone();
+
+    emit!(AllowedMintsUpdatedEvent {
+        authority: ctx.accounts.authority.key(),
+        allowed_mints,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
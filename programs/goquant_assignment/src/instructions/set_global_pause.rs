@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    states::{GlobalPauseEvent, ProgramConfig},
+};
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    has_one = authority @ VaultError::UnAuthorized
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+pub fn set_global_pause_handler(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+    ctx.accounts.program_config.global_paused = paused;
+
+    emit!(GlobalPauseEvent {
+        authority: ctx.accounts.authority.key(),
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
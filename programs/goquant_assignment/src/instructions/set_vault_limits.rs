@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::VaultError, states::CollateralVault};
+
+#[derive(Accounts)]
+pub struct SetVaultLimits<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
+    has_one = owner @ VaultError::UnAuthorized
+  )]
+    pub vault: Account<'info, CollateralVault>,
+}
+
+pub fn set_vault_limits_handler(
+    ctx: Context<SetVaultLimits>,
+    min_deposit: u64,
+    max_balance: u64,
+) -> Result<()> {
+    require!(
+        max_balance == 0 || min_deposit <= max_balance,
+        VaultError::InvalidVaultLimits
+    );
+
+    let vault = &mut ctx.accounts.vault;
+    vault.min_deposit = min_deposit;
+    vault.max_balance = max_balance;
+    vault.record_activity()?;
+
+    Ok(())
+}
@@ -3,18 +3,28 @@ use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, TransferEvent, VaultAuthority},
+    states::{CollateralVault, ProgramConfig, TransferEvent, VaultAuthority},
 };
 
 #[derive(Accounts)]
 pub struct TransferCollateral<'info> {
-    #[account(mut)]
+    /// Token authority of `from_vault_ata`; signs the transfer with its PDA seeds
+    #[account(
+    mut,
+    seeds = [b"vault" , from_vault.creator.as_ref()],
+    bump = from_vault.bump,
+  )]
     pub from_vault: Account<'info, CollateralVault>,
-    #[account(mut)]
+    #[account(
+    mut,
+    seeds = [b"vault" , to_vault.creator.as_ref()],
+    bump = to_vault.bump,
+  )]
     pub to_vault: Account<'info, CollateralVault>,
     #[account(
     mut,
-    constraint = from_vault_ata.key() == from_vault.token_account @VaultError::InvalidTokenAccount
+    constraint = from_vault_ata.key() == from_vault.token_account @VaultError::InvalidTokenAccount,
+    constraint = from_vault_ata.owner == from_vault.key() @VaultError::InvalidTokenAccount
   )]
     pub from_vault_ata: Account<'info, TokenAccount>,
     #[account(
@@ -22,19 +32,30 @@ pub struct TransferCollateral<'info> {
     constraint = to_vault_ata.key() == to_vault.token_account @VaultError::InvalidTokenAccount
   )]
     pub to_vault_ata: Account<'info, TokenAccount>,
+    /// Registry of programs allowed to move `from_vault` collateral; not a token authority
     #[account(
-    mut,
     seeds = [b"vault_authority" , from_vault.key().as_ref()],
     bump
   )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
-    ///CHECK: will be check later
-    pub authority_program: UncheckedAccount<'info>,
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
-pub fn transfer_collateral_handler(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
+pub fn transfer_collateral_handler(
+    ctx: Context<TransferCollateral>,
+    amount: u64,
+    nonce: u64,
+) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
     let authority_account = &ctx.accounts.vault_authority;
     require!(
@@ -44,6 +65,7 @@ pub fn transfer_collateral_handler(ctx: Context<TransferCollateral>, amount: u64
 
     let from_vault = &mut ctx.accounts.from_vault;
     let to_vault = &mut ctx.accounts.to_vault;
+    from_vault.consume_nonce(nonce)?;
 
     require!(
         from_vault.available_balance >= amount,
@@ -66,9 +88,13 @@ pub fn transfer_collateral_handler(ctx: Context<TransferCollateral>, amount: u64
         .available_balance
         .checked_add(amount)
         .ok_or(VaultError::OverFlow)?;
+    from_vault.check_balance_invariant()?;
+    to_vault.check_balance_invariant()?;
+    from_vault.record_activity()?;
+    to_vault.record_activity()?;
 
-    let seeds = &[b"vault", from_vault.owner.as_ref(), &[from_vault.bump]];
-    let signer = &[&seeds[..]];
+    let seeds = from_vault.signer_seeds();
+    let signer: &[&[&[u8]]] = &[&seeds];
 
     let cpi_accounts = Transfer {
         from: ctx.accounts.from_vault_ata.to_account_info(),
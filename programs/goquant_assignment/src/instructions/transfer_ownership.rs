@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::VaultError,
+    states::{CollateralVault, OwnershipTransferredEvent},
+};
+
+#[derive(Accounts)]
+pub struct TransferOwnership<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
+    has_one = owner @ VaultError::UnAuthorized
+  )]
+    pub vault: Account<'info, CollateralVault>,
+}
+
+pub fn transfer_ownership_handler(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let old_owner = vault.owner;
+
+    vault.owner = new_owner;
+    vault.record_activity()?;
+
+    emit!(OwnershipTransferredEvent {
+        vault: vault.key(),
+        old_owner,
+        new_owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, UnLockEvent, VaultAuthority},
+    states::{CollateralVault, ProgramConfig, UnLockEvent, VaultAuthority},
 };
 
 #[derive(Accounts)]
@@ -17,11 +17,22 @@ pub struct UnLockCollateral<'info> {
   )]
     pub vault_authority: Account<'info, VaultAuthority>,
 
-    ///CHECK: will be check later
-    pub authority_program: UncheckedAccount<'info>,
+    /// Signing authority of an authorized program, checked against `vault_authority`
+    pub authority_program: Signer<'info>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
-pub fn unlock_collateral_handler(ctx: Context<UnLockCollateral>, amount: u64) -> Result<()> {
+pub fn unlock_collateral_handler(
+    ctx: Context<UnLockCollateral>,
+    amount: u64,
+    nonce: u64,
+) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
     let authority_account = &ctx.accounts.vault_authority;
 
@@ -31,10 +42,15 @@ pub fn unlock_collateral_handler(ctx: Context<UnLockCollateral>, amount: u64) ->
     );
 
     let vault = &mut ctx.accounts.vault;
+    vault.consume_nonce(nonce)?;
     require!(
         vault.locked_balance >= amount,
         VaultError::InsufficientBalance
     );
+    require!(
+        amount <= vault.unlockable_balance(),
+        VaultError::HasOpenPositions
+    );
 
     vault.locked_balance = vault
         .locked_balance
@@ -44,6 +60,8 @@ pub fn unlock_collateral_handler(ctx: Context<UnLockCollateral>, amount: u64) ->
         .available_balance
         .checked_add(amount)
         .ok_or(VaultError::OverFlow)?;
+    vault.check_balance_invariant()?;
+    vault.record_activity()?;
 
     emit!(UnLockEvent {
         vault: vault.key(),
@@ -3,7 +3,7 @@ use anchor_spl::token::{transfer, Token, TokenAccount, Transfer};
 
 use crate::{
     error::VaultError,
-    states::{CollateralVault, WithdrawEvent},
+    states::{CollateralVault, ProgramConfig, WithdrawEvent},
 };
 
 #[derive(Accounts)]
@@ -13,8 +13,8 @@ pub struct Withdraw<'info> {
 
     #[account(
     mut,
-    seeds = [b"vault" , user.key().as_ref()],
-    bump,
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
   )]
     pub vault: Account<'info, CollateralVault>,
     //source
@@ -30,31 +30,123 @@ pub struct Withdraw<'info> {
   )]
     pub user_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
+}
+
+/// Accounts for `withdraw_to`: as `Withdraw`, but the destination may belong to anyone
+#[derive(Accounts)]
+pub struct WithdrawTo<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"vault" , vault.creator.as_ref()],
+    bump = vault.bump,
+  )]
+    pub vault: Account<'info, CollateralVault>,
+    //source
+    #[account(
+    mut,
+    constraint = vault_ata.key() == vault.token_account @ VaultError::InvalidAmount
+  )]
+    pub vault_ata: Account<'info, TokenAccount>,
+    // Destination; its owner is only checked against `confirm_foreign_destination`
+    #[account(mut)]
+    pub destination_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// Global circuit breaker, see `set_global_pause`
+    #[account(
+    seeds = [ProgramConfig::SEED],
+    bump = program_config.bump,
+    constraint = !program_config.global_paused @ VaultError::ProgramPaused
+  )]
+    pub program_config: Account<'info, ProgramConfig>,
 }
 
 pub fn withdraw_handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
     require!(amount > 0, VaultError::InvalidAmount);
-    let vault = &mut ctx.accounts.vault;
-
     require!(
-        vault.owner == ctx.accounts.user.key(),
-        VaultError::UnAuthorized
+        ctx.accounts.vault.available_balance >= amount,
+        VaultError::InsufficientBalance
     );
+
+    withdraw_from_vault(ctx.accounts, amount)
+}
+
+pub fn withdraw_all_handler(ctx: Context<Withdraw>) -> Result<()> {
+    let amount = ctx.accounts.vault.available_balance;
+    require!(amount > 0, VaultError::InsufficientBalance);
+
+    withdraw_from_vault(ctx.accounts, amount)
+}
+
+pub fn withdraw_to_handler(
+    ctx: Context<WithdrawTo>,
+    amount: u64,
+    confirm_foreign_destination: bool,
+) -> Result<()> {
+    require!(amount > 0, VaultError::InvalidAmount);
     require!(
-        vault.available_balance >= amount,
+        ctx.accounts.vault.available_balance >= amount,
         VaultError::InsufficientBalance
     );
+    require!(
+        confirm_foreign_destination
+            || ctx.accounts.destination_token_account.owner == ctx.accounts.user.key(),
+        VaultError::ForeignDestinationNotConfirmed
+    );
+
+    let accounts = ctx.accounts;
+    transfer_out(
+        &accounts.user,
+        &mut accounts.vault,
+        &accounts.vault_ata,
+        &accounts.destination_token_account,
+        &accounts.token_program,
+        amount,
+    )
+}
+
+fn withdraw_from_vault(accounts: &mut Withdraw, amount: u64) -> Result<()> {
+    transfer_out(
+        &accounts.user,
+        &mut accounts.vault,
+        &accounts.vault_ata,
+        &accounts.user_token_account,
+        &accounts.token_program,
+        amount,
+    )
+}
+
+/// PDA-signed transfer of `amount` from the vault to `destination`, updating balances
+fn transfer_out<'info>(
+    user: &Signer<'info>,
+    vault: &mut Account<'info, CollateralVault>,
+    vault_ata: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    require!(vault.owner == user.key(), VaultError::UnAuthorized);
+    require!(destination.mint == vault_ata.mint, VaultError::MintMismatch);
 
-    let seeds = &[b"vault", vault.owner.as_ref(), &[vault.bump]];
-    let signer: &[&[&[u8]]] = &[&seeds[..]];
+    let seeds = vault.signer_seeds();
+    let signer: &[&[&[u8]]] = &[&seeds];
 
     let cpi_accounts = Transfer {
-        from: ctx.accounts.vault_ata.to_account_info(),
-        to: ctx.accounts.user_token_account.to_account_info(),
+        from: vault_ata.to_account_info(),
+        to: destination.to_account_info(),
         authority: vault.to_account_info(),
     };
 
-    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_program = token_program.to_account_info();
 
     transfer(
         CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
@@ -73,14 +165,17 @@ pub fn withdraw_handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         .total_withdrawn
         .checked_add(amount)
         .ok_or(VaultError::OverFlow)?;
+    vault.check_balance_invariant()?;
+    vault.record_activity()?;
 
     emit!(WithdrawEvent {
-        user: ctx.accounts.user.key(),
+        user: user.key(),
         vault: vault.key(),
         amount,
         new_available_balance: vault.available_balance,
         new_total_balance: vault.total_balance,
         timestamp: Clock::get()?.unix_timestamp,
+        destination: destination.key(),
     });
 
     Ok(())
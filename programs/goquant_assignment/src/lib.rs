@@ -8,16 +8,30 @@
 //!
 //! - **Vault Initialization**: Create new collateral vaults for users
 //! - **Deposit**: Add tokens to vault as collateral
+//! - **Sponsored Deposit**: Fund another user's vault from your own token account
 //! - **Withdraw**: Remove tokens from vault (subject to locking constraints)
+//! - **Withdraw All**: Remove the entire available balance in one instruction
 //! - **Lock/Unlock**: Temporarily lock collateral for DeFi protocols
+//! - **Batch Lock**: Lock collateral across up to 10 vaults in one instruction
+//! - **Positions**: Lock collateral against a position that must be closed before unlocking it
 //! - **Transfer**: Move collateral between vaults
+//! - **Ownership Transfer**: Hand a vault over to a new wallet
+//! - **Liquidation**: Authorized programs seize locked collateral
+//! - **Global Pause**: Program-wide circuit breaker for collateral operations
+//! - **Mint Allowlist**: Restrict the mints vaults may be created for
 //! - **Events**: Emit structured events for off-chain processing
+//! - **Activity Tracking**: Each vault records when an instruction last changed it
 //!
 //! ## Security Considerations
 //!
 //! - All operations validate ownership and balances
 //! - Locked collateral cannot be withdrawn until unlocked
+//! - Withdrawals to a token account owned by another wallet must be confirmed explicitly
+//! - Collateral backing an open position cannot be unlocked until it is closed
 //! - Authority controls for program upgrades
+//! - The program authority can pause every deposit, withdrawal, lock, unlock,
+//!   liquidation and transfer at once
+//! - Once the program authority sets an allowlist, vaults can only be created for listed mints
 //! - Comprehensive event logging for transparency
 //!
 //! ## Program ID
@@ -50,21 +64,107 @@ pub mod goquant_assignment {
     ///
     /// # Security
     /// Only the program authority can call this function
+    ///
+    /// A vault can authorize at most `MAX_AUTHORIZED_PROGRAMS` (8) programs; adding
+    /// another fails with `TooManyAuthorizedPrograms`. Re-adding one already
+    /// authorized is a no-op.
+    ///
+    /// # Events
+    /// Emits `AuthorityAddedEvent` when the program was not already authorized
     pub fn authority_to_add(ctx: Context<AddAuthorizedProgram>, program_id: Pubkey) -> Result<()> {
         add_authorized_program_handler(ctx, program_id)
     }
 
+    /// Create the program-wide `ProgramConfig`
+    ///
+    /// The signer becomes the config authority and the program starts unpaused.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with the upgrade authority signer and program data
+    ///
+    /// # Errors
+    /// Returns `UnAuthorized` unless the signer is the program's upgrade authority
+    pub fn initialize_program_config(ctx: Context<InitializeProgramConfig>) -> Result<()> {
+        initialize_program_config_handler(ctx)
+    }
+
+    /// Pause or resume every collateral operation program-wide
+    ///
+    /// While paused, deposit, withdraw, withdraw_all, withdraw_to, lock, batch_lock,
+    /// unlock, open_position, close_position, liquidate and transfer all fail with
+    /// `ProgramPaused`.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with the config authority signer
+    /// * `paused` - Whether collateral operations are disabled
+    ///
+    /// # Events
+    /// Emits `GlobalPauseEvent` on success
+    ///
+    /// # Errors
+    /// Returns `UnAuthorized` if the signer is not the config authority
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+        set_global_pause_handler(ctx, paused)
+    }
+
+    /// Replace the mints vaults may be created for
+    ///
+    /// An empty list allows any mint. Duplicates are dropped; existing vaults
+    /// are unaffected.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with the config authority signer
+    /// * `mints` - The new allowlist, at most `MAX_ALLOWED_MINTS` (16) mints
+    ///
+    /// # Events
+    /// Emits `AllowedMintsUpdatedEvent` on success
+    ///
+    /// # Errors
+    /// Returns `UnAuthorized` if the signer is not the config authority, or
+    /// `TooManyAllowedMints` above `MAX_ALLOWED_MINTS`
+    pub fn set_allowed_mints(ctx: Context<SetAllowedMints>, mints: Vec<Pubkey>) -> Result<()> {
+        set_allowed_mints_handler(ctx, mints)
+    }
+
     /// Initialize a new collateral vault for a user
     ///
     /// Creates a new vault account and associates it with the user's token account.
     ///
     /// # Arguments
     /// * `ctx` - Program context with vault, owner, and token accounts
+    /// * `min_deposit` - Smallest accepted deposit (0 for no floor)
+    /// * `max_balance` - Cap on the vault's total balance (0 for unlimited)
     ///
     /// # Events
     /// Emits `VaultInitializedEvent` on success
-    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
-        instructions::initialize_vault_handler(ctx)
+    ///
+    /// # Errors
+    /// Returns `MintNotAllowed` if `ProgramConfig.allowed_mints` is non-empty
+    /// and does not contain the mint
+    pub fn initialize_vault(
+        ctx: Context<InitializeVault>,
+        min_deposit: u64,
+        max_balance: u64,
+    ) -> Result<()> {
+        instructions::initialize_vault_handler(ctx, min_deposit, max_balance)
+    }
+
+    /// Update a vault's deposit floor and balance cap
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault and owner signer
+    /// * `min_deposit` - Smallest accepted deposit (0 for no floor)
+    /// * `max_balance` - Cap on the vault's total balance (0 for unlimited)
+    ///
+    /// # Errors
+    /// Returns error if the signer is not the owner or `min_deposit`
+    /// exceeds a non-zero `max_balance`
+    pub fn set_vault_limits(
+        ctx: Context<SetVaultLimits>,
+        min_deposit: u64,
+        max_balance: u64,
+    ) -> Result<()> {
+        set_vault_limits_handler(ctx, min_deposit, max_balance)
     }
 
     /// Deposit tokens into a vault as collateral
@@ -78,10 +178,36 @@ pub mod goquant_assignment {
     ///
     /// # Events
     /// Emits `DepositEvent` on success
+    ///
+    /// # Errors
+    /// Returns error if the amount is below the vault's `min_deposit` or
+    /// would push the total balance over `max_balance`, and `MintMismatch` if
+    /// the user's token account holds a different mint than the vault's
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         deposit_handler(ctx, amount)
     }
 
+    /// Deposit tokens into someone else's vault
+    ///
+    /// Transfers tokens from the funder's token account to the vault's token
+    /// account and credits the vault exactly like `deposit`. The funder gains
+    /// no claim on the vault; only its owner can withdraw.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with the funder signer, vault and token accounts
+    /// * `amount` - Amount of tokens to deposit (in smallest units)
+    ///
+    /// # Events
+    /// Emits `DepositEvent` with `funder` set to the signer
+    ///
+    /// # Errors
+    /// Returns error if the amount is below the vault's `min_deposit` or
+    /// would push the total balance over `max_balance`, and `MintMismatch` if
+    /// the funder's token account holds a different mint than the vault's
+    pub fn deposit_for(ctx: Context<DepositFor>, amount: u64) -> Result<()> {
+        deposit_for_handler(ctx, amount)
+    }
+
     /// Withdraw tokens from a vault
     ///
     /// Transfers tokens from vault back to user's token account.
@@ -95,11 +221,92 @@ pub mod goquant_assignment {
     /// Emits `WithdrawEvent` on success
     ///
     /// # Errors
-    /// Returns error if insufficient available balance
+    /// Returns error if insufficient available balance, or if `authority_program`
+    /// is not an authorized program signing the instruction. Returns `MintMismatch`
+    /// if the user's token account holds a different mint than the vault's
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         withdraw_handler(ctx, amount)
     }
 
+    /// Withdraw the entire available balance from a vault
+    ///
+    /// Transfers `available_balance` back to the user's token account,
+    /// leaving locked collateral untouched.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault and token accounts
+    ///
+    /// # Events
+    /// Emits `WithdrawEvent` with the withdrawn amount on success
+    ///
+    /// # Errors
+    /// Returns `InsufficientBalance` if nothing is available to withdraw, and
+    /// `MintMismatch` as for `withdraw`
+    pub fn withdraw_all(ctx: Context<Withdraw>) -> Result<()> {
+        withdraw_all_handler(ctx)
+    }
+
+    /// Withdraw tokens from a vault to any token account of the vault's mint
+    ///
+    /// Works like `withdraw`, but the destination need not be owned by the
+    /// signer, e.g. a custodial account or another wallet. Sending to an account
+    /// owned by someone else requires `confirm_foreign_destination`.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault and destination token accounts
+    /// * `amount` - Amount of tokens to withdraw (in smallest units)
+    /// * `confirm_foreign_destination` - Acknowledges a destination the signer does not own
+    ///
+    /// # Events
+    /// Emits `WithdrawEvent` with `destination` set to the receiving token account
+    ///
+    /// # Errors
+    /// Returns `ForeignDestinationNotConfirmed` if the destination belongs to
+    /// another wallet and the flag is unset, otherwise as for `withdraw`
+    pub fn withdraw_to(
+        ctx: Context<WithdrawTo>,
+        amount: u64,
+        confirm_foreign_destination: bool,
+    ) -> Result<()> {
+        withdraw_to_handler(ctx, amount, confirm_foreign_destination)
+    }
+
+    /// Upgrade a vault account to the current layout version
+    ///
+    /// Reads any older layout (v0 through v4), reallocates the account to
+    /// `CollateralVault::LEN` (owner pays any extra rent) and rewrites it
+    /// with `version = CURRENT_VERSION`. Vaults already grown by
+    /// `realloc_vault` are read the same way.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault account and owner signer
+    ///
+    /// # Errors
+    /// Returns error if the vault is already current, the data is not a
+    /// known layout, or the signer is not the vault owner
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        migrate_vault_handler(ctx)
+    }
+
+    /// Grow a legacy vault account to the current layout size ahead of `migrate_vault`
+    ///
+    /// Reallocates a versioned (v1 through v4) vault to `CollateralVault::LEN`,
+    /// with the owner paying the extra rent, and leaves its data untouched so
+    /// `migrate_vault` can still read the old layout. Lets the rent top-up and
+    /// the rewrite land in separate transactions.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault account and owner signer
+    ///
+    /// # Errors
+    /// Returns `AlreadyMigrated` if the account is already at (or past) the
+    /// current size, so it never shrinks and runs once per migration,
+    /// `InvalidVaultData` for an unversioned or unknown layout, and
+    /// `UnAuthorized` if the signer is not the vault owner
+    pub fn realloc_vault(ctx: Context<ReallocVault>) -> Result<()> {
+        realloc_vault_handler(ctx)
+    }
+
     /// Lock collateral for DeFi protocol use
     ///
     /// Moves tokens from available to locked balance.
@@ -108,14 +315,47 @@ pub mod goquant_assignment {
     /// # Arguments
     /// * `ctx` - Program context with vault account
     /// * `amount` - Amount of tokens to lock (in smallest units)
+    /// * `nonce` - The vault's current `nonce`; advanced on success
     ///
     /// # Events
     /// Emits `LockEvent` on success
     ///
     /// # Errors
-    /// Returns error if insufficient available balance
-    pub fn lock_collateral(ctx: Context<LockCollateral>, amount: u64) -> Result<()> {
-        lock_collateral_handler(ctx, amount)
+    /// Returns error if insufficient available balance, or `InvalidNonce`
+    /// for a stale or replayed nonce
+    pub fn lock_collateral(ctx: Context<LockCollateral>, amount: u64, nonce: u64) -> Result<()> {
+        lock_collateral_handler(ctx, amount, nonce)
+    }
+
+    /// Lock collateral across several vaults in one instruction
+    ///
+    /// `remaining_accounts` holds one `(vault, vault_authority)` pair per
+    /// amount. Every pair is validated and locked; any failure aborts the
+    /// whole batch.
+    ///
+    /// Each vault costs roughly 10-15k compute units (PDA check, account
+    /// (de)serialization and event log), so a full batch of `MAX_BATCH_SIZE`
+    /// fits within the default 200k limit. Callers adding other instructions
+    /// should request a higher compute unit limit.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with the authorized program and vault pairs
+    /// * `amounts` - Amount to lock in each vault (in smallest units)
+    /// * `nonces` - Each vault's current `nonce`, in the same order; advanced on success
+    ///
+    /// # Events
+    /// Emits one `LockEvent` per vault on success
+    ///
+    /// # Errors
+    /// Returns `BatchTooLarge` above `MAX_BATCH_SIZE` vaults, `InvalidNonce`
+    /// for a stale or replayed nonce, or the first authorization/balance
+    /// error encountered
+    pub fn batch_lock<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchLock<'info>>,
+        amounts: Vec<u64>,
+        nonces: Vec<u64>,
+    ) -> Result<()> {
+        batch_lock_handler(ctx, amounts, nonces)
     }
 
     /// Unlock previously locked collateral
@@ -125,28 +365,129 @@ pub mod goquant_assignment {
     /// # Arguments
     /// * `ctx` - Program context with vault account
     /// * `amount` - Amount of tokens to unlock (in smallest units)
+    /// * `nonce` - The vault's current `nonce`; advanced on success
     ///
     /// # Events
     /// Emits `UnlockEvent` on success
     ///
     /// # Errors
-    /// Returns error if insufficient locked balance
-    pub fn unlock_collateral(ctx: Context<UnLockCollateral>, amount: u64) -> Result<()> {
-        unlock_collateral_handler(ctx, amount)
+    /// Returns error if insufficient locked balance, `HasOpenPositions` if the
+    /// amount would free collateral backing an open position, if `authority_program`
+    /// is not an authorized program signing the instruction, or `InvalidNonce`
+    /// for a stale or replayed nonce
+    pub fn unlock_collateral(
+        ctx: Context<UnLockCollateral>,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        unlock_collateral_handler(ctx, amount, nonce)
+    }
+
+    /// Lock collateral against a new position
+    ///
+    /// Locks `amount` like `lock_collateral` and records it in a `Position`
+    /// account at `[b"position", vault, position_id]`. That collateral cannot be
+    /// unlocked until `close_position` is called.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault, new position account and authorized program
+    /// * `position_id` - Caller-chosen ID, unique per vault
+    /// * `amount` - Amount of tokens to lock (in smallest units)
+    /// * `nonce` - The vault's current `nonce`; advanced on success
+    ///
+    /// # Events
+    /// Emits `LockEvent` and `PositionOpenedEvent` on success
+    ///
+    /// # Errors
+    /// Returns error if insufficient available balance, the program is not
+    /// authorized, the position ID is already open, or `InvalidNonce`
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        position_id: u64,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        open_position_handler(ctx, position_id, amount, nonce)
+    }
+
+    /// Close a position, making its collateral unlockable
+    ///
+    /// The collateral stays locked; a following `unlock_collateral` frees it.
+    /// The position account's rent goes back to whoever opened it.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault, position account and authorized program
+    ///
+    /// # Events
+    /// Emits `PositionClosedEvent` on success
+    ///
+    /// # Errors
+    /// Returns `UnAuthorized` unless signed by the program that opened the position
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        close_position_handler(ctx)
+    }
+
+    /// Liquidate locked collateral
+    ///
+    /// Seizes locked collateral for an underwater position, transferring it
+    /// from the vault to the liquidator's token account.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault, token accounts and authorized program
+    /// * `amount` - Amount of locked tokens to seize (in smallest units)
+    /// * `nonce` - The vault's current `nonce`; advanced on success
+    ///
+    /// # Events
+    /// Emits `LiquidationEvent` on success
+    ///
+    /// # Errors
+    /// Returns error if the caller is not a signing authorized program or the
+    /// locked balance is insufficient, or `InvalidNonce` for a stale or
+    /// replayed nonce
+    pub fn liquidate_collateral(
+        ctx: Context<LiquidateCollateral>,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        liquidate_collateral_handler(ctx, amount, nonce)
     }
 
     /// Transfer collateral between vaults
     ///
     /// Moves collateral from one vault to another.
-    /// Both vaults must be owned by the signer.
+    /// Must be signed by a program authorized on the source vault.
     ///
     /// # Arguments
     /// * `ctx` - Program context with both vault accounts
     /// * `amount` - Amount of tokens to transfer (in smallest units)
+    /// * `nonce` - The source vault's current `nonce`; advanced on success
     ///
     /// # Events
     /// Emits `TransferEvent` on success
-    pub fn transfer_collateral(ctx: Context<TransferCollateral>, amount: u64) -> Result<()> {
-        transfer_collateral_handler(ctx, amount)
+    pub fn transfer_collateral(
+        ctx: Context<TransferCollateral>,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        transfer_collateral_handler(ctx, amount, nonce)
+    }
+
+    /// Transfer vault ownership to a new wallet
+    ///
+    /// Only `vault.owner` changes. The vault PDA stays derived from the
+    /// original creator's key, so its address and token account are unchanged;
+    /// instructions authorize against `vault.owner` instead of the PDA seed.
+    ///
+    /// # Arguments
+    /// * `ctx` - Program context with vault and current owner signer
+    /// * `new_owner` - Wallet that will own the vault
+    ///
+    /// # Events
+    /// Emits `OwnershipTransferredEvent` on success
+    ///
+    /// # Errors
+    /// Returns error if the signer is not the current owner
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
+        transfer_ownership_handler(ctx, new_owner)
     }
 }
@@ -16,6 +16,8 @@ pub struct DepositEvent {
     pub new_total_balance: u64,
     pub new_available_balance: u64,
     pub timestamp: i64,
+    /// Wallet the tokens came from; `user` unless deposited through `deposit_for`
+    pub funder: Pubkey,
 }
 
 #[event]
@@ -26,6 +28,8 @@ pub struct WithdrawEvent {
     pub new_total_balance: u64,
     pub new_available_balance: u64,
     pub timestamp: i64,
+    /// Token account the tokens were sent to
+    pub destination: Pubkey,
 }
 
 #[event]
@@ -53,3 +57,58 @@ pub struct TransferEvent {
     pub amount: u64,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct LiquidationEvent {
+    pub vault: Pubkey,
+    pub liquidator: Pubkey,
+    pub amount: u64,
+    pub remaining_locked: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnershipTransferredEvent {
+    pub vault: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAddedEvent {
+    pub vault: Pubkey,
+    pub program_id: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalPauseEvent {
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AllowedMintsUpdatedEvent {
+    pub authority: Pubkey,
+    pub allowed_mints: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionOpenedEvent {
+    pub vault: Pubkey,
+    pub position_id: u64,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PositionClosedEvent {
+    pub vault: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
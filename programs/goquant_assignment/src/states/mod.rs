@@ -1,5 +1,9 @@
 pub mod events;
+pub mod position;
+pub mod program_config;
 pub mod vault;
 
 pub use events::*;
+pub use position::*;
+pub use program_config::*;
 pub use vault::*;
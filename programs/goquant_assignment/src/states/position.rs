@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Collateral an authorized program locked against one open position,
+/// at `[b"position", vault, position_id]`. Closing it lets that collateral be unlocked.
+#[account]
+pub struct Position {
+    pub vault: Pubkey,
+    /// Authorized program that opened the position; only it can close it
+    pub authority: Pubkey,
+    pub position_id: u64,
+    pub amount: u64,
+    /// Paid the account's rent and receives it back on close
+    pub payer: Pubkey,
+    pub bump: u8,
+}
+
+impl Position {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 32 + 1;
+    pub const SEED: &'static [u8] = b"position";
+}
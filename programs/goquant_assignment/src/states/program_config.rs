@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// Mints a `ProgramConfig` can allow; its account is sized for exactly this many
+pub const MAX_ALLOWED_MINTS: usize = 16;
+
+/// Program-wide settings, one per deployment at `[b"program_config"]`
+#[account]
+pub struct ProgramConfig {
+    /// Key allowed to change these settings; the upgrade authority at initialization
+    pub authority: Pubkey,
+    /// Circuit breaker: while set, every instruction that moves or locks collateral fails
+    pub global_paused: bool,
+    pub bump: u8,
+    /// Mints vaults may be created for; empty allows any mint
+    pub allowed_mints: Vec<Pubkey>,
+}
+
+impl ProgramConfig {
+    pub const LEN: usize = 32 + 1 + 1 + 4 + (32 * MAX_ALLOWED_MINTS); // 4 bytes are the vector length
+    pub const SEED: &'static [u8] = b"program_config";
+
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.is_empty() || self.allowed_mints.contains(mint)
+    }
+}
@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 
 #[account]
 pub struct CollateralVault {
+    /// Layout version, see `CollateralVault::CURRENT_VERSION`
+    pub version: u8,
     pub owner: Pubkey,
     pub token_account: Pubkey,
     pub total_balance: u64,
@@ -11,19 +13,130 @@ pub struct CollateralVault {
     pub total_withdrawn: u64,
     pub created_at: i64,
     pub bump: u8,
+    /// Key the vault PDA was derived from. `owner` can change via
+    /// `transfer_ownership`, but the PDA address and its signer seeds cannot.
+    pub creator: Pubkey,
+    /// Smallest accepted deposit, 0 for no floor
+    pub min_deposit: u64,
+    /// Cap on `total_balance` after a deposit, 0 for unlimited
+    pub max_balance: u64,
+    /// Advanced by every lock, unlock and outgoing transfer, whose instruction
+    /// data must carry the current value so a captured call can't be replayed
+    pub nonce: u64,
+    /// Part of `locked_balance` backing open `Position`s; `unlock_collateral`
+    /// can only free the rest until `close_position` releases it
+    pub position_locked: u64,
+    /// Unix time of the last instruction that changed the vault, starting at
+    /// `created_at`; lets off-chain monitors spot dormant vaults
+    pub last_activity: i64,
 }
 
 impl CollateralVault {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 8 + 8 + 8 + 8;
+
+    /// Version written by `initialize_vault` and `migrate_vault`
+    pub const CURRENT_VERSION: u8 = 5;
+
+    /// v4 layout: v3 + `position_locked`
+    pub const V4_LEN: usize = Self::V3_LEN + 8;
+
+    /// v3 layout: v2 + `nonce`
+    pub const V3_LEN: usize = Self::V2_LEN + 8;
+
+    /// v2 layout: v1 + `min_deposit` and `max_balance`
+    pub const V2_LEN: usize = Self::V1_LEN + 8 + 8;
+
+    /// v1 layout: version + legacy fields + `creator`
+    pub const V1_LEN: usize = 1 + Self::LEGACY_LEN + 32;
+
+    /// Unversioned layout from before `version` and `creator` were added
+    pub const LEGACY_LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Check a deposit of `amount` against the vault's floor and cap
+    pub fn check_deposit_limits(&self, amount: u64) -> Result<()> {
+        require!(
+            self.min_deposit == 0 || amount >= self.min_deposit,
+            crate::error::VaultError::BelowMinimumDeposit
+        );
+
+        let new_total = self
+            .total_balance
+            .checked_add(amount)
+            .ok_or(crate::error::VaultError::OverFlow)?;
+        require!(
+            self.max_balance == 0 || new_total <= self.max_balance,
+            crate::error::VaultError::ExceedsMaxBalance
+        );
+
+        Ok(())
+    }
+
+    /// Add a deposit of `amount` to the total, available and deposited balances
+    pub fn credit_deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_balance = self
+            .total_balance
+            .checked_add(amount)
+            .ok_or(crate::error::VaultError::OverFlow)?;
+        self.available_balance = self
+            .available_balance
+            .checked_add(amount)
+            .ok_or(crate::error::VaultError::OverFlow)?;
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(crate::error::VaultError::OverFlow)?;
+        self.check_balance_invariant()
+    }
+
+    /// Reject `nonce` unless it is the vault's current one, then advance it
+    pub fn consume_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(nonce == self.nonce, crate::error::VaultError::InvalidNonce);
+        self.nonce = self
+            .nonce
+            .checked_add(1)
+            .ok_or(crate::error::VaultError::OverFlow)?;
+
+        Ok(())
+    }
+
+    /// Stamp `last_activity` with the current cluster time
+    pub fn record_activity(&mut self) -> Result<()> {
+        self.last_activity = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// Locked collateral not tied to an open position, i.e. what may be unlocked
+    pub fn unlockable_balance(&self) -> u64 {
+        self.locked_balance.saturating_sub(self.position_locked)
+    }
+
+    /// Seeds the vault PDA signs with. The vault PDA, not `VaultAuthority`, is the
+    /// token authority of the vault ATA, so every transfer out of it signs with these.
+    pub fn signer_seeds(&self) -> [&[u8]; 3] {
+        [b"vault", self.creator.as_ref(), std::slice::from_ref(&self.bump)]
+    }
+
+    /// Post-condition for every balance change: `total == available + locked`
+    pub fn check_balance_invariant(&self) -> Result<()> {
+        require!(
+            self.available_balance.checked_add(self.locked_balance) == Some(self.total_balance),
+            crate::error::VaultError::BalanceInvariantViolation
+        );
+
+        Ok(())
+    }
 }
 
+/// Programs a single `VaultAuthority` can hold; its account is sized for exactly this many
+pub const MAX_AUTHORIZED_PROGRAMS: usize = 8;
+
 #[account]
 pub struct VaultAuthority {
     pub authorized_programs: Vec<Pubkey>,
     pub bump: u8,
 }
 impl VaultAuthority {
-    pub const LEN: usize = 4 + (32 * 8) + 1; // 4 bytes are the vector length
+    pub const LEN: usize = 4 + (32 * MAX_AUTHORIZED_PROGRAMS) + 1; // 4 bytes are the vector length
 }
 impl VaultAuthority {
     pub fn is_program_authorized(&self, program: &Pubkey) -> bool {
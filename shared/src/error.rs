@@ -7,6 +7,8 @@ pub enum VaultError{
   DatabaseError(String),
   #[error("Invalid pubkey: {0}")]
   InvalidPubkey(String),
+  #[error("Invalid signature: {0}")]
+  InvalidSignature(String),
   #[error("Vault not found: {0}")]
   VaultNotFound(String),
   #[error("Insufficient Balance : available={available}, required={required}")]
@@ -15,6 +17,14 @@ pub enum VaultError{
   InsufficientLockedBalance { locked: i64, required: i64 },
   #[error("Invalid amount: {0}")]
   InvalidAmount(String),
+  #[error("Deposit of {amount} is below the vault minimum of {min_deposit}")]
+  BelowMinimumDeposit { amount: i64, min_deposit: i64 },
+  #[error("Deposit would bring the vault to {new_total}, above its maximum of {max_balance}")]
+  ExceedsMaxBalance { new_total: i64, max_balance: i64 },
+  #[error("Amount {amount} exceeds the per-operation maximum of {max}")]
+  AmountExceedsMax { amount: u64, max: u64 },
+  #[error("Mint {0} is not on the allowlist")]
+  MintNotAllowed(String),
   #[error("Arithmetic overflow")]
   Overflow,
   #[error("Arithmetic underflow")]
@@ -37,4 +47,66 @@ pub enum VaultError{
   InternalError(String),
 }
 
-pub type VaultResult<T> = Result<T , VaultError>;
\ No newline at end of file
+impl VaultError{
+  /// Stable code for API clients to branch on
+  pub fn code(&self) -> &'static str {
+    use error_codes::*;
+    match self {
+      VaultError::DatabaseError(_) => DATABASE_ERROR,
+      VaultError::InvalidPubkey(_) => INVALID_PUBKEY,
+      VaultError::InvalidSignature(_) => INVALID_SIGNATURE,
+      VaultError::VaultNotFound(_) => VAULT_NOT_FOUND,
+      VaultError::InsufficientBalance { .. } => INSUFFICIENT_BALANCE,
+      VaultError::InsufficientLockedBalance { .. } => INSUFFICIENT_LOCKED_BALANCE,
+      VaultError::InvalidAmount(_) => INVALID_AMOUNT,
+      VaultError::BelowMinimumDeposit { .. } => BELOW_MINIMUM_DEPOSIT,
+      VaultError::ExceedsMaxBalance { .. } => EXCEEDS_MAX_BALANCE,
+      VaultError::AmountExceedsMax { .. } => AMOUNT_EXCEEDS_MAX,
+      VaultError::MintNotAllowed(_) => MINT_NOT_ALLOWED,
+      VaultError::Overflow | VaultError::Underflow => ARITHMETIC_ERROR,
+      VaultError::BalanceInvariantViolation { .. } => BALANCE_INVARIANT_VIOLATION,
+      VaultError::Unauthorized => UNAUTHORIZED,
+      VaultError::TransactionNotFound(_) => TRANSACTION_NOT_FOUND,
+      VaultError::SolanaRpcError(_) => SOLANA_RPC_ERROR,
+      VaultError::SerializationError(_) | VaultError::DeserializationError(_) => SERIALIZATION_ERROR,
+      VaultError::ConfigError(_) | VaultError::InternalError(_) => INTERNAL_ERROR,
+    }
+  }
+}
+
+pub type VaultResult<T> = Result<T , VaultError>;
+
+/// Values of `ApiResponse::error_code`; these are part of the API contract and must not change
+pub mod error_codes{
+  pub const VAULT_NOT_FOUND: &str = "VAULT_NOT_FOUND";
+  pub const VAULT_ALREADY_EXISTS: &str = "VAULT_ALREADY_EXISTS";
+  pub const TRANSACTION_NOT_FOUND: &str = "TRANSACTION_NOT_FOUND";
+  pub const WEBHOOK_NOT_FOUND: &str = "WEBHOOK_NOT_FOUND";
+  pub const INSUFFICIENT_BALANCE: &str = "INSUFFICIENT_BALANCE";
+  pub const INSUFFICIENT_LOCKED_BALANCE: &str = "INSUFFICIENT_LOCKED_BALANCE";
+  pub const INVALID_PUBKEY: &str = "INVALID_PUBKEY";
+  pub const INVALID_SIGNATURE: &str = "INVALID_SIGNATURE";
+  pub const INVALID_AMOUNT: &str = "INVALID_AMOUNT";
+  pub const INVALID_REQUEST: &str = "INVALID_REQUEST";
+  pub const BELOW_MINIMUM_DEPOSIT: &str = "BELOW_MINIMUM_DEPOSIT";
+  pub const DEPOSIT_NOT_VERIFIED: &str = "DEPOSIT_NOT_VERIFIED";
+  pub const EXCEEDS_MAX_BALANCE: &str = "EXCEEDS_MAX_BALANCE";
+  pub const AMOUNT_EXCEEDS_MAX: &str = "AMOUNT_EXCEEDS_MAX";
+  pub const MINT_NOT_ALLOWED: &str = "MINT_NOT_ALLOWED";
+  pub const DAILY_LIMIT_EXCEEDED: &str = "DAILY_LIMIT_EXCEEDED";
+  pub const ARITHMETIC_ERROR: &str = "ARITHMETIC_ERROR";
+  pub const BALANCE_INVARIANT_VIOLATION: &str = "BALANCE_INVARIANT_VIOLATION";
+  pub const UNAUTHORIZED: &str = "UNAUTHORIZED";
+  pub const PROGRAM_NOT_AUTHORIZED: &str = "PROGRAM_NOT_AUTHORIZED";
+  pub const PROGRAM_PAUSED: &str = "PROGRAM_PAUSED";
+  pub const VAULT_FROZEN: &str = "VAULT_FROZEN";
+  pub const UNSUPPORTED_TOKEN_PROGRAM: &str = "UNSUPPORTED_TOKEN_PROGRAM";
+  pub const IDEMPOTENCY_KEY_REUSED: &str = "IDEMPOTENCY_KEY_REUSED";
+  pub const IDEMPOTENCY_KEY_IN_PROGRESS: &str = "IDEMPOTENCY_KEY_IN_PROGRESS";
+  pub const DATABASE_ERROR: &str = "DATABASE_ERROR";
+  pub const SOLANA_RPC_ERROR: &str = "SOLANA_RPC_ERROR";
+  pub const SERIALIZATION_ERROR: &str = "SERIALIZATION_ERROR";
+  pub const TRANSACTION_BUILD_ERROR: &str = "TRANSACTION_BUILD_ERROR";
+  pub const NOT_IMPLEMENTED: &str = "NOT_IMPLEMENTED";
+  pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
+}
\ No newline at end of file
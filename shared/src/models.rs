@@ -13,6 +13,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{prelude::FromRow, types::JsonValue};
+use uuid::Uuid;
+
+use crate::error::{VaultError, VaultResult};
+use crate::utils::{base_units_to_ui, format_ui_amount};
 
 /// Represents a collateral vault owned by a user
 ///
@@ -40,6 +44,17 @@ pub struct Vault {
     pub created_at: DateTime<Utc>,
     /// When the vault was last updated
     pub updated_at: DateTime<Utc>,
+    /// Smallest accepted deposit (0 for no floor)
+    pub min_deposit: i64,
+    /// Cap on the total balance after a deposit (0 for unlimited)
+    pub max_balance: i64,
+    /// Decimals of the collateral mint, used to render base units
+    pub decimals: i16,
+    /// Program owning `token_account`: legacy SPL Token or Token-2022
+    pub token_program: String,
+    /// When the vault last had a recorded deposit, withdrawal, lock, unlock or
+    /// transfer; mirrors the on-chain `last_activity`
+    pub last_activity: DateTime<Utc>,
 }
 
 impl Vault {
@@ -61,6 +76,29 @@ impl Vault {
         self.locked_balance >= amount
     }
 
+    /// Check a deposit of `amount` against the vault's floor and cap
+    pub fn check_deposit_limits(&self, amount: i64) -> VaultResult<()> {
+        if self.min_deposit > 0 && amount < self.min_deposit {
+            return Err(VaultError::BelowMinimumDeposit {
+                amount,
+                min_deposit: self.min_deposit,
+            });
+        }
+
+        let new_total = self
+            .total_balance
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+        if self.max_balance > 0 && new_total > self.max_balance {
+            return Err(VaultError::ExceedsMaxBalance {
+                new_total,
+                max_balance: self.max_balance,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Verify that the vault's balance invariant holds
     ///
     /// The invariant is: total_balance = available_balance + locked_balance
@@ -82,6 +120,45 @@ impl Vault {
     }
 }
 
+/// API view of a [`Vault`]: every raw field plus balances in USDT
+///
+/// The raw `i64` fields are base units of the vault's mint; the companions
+/// apply `decimals` so clients don't have to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultResponse {
+    #[serde(flatten)]
+    pub vault: Vault,
+    pub total_balance_usdt: f64,
+    pub locked_balance_usdt: f64,
+    pub available_balance_usdt: f64,
+    /// `total_balance` formatted for display, e.g. `"1000.000000 USDT"`
+    pub total_balance_formatted: String,
+}
+
+impl From<Vault> for VaultResponse {
+    fn from(vault: Vault) -> Self {
+        let decimals = vault.decimals as u8;
+        Self {
+            total_balance_usdt: base_units_to_ui(vault.total_balance, decimals),
+            locked_balance_usdt: base_units_to_ui(vault.locked_balance, decimals),
+            available_balance_usdt: base_units_to_ui(vault.available_balance, decimals),
+            total_balance_formatted: format_ui_amount(vault.total_balance, decimals, "USDT"),
+            vault,
+        }
+    }
+}
+
+/// Response to `/vault/initialize`
+///
+/// `already_existed` is set when the vault was registered before; it is then
+/// returned as stored rather than reset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitializeVaultResponse {
+    #[serde(flatten)]
+    pub vault: VaultResponse,
+    pub already_existed: bool,
+}
+
 /// Record of a vault transaction/operation
 ///
 /// Tracks all operations performed on vaults for audit and history purposes.
@@ -130,6 +207,8 @@ pub enum TransactionType {
     Unlock,
     /// Transfer collateral between vaults
     Transfer,
+    /// Locked collateral seized by an authorized program
+    Liquidation,
 }
 
 impl TransactionType {
@@ -141,6 +220,7 @@ impl TransactionType {
             TransactionType::Transfer => "transfer",
             TransactionType::Unlock => "unlock",
             TransactionType::Withdraw => "withdraw",
+            TransactionType::Liquidation => "liquidation",
         }
     }
 }
@@ -231,6 +311,121 @@ impl ReconciliationStatus {
     }
 }
 
+/// On-chain event the listener failed to apply, kept for retry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FailedEvent {
+    pub id: i64,
+    pub tx_signature: String,
+    pub event_type: String,
+    /// Raw Anchor event bytes: 8-byte discriminator followed by the payload
+    pub event_data: Vec<u8>,
+    /// Error from the most recent attempt
+    pub error: String,
+    pub retry_count: i32,
+    /// `pending`, `resolved` or `abandoned`
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Registered HTTP callback for vault events
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// HMAC-SHA256 key; never returned by the API
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+    /// Event types delivered; empty for all
+    pub event_types: Vec<String>,
+    /// Only deliver events for this vault; `None` for all
+    pub vault_pubkey: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Stored outcome of a request made with an `Idempotency-Key` header
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct IdempotencyRecord {
+    pub idempotency_key: String,
+    /// SHA-256 of the endpoint and request body
+    pub request_hash: String,
+    /// `None` while the original request is still running
+    pub status_code: Option<i32>,
+    pub response: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Session issued by `/auth`; its `secret` keys the `X-Signature` HMAC of mutation requests
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiSession {
+    pub session_id: Uuid,
+    /// Wallet that proved ownership when the session was issued
+    pub owner_pubkey: String,
+    /// Hex HMAC-SHA256 key; only returned when the session is issued
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Per-vault auto-lock opt-in: available balance above `auto_lock_buffer` is locked automatically
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AutoLockSettings {
+    pub vault_pubkey: String,
+    pub auto_lock_enabled: bool,
+    /// Available balance left untouched by auto-lock
+    pub auto_lock_buffer: i64,
+}
+
+impl AutoLockSettings {
+    /// Amount auto-lock should lock for a vault with `available` balance, if any
+    pub fn excess(&self, available: i64) -> Option<i64> {
+        let excess = available.checked_sub(self.auto_lock_buffer)?;
+        (self.auto_lock_enabled && excess > 0).then_some(excess)
+    }
+}
+
+/// Per-vault thresholds below which the monitor raises a `low_balance` alert
+///
+/// A floor or percentage of 0 falls back to the server-wide default.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LowBalanceSettings {
+    pub vault_pubkey: String,
+    /// Absolute available balance to stay above
+    pub low_balance_floor: i64,
+    /// Share of the total balance, in percent, to keep available
+    pub low_balance_percent: f64,
+}
+
+impl LowBalanceSettings {
+    /// Available balance below which a vault holding `total_balance` is low: the larger of
+    /// the floor and the percentage, each falling back to the given default when unset
+    pub fn threshold(&self, total_balance: i64, default_floor: i64, default_percent: f64) -> i64 {
+        let floor = if self.low_balance_floor > 0 {
+            self.low_balance_floor
+        } else {
+            default_floor
+        };
+        let percent = if self.low_balance_percent > 0.0 {
+            self.low_balance_percent
+        } else {
+            default_percent
+        };
+        floor.max((total_balance as f64 * percent / 100.0) as i64)
+    }
+}
+
+/// Per-vault caps on deposits and withdrawals over any rolling 24h window
+///
+/// A limit of 0 falls back to the server-wide default.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyLimitSettings {
+    pub vault_pubkey: String,
+    pub daily_deposit_limit: i64,
+    pub daily_withdraw_limit: i64,
+}
+
 #[derive(Debug , Clone , Serialize , Deserialize , FromRow)]
 pub struct AuditTrailEntry {
   pub id : i64,
@@ -243,6 +438,22 @@ pub struct AuditTrailEntry {
   pub ip_address: Option<String>,
   pub user_agent: Option<String>,
   pub created_at: DateTime<Utc>,
+  /// `entry_hash` of the previous entry in this vault's chain; `None` outside the chain
+  pub prev_hash: Option<String>,
+  /// Hex sha256 of `prev_hash` followed by this entry's contents
+  pub entry_hash: Option<String>,
+}
+
+/// Result of walking one vault's audit trail hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditChainVerification {
+    pub vault_pubkey: String,
+    /// Hashed entries checked, up to and including the first broken one
+    pub entries_checked: i64,
+    pub valid: bool,
+    /// First entry whose link or hash doesn't match
+    pub broken_entry_id: Option<i64>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -337,11 +548,260 @@ pub struct TvlStats {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Deposit and withdrawal totals across all vaults over a time window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ProtocolVolume {
+    pub deposit_volume: i64,
+    pub withdraw_volume: i64,
+}
+
+/// Active-vault counts behind the analytics overview
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, FromRow)]
+pub struct VaultActivityStats {
+    /// Active vaults holding a nonzero balance
+    pub funded_vaults: i64,
+    /// Mean of `locked / total` over funded vaults, as a percentage
+    pub avg_utilization_pct: f64,
+}
+
+/// Protocol KPIs for the dashboard, served by `GET /analytics/overview`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsOverview {
+    pub total_vaults: i64,
+    /// Vaults with a nonzero balance
+    pub active_vaults: i64,
+    pub total_value_locked: i64,
+    /// Non-failed deposits recorded in the last 24h
+    pub deposit_volume_24h: i64,
+    /// Non-failed withdrawals recorded in the last 24h
+    pub withdraw_volume_24h: i64,
+    pub avg_utilization_pct: f64,
+    /// Largest vaults by `total_balance`
+    pub top_vaults: Vec<VaultResponse>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Bucket width for TVL and vault balance history
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TvlInterval {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl TvlInterval {
+    /// Field name understood by Postgres `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TvlInterval::Hour => "hour",
+            TvlInterval::Day => "day",
+        }
+    }
+
+    /// Width of one bucket
+    pub fn duration(&self) -> chrono::Duration {
+        match self {
+            TvlInterval::Hour => chrono::Duration::hours(1),
+            TvlInterval::Day => chrono::Duration::days(1),
+        }
+    }
+
+    /// Start of the bucket holding `at`, as UTC `date_trunc` would give it
+    pub fn truncate(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        use chrono::DurationRound;
+        at.duration_trunc(self.duration()).unwrap_or(at)
+    }
+}
+
+/// A vault's reconstructed balances at the close of the bucket starting at `timestamp`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BalanceHistoryPoint {
+    /// Start of the bucket
+    pub timestamp: DateTime<Utc>,
+    pub total: i64,
+    pub available: i64,
+    pub locked: i64,
+}
+
+/// Outcome of one balance reconciliation cycle, as stored in `reconciliation_runs`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReconciliationRun {
+    pub id: i64,
+    pub started_at: DateTime<Utc>,
+    pub total_vaults: i64,
+    /// Vaults whose on-chain balance differed, including those auto-repaired
+    pub mismatches: i64,
+    pub repaired: i64,
+    /// Vaults that could not be checked
+    pub errors: i64,
+    pub duration_ms: i64,
+}
+
+/// Collateral an authorized program holds locked against an open position, as stored in `positions`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VaultPosition {
+    pub vault_pubkey: String,
+    pub position_id: i64,
+    pub amount: i64,
+    /// Authorized program that opened the position
+    pub authority_program: String,
+    pub opened_at: DateTime<Utc>,
+    /// Earliest unlock time for time-locked positions
+    pub unlock_after: Option<DateTime<Utc>>,
+    pub tx_signature: String,
+}
+
+/// One entry of a vault's activity feed, tagged with `kind` (`transaction` or `alert`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum VaultActivity {
+    Transaction(TransactionRecord),
+    Alert(Alert),
+}
+
+impl VaultActivity {
+    pub fn created_at(&self) -> DateTime<Utc> {
+        match self {
+            VaultActivity::Transaction(tx) => tx.created_at,
+            VaultActivity::Alert(alert) => alert.created_at,
+        }
+    }
+}
+
+/// One downsampled TVL bucket; balances are averaged over the samples in it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TvlHistoryPoint {
+    pub bucket: DateTime<Utc>,
+    pub total_vaults: i64,
+    pub total_value_locked: i64,
+    pub total_locked: i64,
+    pub total_available: i64,
+    pub samples: i64,
+}
+
+/// Risk classification of a vault based on how much of its collateral is locked
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskBand {
+    /// Less than 50% of the balance is locked
+    Low,
+    /// Between 50% and 90% of the balance is locked
+    Medium,
+    /// More than 90% of the balance is locked
+    High,
+}
+
+impl RiskBand {
+    /// Classify a utilization percentage (0.0 to 100.0) into a risk band
+    pub fn from_utilization(utilization_pct: f64) -> Self {
+        if utilization_pct > 90.0 {
+            RiskBand::High
+        } else if utilization_pct >= 50.0 {
+            RiskBand::Medium
+        } else {
+            RiskBand::Low
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskBand::Low => "low",
+            RiskBand::Medium => "medium",
+            RiskBand::High => "high",
+        }
+    }
+}
+
+/// Utilization and risk summary for a single vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultUtilization {
+    pub vault_pubkey: String,
+    pub utilization_pct: f64,
+    pub available_balance: i64,
+    pub locked_balance: i64,
+    pub risk_band: RiskBand,
+}
+
+impl From<&Vault> for VaultUtilization {
+    fn from(vault: &Vault) -> Self {
+        let utilization_pct = vault.utilization();
+        Self {
+            vault_pubkey: vault.vault_pubkey.clone(),
+            utilization_pct,
+            available_balance: vault.available_balance,
+            locked_balance: vault.locked_balance,
+            risk_band: RiskBand::from_utilization(utilization_pct),
+        }
+    }
+}
+
+/// A vault field whose on-chain value disagrees with the database copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultFieldDiff {
+    pub field: String,
+    pub onchain: JsonValue,
+    pub database: JsonValue,
+}
+
+/// Parsed on-chain vault next to the database copy, for debugging drift
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnChainVaultComparison {
+    pub vault_pubkey: String,
+    pub onchain: Vault,
+    /// `None` when the vault exists on-chain but was never recorded
+    pub database: Option<Vault>,
+    /// Mismatched fields; empty when the copies agree
+    pub diff: Vec<VaultFieldDiff>,
+    pub in_sync: bool,
+}
+
+/// Current on-chain nonce of a vault, for building lock/unlock/transfer calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultNonce {
+    pub vault_pubkey: String,
+    /// Value the next collateral instruction must carry
+    pub nonce: u64,
+}
+
+/// Programs a vault's on-chain `VaultAuthority` allows to lock and unlock its collateral
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizedPrograms {
+    pub vault_pubkey: String,
+    /// The `[b"vault_authority", vault]` PDA the list was read from
+    pub vault_authority: String,
+    pub programs: Vec<String>,
+}
+
+/// Balances a vault would have after an operation that is previewed but not submitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceSimulation {
+    pub vault_pubkey: String,
+    pub operation: TransactionType,
+    pub amount: i64,
+    /// Whether the real operation would currently be accepted
+    pub valid: bool,
+    /// Why it would be rejected; `None` when `valid`
+    pub reason: Option<String>,
+    /// `error_code` the real operation would fail with; `None` when `valid`
+    pub error_code: Option<String>,
+    /// Balances after the operation, or the current ones when it would be rejected
+    pub total: i64,
+    pub available: i64,
+    pub locked: i64,
+}
+
 #[derive(Debug , Clone , Serialize , Deserialize)]
 pub struct CreateVaultRequest{
   pub vault_pubkey: String,
   pub owner_pubkey: String,
   pub token_account: String,
+  /// Decimals of the collateral mint (defaults to 6, as for USDT)
+  #[serde(default)]
+  pub decimals: Option<u8>,
+  /// Program owning `token_account`; detected from the account when omitted
+  #[serde(default)]
+  pub token_program: Option<String>,
 } 
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -359,6 +819,12 @@ pub struct ProcessWithdrawalRequest {
     pub tx_signature: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessWithdrawAllRequest {
+    pub vault_pubkey: String,
+    pub tx_signature: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockCollateralRequest {
     pub vault_pubkey: String,
@@ -373,6 +839,76 @@ pub struct UnlockCollateralRequest {
     pub tx_signature: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulateOperationRequest {
+    /// One of `deposit`, `withdraw`, `lock` or `unlock`
+    pub operation: TransactionType,
+    pub amount: i64,
+}
+
+/// Body of `POST /auth`: `signature` is the owner's base58 ed25519 signature over
+/// `goquant-vault-session:{owner_pubkey}:{timestamp}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSessionRequest {
+    pub owner_pubkey: String,
+    /// Unix seconds the message was signed at
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetAutoLockRequest {
+    pub enabled: bool,
+    pub buffer: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetDailyLimitsRequest {
+    pub daily_deposit_limit: i64,
+    pub daily_withdraw_limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLowBalanceRequest {
+    pub low_balance_floor: i64,
+    pub low_balance_percent: f64,
+}
+
+/// Operator hold on a vault: withdrawals are refused while `frozen`, reads and deposits are not
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VaultFreezeStatus {
+    pub vault_pubkey: String,
+    pub frozen: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetVaultFreezeRequest {
+    pub frozen: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileVaultsRequest {
+    pub vault_pubkeys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub secret: String,
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    pub vault_pubkey: Option<String>,
+}
+
+/// Fields left out are unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub event_types: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
 #[derive(Debug , Clone , Serialize ,Deserialize)]
 
 pub struct ApiResponse<T>{
@@ -381,15 +917,22 @@ pub struct ApiResponse<T>{
   pub data : Option<T>,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub error : Option<String>,
+  /// Stable machine-readable code from [`crate::error_codes`]
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error_code : Option<String>,
 }
 
 impl<T> ApiResponse<T>{
     pub fn success(data: T) -> Self {
-      Self { success: true, data: Some(data), error: None }
+      Self { success: true, data: Some(data), error: None, error_code: None }
     }
 
     pub fn error(error : String) -> Self{
-      Self { success: false, data: None, error: Some(error) }
+      Self { success: false, data: None, error: Some(error), error_code: None }
+    }
+
+    pub fn error_with_code(code : &str, error : String) -> Self{
+      Self { success: false, data: None, error: Some(error), error_code: Some(code.to_string()) }
     }
 }
 
@@ -405,6 +948,156 @@ fn default_limit() -> i64 {
   100
 }
 
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct VaultListParams{
+  #[serde(default = "default_limit")]
+  pub limit : i64,
+  #[serde(default)]
+  pub offset : i64,
+  pub owner : Option<String>,
+  /// Only vaults with at least this total balance
+  pub min_balance : Option<i64>,
+  /// Only vaults with at least this utilization, in percent
+  pub min_utilization : Option<f64>,
+  #[serde(default)]
+  pub sort_by : VaultSortBy,
+  #[serde(default)]
+  pub order : SortOrder,
+}
+
+impl Default for VaultListParams {
+  fn default() -> Self {
+    Self {
+      limit: default_limit(),
+      offset: 0,
+      owner: None,
+      min_balance: None,
+      min_utilization: None,
+      sort_by: VaultSortBy::default(),
+      order: SortOrder::default(),
+    }
+  }
+}
+
+/// Whitelisted sort columns for vault listings
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultSortBy {
+    #[default]
+    CreatedAt,
+    TotalBalance,
+    LockedBalance,
+}
+
+impl VaultSortBy {
+    pub fn column(&self) -> &'static str {
+        match self {
+            VaultSortBy::CreatedAt => "created_at",
+            VaultSortBy::TotalBalance => "total_balance",
+            VaultSortBy::LockedBalance => "locked_balance",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct TopUtilizationParams{
+  #[serde(default = "default_top_limit")]
+  pub limit : i64,
+}
+
+fn default_top_limit() -> i64 {
+  10
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct VaultSearchParams{
+  /// Prefix of a vault or owner pubkey
+  pub q : String,
+  #[serde(default = "default_search_limit")]
+  pub limit : i64,
+}
+
+fn default_search_limit() -> i64 {
+  20
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct ReconciliationRunParams{
+  #[serde(default = "default_top_limit")]
+  pub limit : i64,
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct VaultActivityParams{
+  #[serde(default = "default_activity_limit")]
+  pub limit : i64,
+  #[serde(default)]
+  pub offset : i64,
+}
+
+fn default_activity_limit() -> i64 {
+  50
+}
+
+/// Shortest prefix `/vault/search` accepts; shorter ones match too many rows to be useful
+pub const MIN_SEARCH_PREFIX_LEN: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayParams {
+    /// Oldest slot whose transactions are replayed
+    pub from_slot: u64,
+}
+
+/// Outcome of replaying a vault's on-chain history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub vault_pubkey: String,
+    pub from_slot: u64,
+    /// Successful transactions touching the vault since `from_slot`
+    pub signatures_found: usize,
+    /// Transactions skipped because they were already recorded
+    pub signatures_skipped: usize,
+    /// Events applied; events that failed were dead-lettered for retry
+    pub events_applied: usize,
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct TvlHistoryParams{
+  /// Inclusive start; defaults to 24 hours before `to`
+  pub from : Option<DateTime<Utc>>,
+  /// Exclusive end; defaults to now
+  pub to : Option<DateTime<Utc>>,
+  #[serde(default)]
+  pub interval : TvlInterval,
+}
+
+#[derive(Debug , Clone , Serialize , Deserialize)]
+pub struct BalanceHistoryParams{
+  /// Inclusive start; defaults to 24 hours before `to`
+  pub from : Option<DateTime<Utc>>,
+  /// Exclusive end; defaults to now
+  pub to : Option<DateTime<Utc>>,
+  #[serde(default)]
+  pub interval : TvlInterval,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
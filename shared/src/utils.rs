@@ -11,14 +11,14 @@ pub fn validate_pubkey(pubkey : &str) -> VaultResult<()>{
 
 pub fn validate_signature(signature: &str) -> VaultResult<()> {
     if signature.len() < 86 || signature.len() > 88 {
-        return Err(VaultError::InvalidPubkey(
+        return Err(VaultError::InvalidSignature(
             "Signature length must be 86-88 characters".to_string()
         ));
     }
     
     bs58::decode(signature)
         .into_vec()
-        .map_err(|e| VaultError::InvalidPubkey(format!("Invalid signature: {}", e)))?;
+        .map_err(|e| VaultError::InvalidSignature(format!("Invalid base58: {}", e)))?;
     
     Ok(())
 }
@@ -45,14 +45,83 @@ pub fn checked_mul(a: i64, b: i64) -> VaultResult<i64> {
     a.checked_mul(b).ok_or(VaultError::Overflow)
 }
 
+/// Decimals of the USDT mint, the default collateral
+pub const USDT_DECIMALS: u8 = 6;
+
+/// Largest supported mint decimals; 10^19 no longer fits in an i64
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Convert base units of a mint with `decimals` into whole tokens
+pub fn base_units_to_ui(amount: i64, decimals: u8) -> f64 {
+  amount as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Convert whole tokens into base units of a mint with `decimals`
+///
+/// Rounds to the nearest unit so values like `0.29` survive float error.
+pub fn ui_to_base_units(amount: f64, decimals: u8) -> i64 {
+  (amount * 10f64.powi(decimals as i32)).round() as i64
+}
+
+/// How `checked_ui_to_base_units` treats digits below one base unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountPrecision {
+  /// Round to the nearest base unit, like `ui_to_base_units`
+  Round,
+  /// Reject amounts that aren't a whole number of base units
+  Exact,
+}
+
+/// Convert whole tokens into base units, rejecting amounts that can't be represented
+///
+/// Negative, NaN and infinite amounts are `InvalidAmount`; amounts beyond `i64::MAX`
+/// base units are `Overflow`.
+pub fn checked_ui_to_base_units(
+  amount: f64,
+  decimals: u8,
+  precision: AmountPrecision,
+) -> VaultResult<i64> {
+  if !amount.is_finite() {
+    return Err(VaultError::InvalidAmount("Amount must be a finite number".to_string()));
+  }
+  if amount < 0.0 {
+    return Err(VaultError::InvalidAmount("Amount must not be negative".to_string()));
+  }
+
+  let scaled = amount * 10f64.powi(decimals as i32);
+  let rounded = scaled.round();
+
+  // Leave room for float error in the scaling itself, e.g. 0.29 * 10^6
+  let tolerance = scaled.max(1.0) * f64::EPSILON * 4.0;
+  if precision == AmountPrecision::Exact && (scaled - rounded).abs() > tolerance {
+    return Err(VaultError::InvalidAmount(format!(
+      "Amount has more than {} decimal places",
+      decimals
+    )));
+  }
+
+  // `i64::MAX as f64` rounds up to 2^63, which is already out of range
+  if rounded >= i64::MAX as f64 {
+    return Err(VaultError::Overflow);
+  }
+  Ok(rounded as i64)
+}
+
+pub fn format_ui_amount(amount: i64, decimals: u8, symbol: &str) -> String {
+  format!("{:.*} {}", decimals as usize, base_units_to_ui(amount, decimals), symbol)
+}
+
 pub fn base_units_to_usdt(amount: i64) -> f64 {
-  amount as f64 / 1_000_000.0
+  base_units_to_ui(amount, USDT_DECIMALS)
 }
 
-pub fn usdt_to_base_units(amount : f64) -> i64 {
-  (amount * 1_000_000.0) as i64
+/// Convert whole USDT into base units, rounding below one base unit
+///
+/// See `checked_ui_to_base_units` for the amounts rejected.
+pub fn usdt_to_base_units(amount: f64) -> VaultResult<i64> {
+  checked_ui_to_base_units(amount, USDT_DECIMALS, AmountPrecision::Round)
 }
 
 pub fn format_usdt(amount: i64) -> String {
-  format!("{:.6} USDT" , base_units_to_usdt(amount))
+  format_ui_amount(amount, USDT_DECIMALS, "USDT")
 }
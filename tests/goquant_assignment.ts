@@ -1,7 +1,13 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { GoquantAssignment } from "../target/types/goquant_assignment";
-import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createMint,
@@ -26,6 +32,8 @@ describe("Collateral Vault Management System", () => {
   const payer = provider.wallet as anchor.Wallet;
   let user1: Keypair;
   let user2: Keypair;
+  // Signs lock/unlock/transfer as an authorized program would via CPI
+  let lockAuthority: Keypair;
 
   //Token Mint (_USDT_)
   let usdtMint: PublicKey;
@@ -48,6 +56,9 @@ describe("Collateral Vault Management System", () => {
   let user2VaultAuthority: PublicKey;
   let user2VaultAuthorityBump: number;
 
+  // Program-wide circuit breaker, owned by the upgrade authority (the provider wallet)
+  let programConfig: PublicKey;
+
   const INITIAL_MINT = 10_000 * 1_000_000;
   const DEPOSIT_AMOUNT = 1_000 * 1_000_000;
   const WITHDRAWN_AMOUNT = 500 * 1_000_000;
@@ -58,6 +69,7 @@ describe("Collateral Vault Management System", () => {
     //Create test users
     user1 = Keypair.generate();
     user2 = Keypair.generate();
+    lockAuthority = Keypair.generate();
 
     //AirdropSol
     const airdropAmount = 5 * anchor.web3.LAMPORTS_PER_SOL;
@@ -143,6 +155,24 @@ describe("Collateral Vault Management System", () => {
         program.programId
       );
 
+    [programConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("program_config")],
+      program.programId
+    );
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await program.methods
+      .initializeProgramConfig()
+      .accounts({
+        authority: payer.publicKey,
+        program: program.programId,
+        programData,
+      })
+      .rpc();
+    console.log("Initialized program config");
+
     console.log(" Derived vault PDAs");
     console.log("   User1 Vault:", user1VaultPda.toBase58());
     console.log("   User2 Vault:", user2VaultPda.toBase58());
@@ -150,6 +180,14 @@ describe("Collateral Vault Management System", () => {
     console.log("\n Test environment setup complete!\n");
   });
 
+  const assertBalanceInvariant = (vault: any) => {
+    assert.equal(
+      vault.totalBalance.toNumber(),
+      vault.availableBalance.toNumber() + vault.lockedBalance.toNumber(),
+      "Balance invariant broken: available + locked != total"
+    );
+  };
+
   describe("1. Vault Initialization", () => {
     it("should initialize vault for user 1", async () => {
       user1VaultAta = await anchor.utils.token.associatedAddress({
@@ -158,7 +196,7 @@ describe("Collateral Vault Management System", () => {
       });
       console.log(user1VaultAta)
       const tx = await program.methods
-        .initializeVault()
+        .initializeVault(new anchor.BN(0), new anchor.BN(0))
         .accounts({
           user: user1.publicKey,
           mint: usdtMint,
@@ -172,6 +210,11 @@ describe("Collateral Vault Management System", () => {
       );
 
       assert.ok(vaultAccount.owner.equals(user1.publicKey));
+      assert.equal(vaultAccount.version, 5);
+      assert.equal(
+        vaultAccount.lastActivity.toNumber(),
+        vaultAccount.createdAt.toNumber()
+      );
       assert.ok(vaultAccount.tokenAccount.equals(user1VaultAta));
       assert.equal(vaultAccount.totalBalance.toNumber(), 0);
       assert.equal(vaultAccount.lockedBalance.toNumber(), 0);
@@ -195,6 +238,15 @@ describe("Collateral Vault Management System", () => {
         .authorityToAdd(program.programId)
         .accounts({
           admin: user1.publicKey,
+          vault: user1VaultPda,
+        })
+        .signers([user1])
+        .rpc();
+      await program.methods
+        .authorityToAdd(lockAuthority.publicKey)
+        .accounts({
+          admin: user1.publicKey,
+          vault: user1VaultPda,
         })
         .signers([user1])
         .rpc();
@@ -217,7 +269,7 @@ describe("Collateral Vault Management System", () => {
       
       console.log(user2VaultAta)
       await program.methods
-        .initializeVault()
+        .initializeVault(new anchor.BN(0), new anchor.BN(0))
         .accounts({
           user: user2.publicKey,
           mint: usdtMint,
@@ -249,6 +301,15 @@ describe("Collateral Vault Management System", () => {
         .authorityToAdd(program.programId)
         .accounts({
           admin: user2.publicKey,
+          vault: user2VaultPda,
+        })
+        .signers([user2])
+        .rpc();
+      await program.methods
+        .authorityToAdd(lockAuthority.publicKey)
+        .accounts({
+          admin: user2.publicKey,
+          vault: user2VaultPda,
         })
         .signers([user2])
         .rpc();
@@ -270,7 +331,7 @@ describe("Collateral Vault Management System", () => {
     it("Should fail to initialize same vault twice", async () => {
       try {
         await program.methods
-          .initializeVault()
+          .initializeVault(new anchor.BN(0), new anchor.BN(0))
           .accounts({
             user: user1.publicKey,
             mint: usdtMint,
@@ -296,6 +357,7 @@ describe("Collateral Vault Management System", () => {
         .deposit(new anchor.BN(DEPOSIT_AMOUNT))
         .accounts({
           user: user1.publicKey,
+          vault: user1VaultPda,
           vaultAta: user1VaultAta,
           userTokenAccount: user1TokenAccount,
         })
@@ -322,6 +384,7 @@ describe("Collateral Vault Management System", () => {
       assert.equal(vaultAccount.totalBalance.toNumber(), DEPOSIT_AMOUNT);
       assert.equal(vaultAccount.availableBalance.toNumber(), DEPOSIT_AMOUNT);
       assert.equal(vaultAccount.totalDeposited.toNumber(), DEPOSIT_AMOUNT);
+      assertBalanceInvariant(vaultAccount);
 
       console.log("Deposited", DEPOSIT_AMOUNT / 1_000_000, "USDT");
     });
@@ -332,6 +395,7 @@ describe("Collateral Vault Management System", () => {
         .deposit(new anchor.BN(secondDeposit))
         .accounts({
           user: user1.publicKey,
+          vault: user1VaultPda,
           userTokenAccount: user1TokenAccount,
           vaultAta: user1VaultAta,
         })
@@ -356,6 +420,7 @@ describe("Collateral Vault Management System", () => {
           .deposit(new anchor.BN(0))
           .accounts({
             user: user1.publicKey,
+            vault: user1VaultPda,
             userTokenAccount: user1TokenAccount,
             vaultAta: user1VaultAta,
           })
@@ -384,6 +449,7 @@ await
         .withdraw(new anchor.BN(WITHDRAWN_AMOUNT))
         .accounts({
           user: user1.publicKey,
+          vault: user1VaultPda,
           userTokenAccount: user1TokenAccount,
           vaultAta: user1VaultAta,
         })
@@ -405,6 +471,7 @@ await
       );
 
       assert.equal(vaultAfter.totalWithdrawn.toNumber(), WITHDRAWN_AMOUNT);
+      assertBalanceInvariant(vaultAfter);
 
       const userBalanceAfter = await getAccount(
         provider.connection,
@@ -430,6 +497,7 @@ await
           .withdraw(new anchor.BN(excessiveAmount))
           .accounts({
             user: user1.publicKey,
+            vault: user1VaultPda,
             vaultAta: user1VaultAta,
             userTokenAccount: user1TokenAccount,
           })
@@ -448,6 +516,7 @@ await
           .withdraw(new anchor.BN(100_000))
           .accounts({
             user: user2.publicKey, // Wrong user!
+            vault: user1VaultPda,
             vaultAta: user1VaultAta,
             userTokenAccount: user2TokenAccount,
           })
@@ -461,6 +530,27 @@ await
     });
   });
 
+  // Current nonce a lock, unlock or transfer out of `vault` must carry
+  const vaultNonce = async (vault: PublicKey) =>
+    (await program.account.collateralVault.fetch(vault)).nonce;
+
+  // Send `ix` with `lockAuthority` listed but not signing
+  const sendWithoutAuthoritySignature = async (ix: TransactionInstruction) => {
+    ix.keys
+      .filter((key) => key.pubkey.equals(lockAuthority.publicKey))
+      .forEach((key) => (key.isSigner = false));
+    await provider.sendAndConfirm(new Transaction().add(ix));
+  };
+
+  const assertNotSigner = (error: any) => {
+    const logs = (error.logs ?? []).join("\n");
+    assert.ok(
+      logs.includes("AccountNotSigner") ||
+        error.toString().includes("AccountNotSigner"),
+      `expected AccountNotSigner, got ${error}`
+    );
+  };
+
   describe("4. Lock/UnLock Collateral", () => {
     it("should lock collateral", async () => {
       const vaultBefore = await program.account.collateralVault.fetch(
@@ -468,11 +558,15 @@ await
       );
 
       await program.methods
-        .lockCollateral(new anchor.BN(LOCK_AMOUNT))
+        .lockCollateral(
+          new anchor.BN(LOCK_AMOUNT),
+          await vaultNonce(user1VaultPda)
+        )
         .accounts({
           vault: user1VaultPda,
-          authorityProgram: program.programId,
+          authorityProgram: lockAuthority.publicKey,
         })
+        .signers([lockAuthority])
         .rpc();
 
       const vaultAfter = await program.account.collateralVault.fetch(
@@ -487,6 +581,7 @@ await
         vaultAfter.availableBalance.toNumber(),
         vaultBefore.availableBalance.toNumber() - LOCK_AMOUNT
       );
+      assertBalanceInvariant(vaultAfter);
 
       console.log("Locked", LOCK_AMOUNT / 1_000_000, "USDT");
     });
@@ -497,11 +592,14 @@ await
       );
       const unlockAmount = LOCK_AMOUNT / 2;
       await program.methods
-        .unlockCollateral(new anchor.BN(unlockAmount))
+        .unlockCollateral(
+          new anchor.BN(unlockAmount),
+          await vaultNonce(user1VaultPda)
+        )
         .accounts({
           vault: user1VaultPda,
-          authorityProgram: program.programId,
-        }).rpc();
+          authorityProgram: lockAuthority.publicKey,
+        }).signers([lockAuthority]).rpc();
 
       const vaultAfter = await program.account.collateralVault.fetch(
         user1VaultPda
@@ -515,6 +613,7 @@ await
         vaultAfter.availableBalance.toNumber(),
         vaultBefore.availableBalance.toNumber() + unlockAmount
       );
+      assertBalanceInvariant(vaultAfter);
 
       console.log("Unlocked", unlockAmount / 1_000_000, "USDT");
     });
@@ -528,11 +627,15 @@ await
 
       try {
         await program.methods
-          .lockCollateral(new anchor.BN(excessiveAmount))
+          .lockCollateral(
+            new anchor.BN(excessiveAmount),
+            await vaultNonce(user1VaultPda)
+          )
           .accounts({
             vault: user1VaultPda,
-            authorityProgram: program.programId,
+            authorityProgram: lockAuthority.publicKey,
           })
+          .signers([lockAuthority])
           .rpc();
 
         assert.fail("Should have failed to lock excessive amount");
@@ -550,11 +653,15 @@ await
 
       try {
         await program.methods
-          .unlockCollateral(new anchor.BN(excessiveAmount))
+          .unlockCollateral(
+            new anchor.BN(excessiveAmount),
+            await vaultNonce(user1VaultPda)
+          )
           .accounts({
             vault: user1VaultPda,
-            authorityProgram: program.programId,
+            authorityProgram: lockAuthority.publicKey,
           })
+          .signers([lockAuthority])
           .rpc();
 
         assert.fail("Should have failed to unlock excessive amount");
@@ -562,6 +669,45 @@ await
         console.log("Correctly rejected excessive unlock");
       }
     });
+
+    it("should reject a lock by an authorized key that did not sign", async () => {
+      const ix = await program.methods
+        .lockCollateral(
+          new anchor.BN(LOCK_AMOUNT),
+          await vaultNonce(user1VaultPda)
+        )
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .instruction();
+
+      try {
+        await sendWithoutAuthoritySignature(ix);
+        assert.fail("Should have rejected an unsigned authority");
+      } catch (error) {
+        assertNotSigner(error);
+        console.log(" Correctly rejected unsigned lock authority");
+      }
+    });
+
+    it("should reject an unlock by an authorized key that did not sign", async () => {
+      const ix = await program.methods
+        .unlockCollateral(new anchor.BN(1), await vaultNonce(user1VaultPda))
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .instruction();
+
+      try {
+        await sendWithoutAuthoritySignature(ix);
+        assert.fail("Should have rejected an unsigned authority");
+      } catch (error) {
+        assertNotSigner(error);
+        console.log(" Correctly rejected unsigned unlock authority");
+      }
+    });
   });
 
   describe("5. Transfer Between Vaults", async () => {
@@ -570,6 +716,7 @@ await
         .deposit(new anchor.BN(DEPOSIT_AMOUNT))
         .accounts({
           user: user2.publicKey,
+          vault: user2VaultPda,
           userTokenAccount: user2TokenAccount,
           vaultAta: user2VaultAta,
         })
@@ -585,16 +732,22 @@ await
       const vault2Before = await program.account.collateralVault.fetch(
         user2VaultPda
       );
+      const ata1Before = await getAccount(provider.connection, user1VaultAta);
+      const ata2Before = await getAccount(provider.connection, user2VaultAta);
 
       await program.methods
-        .transferCollateral(new anchor.BN(transfer_amount))
+        .transferCollateral(
+          new anchor.BN(transfer_amount),
+          await vaultNonce(user1VaultPda)
+        )
         .accounts({
           fromVault: user1VaultPda,
           fromVaultAta: user1VaultAta,
           toVault: user2VaultPda,
           toVaultAta: user2VaultAta,
-          authorityProgram: program.programId,
+          authorityProgram: lockAuthority.publicKey,
         })
+        .signers([lockAuthority])
         .rpc();
 
       const vault1After = await program.account.collateralVault.fetch(
@@ -624,6 +777,22 @@ await
         vault2Before.availableBalance.toNumber() + transfer_amount
       );
 
+      // Tokens actually settled: the vault PDA signed for its ATA
+      const ata1After = await getAccount(provider.connection, user1VaultAta);
+      const ata2After = await getAccount(provider.connection, user2VaultAta);
+      assert.equal(
+        Number(ata1After.amount),
+        Number(ata1Before.amount) - transfer_amount
+      );
+      assert.equal(
+        Number(ata2After.amount),
+        Number(ata2Before.amount) + transfer_amount
+      );
+      assert.equal(Number(ata1After.amount), vault1After.totalBalance.toNumber());
+      assert.equal(Number(ata2After.amount), vault2After.totalBalance.toNumber());
+      assertBalanceInvariant(vault1After);
+      assertBalanceInvariant(vault2After);
+
       console.log(
         " Transferred",
         transfer_amount / 1_000_000,
@@ -639,14 +808,18 @@ await
         vaultAccount.availableBalance.toNumber() + 1_000_000;
       try {
         await program.methods
-          .transferCollateral(new anchor.BN(excessive_amount))
+          .transferCollateral(
+            new anchor.BN(excessive_amount),
+            await vaultNonce(user1VaultPda)
+          )
           .accounts({
             fromVault: user1VaultPda,
             toVault: user2VaultPda,
             fromVaultAta: user1VaultAta,
             toVaultAta: user2VaultAta,
-            authorityProgram: program.programId,
+            authorityProgram: lockAuthority.publicKey,
           })
+          .signers([lockAuthority])
           .rpc();
 
         assert.fail("Should have failed to transfer excessive amount");
@@ -656,6 +829,30 @@ await
       }
     });
 
+    it("should reject a transfer by an authorized key that did not sign", async () => {
+      const ix = await program.methods
+        .transferCollateral(
+          new anchor.BN(1_000_000),
+          await vaultNonce(user1VaultPda)
+        )
+        .accounts({
+          fromVault: user1VaultPda,
+          fromVaultAta: user1VaultAta,
+          toVault: user2VaultPda,
+          toVaultAta: user2VaultAta,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .instruction();
+
+      try {
+        await sendWithoutAuthoritySignature(ix);
+        assert.fail("Should have rejected an unsigned authority");
+      } catch (error) {
+        assertNotSigner(error);
+        console.log(" Correctly rejected unsigned transfer authority");
+      }
+    });
+
     describe("6. Balance Invariants", async () => {
       it("Should maintain balance invariant: available + locked = total", async () => {
         const vault = await program.account.collateralVault.fetch(
@@ -701,11 +898,15 @@ await
 
       if (availableBalance > 0) {
         await program.methods
-          .lockCollateral(new anchor.BN(availableBalance))
+          .lockCollateral(
+            new anchor.BN(availableBalance),
+            await vaultNonce(user1VaultPda)
+          )
           .accounts({
             vault: user1VaultPda,
-            authorityProgram: program.programId,
+            authorityProgram: lockAuthority.publicKey,
           })
+          .signers([lockAuthority])
           .rpc();
       }
       try {
@@ -713,6 +914,7 @@ await
           .withdraw(new anchor.BN(100_000))
           .accounts({
             user: user1.publicKey,
+            vault: user1VaultPda,
             vaultAta: user1VaultAta,
             userTokenAccount: user1TokenAccount,
           })
@@ -728,64 +930,1335 @@ await
         user1VaultPda
       );
       await program.methods
-        .unlockCollateral(new anchor.BN(vaultAfterLock.lockedBalance))
+        .unlockCollateral(
+          new anchor.BN(vaultAfterLock.lockedBalance),
+          vaultAfterLock.nonce
+        )
         .accounts({
           vault: user1VaultPda,
-          authorityProgram: program.programId,
+          authorityProgram: lockAuthority.publicKey,
         })
+        .signers([lockAuthority])
         .rpc();
     });
   });
 
-  after(async () => {
-    console.log("\n Final Vault States:\n");
+  describe("8. Withdraw All", () => {
+    it("should withdraw the entire available balance", async () => {
+      await program.methods
+        .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          user: user2.publicKey,
+          vault: user2VaultPda,
+          vaultAta: user2VaultAta,
+          userTokenAccount: user2TokenAccount,
+        })
+        .signers([user2])
+        .rpc();
 
-    const vault1 = await program.account.collateralVault.fetch(user1VaultPda);
-    console.log("User1 Vault:");
-    console.log(
-      "  Total Balance:",
-      vault1.totalBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Available:",
-      vault1.availableBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Locked:",
-      vault1.lockedBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Total Deposited:",
-      vault1.totalDeposited.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Total Withdrawn:",
-      vault1.totalWithdrawn.toNumber() / 1_000_000,
-      "USDT"
-    );
+      const vaultBefore = await program.account.collateralVault.fetch(
+        user2VaultPda
+      );
+      const availableBefore = vaultBefore.availableBalance.toNumber();
+      const userBalanceBefore = await getAccount(
+        provider.connection,
+        user2TokenAccount
+      );
 
-    const vault2 = await program.account.collateralVault.fetch(user2VaultPda);
-    console.log("\nUser2 Vault:");
-    console.log(
-      "  Total Balance:",
-      vault2.totalBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Available:",
-      vault2.availableBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
-    console.log(
-      "  Locked:",
-      vault2.lockedBalance.toNumber() / 1_000_000,
-      "USDT"
-    );
+      await program.methods
+        .withdrawAll()
+        .accounts({
+          user: user2.publicKey,
+          vault: user2VaultPda,
+          vaultAta: user2VaultAta,
+          userTokenAccount: user2TokenAccount,
+        })
+        .signers([user2])
+        .rpc();
 
-    console.log("\n All tests completed!\n");
+      const vaultAfter = await program.account.collateralVault.fetch(
+        user2VaultPda
+      );
+      assert.equal(vaultAfter.availableBalance.toNumber(), 0);
+      assert.equal(
+        vaultAfter.totalBalance.toNumber(),
+        vaultAfter.lockedBalance.toNumber()
+      );
+
+      const userBalanceAfter = await getAccount(
+        provider.connection,
+        user2TokenAccount
+      );
+      assert.equal(
+        Number(userBalanceAfter.amount) - Number(userBalanceBefore.amount),
+        availableBefore
+      );
+      console.log(" Withdrew all", availableBefore / 1_000_000, "USDT");
+    });
+
+    it("should fail to withdraw all from an empty vault", async () => {
+      try {
+        await program.methods
+          .withdrawAll()
+          .accounts({
+            user: user2.publicKey,
+            vault: user2VaultPda,
+            vaultAta: user2VaultAta,
+            userTokenAccount: user2TokenAccount,
+          })
+          .signers([user2])
+          .rpc();
+
+        assert.fail("Should have failed with nothing available");
+      } catch (error) {
+        assert.ok(error.toString().includes("InsufficientBalance"));
+        console.log(" Correctly rejected withdraw-all with zero available");
+      }
+    });
+  });
+
+  describe("9. Liquidation", () => {
+    const LIQUIDATION_AMOUNT = 100 * 1_000_000;
+    let liquidatorTokenAccount: PublicKey;
+
+    before(async () => {
+      const liquidator = Keypair.generate();
+      const liquidatorAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        payer.payer,
+        usdtMint,
+        liquidator.publicKey
+      );
+      liquidatorTokenAccount = liquidatorAccount.address;
+
+      await program.methods
+        .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          user: user1.publicKey,
+          vault: user1VaultPda,
+          vaultAta: user1VaultAta,
+          userTokenAccount: user1TokenAccount,
+        })
+        .signers([user1])
+        .rpc();
+
+      await program.methods
+        .lockCollateral(
+          new anchor.BN(LOCK_AMOUNT),
+          await vaultNonce(user1VaultPda)
+        )
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+    });
+
+    it("should liquidate locked collateral by an authorized program", async () => {
+      const vaultBefore = await program.account.collateralVault.fetch(
+        user1VaultPda
+      );
+
+      await program.methods
+        .liquidateCollateral(
+          new anchor.BN(LIQUIDATION_AMOUNT),
+          await vaultNonce(user1VaultPda)
+        )
+        .accounts({
+          vault: user1VaultPda,
+          vaultAta: user1VaultAta,
+          liquidatorTokenAccount,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+
+      const vaultAfter = await program.account.collateralVault.fetch(
+        user1VaultPda
+      );
+      assert.equal(
+        vaultAfter.lockedBalance.toNumber(),
+        vaultBefore.lockedBalance.toNumber() - LIQUIDATION_AMOUNT
+      );
+      assert.equal(
+        vaultAfter.totalBalance.toNumber(),
+        vaultBefore.totalBalance.toNumber() - LIQUIDATION_AMOUNT
+      );
+      assert.equal(
+        vaultAfter.availableBalance.toNumber(),
+        vaultBefore.availableBalance.toNumber()
+      );
+
+      const liquidatorBalance = await getAccount(
+        provider.connection,
+        liquidatorTokenAccount
+      );
+      assert.equal(Number(liquidatorBalance.amount), LIQUIDATION_AMOUNT);
+      console.log(" Liquidated", LIQUIDATION_AMOUNT / 1_000_000, "USDT");
+    });
+
+    it("should fail when an unauthorized program liquidates", async () => {
+      try {
+        const outsider = Keypair.generate();
+        await program.methods
+          .liquidateCollateral(
+            new anchor.BN(LIQUIDATION_AMOUNT),
+            await vaultNonce(user1VaultPda)
+          )
+          .accounts({
+            vault: user1VaultPda,
+            vaultAta: user1VaultAta,
+            liquidatorTokenAccount,
+            authorityProgram: outsider.publicKey,
+          })
+          .signers([outsider])
+          .rpc();
+
+        assert.fail("Should have failed unauthorized liquidation");
+      } catch (error) {
+        assert.ok(error.toString().includes("ProgramNotAuthorized"));
+        console.log(" Correctly rejected unauthorized liquidation");
+      }
+    });
+
+    it("should reject a liquidation by an authorized key that did not sign", async () => {
+      const ix = await program.methods
+        .liquidateCollateral(
+          new anchor.BN(LIQUIDATION_AMOUNT),
+          await vaultNonce(user1VaultPda)
+        )
+        .accounts({
+          vault: user1VaultPda,
+          vaultAta: user1VaultAta,
+          liquidatorTokenAccount,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .instruction();
+
+      try {
+        await sendWithoutAuthoritySignature(ix);
+        assert.fail("Should have rejected an unsigned authority");
+      } catch (error) {
+        assertNotSigner(error);
+        console.log(" Correctly rejected unsigned liquidation authority");
+      }
+    });
+
+    it("should reject a replayed liquidation nonce", async () => {
+      const staleNonce = (await vaultNonce(user1VaultPda)).subn(1);
+
+      try {
+        await program.methods
+          .liquidateCollateral(new anchor.BN(LIQUIDATION_AMOUNT), staleNonce)
+          .accounts({
+            vault: user1VaultPda,
+            vaultAta: user1VaultAta,
+            liquidatorTokenAccount,
+            authorityProgram: lockAuthority.publicKey,
+          })
+          .signers([lockAuthority])
+          .rpc();
+
+        assert.fail("Should have rejected a replayed nonce");
+      } catch (error) {
+        assert.ok(error.toString().includes("InvalidNonce"), `${error}`);
+        console.log(" Correctly rejected replayed liquidation nonce");
+      }
+    });
+
+    it("should fail to liquidate more than locked balance", async () => {
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+
+      try {
+        await program.methods
+          .liquidateCollateral(
+            new anchor.BN(vault.lockedBalance.toNumber() + 1),
+            vault.nonce
+          )
+          .accounts({
+            vault: user1VaultPda,
+            vaultAta: user1VaultAta,
+            liquidatorTokenAccount,
+            authorityProgram: lockAuthority.publicKey,
+          })
+          .signers([lockAuthority])
+          .rpc();
+
+        assert.fail("Should have failed to liquidate excessive amount");
+      } catch (error) {
+        assert.ok(error.toString().includes("InsufficientLockedBalance"));
+        console.log(" Correctly rejected excessive liquidation");
+      }
+    });
+
+    after(async () => {
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+      await program.methods
+        .unlockCollateral(vault.lockedBalance, vault.nonce)
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+    });
+  });
+
+  describe("10. Ownership Transfer", () => {
+    let newOwner: Keypair;
+
+    before(() => {
+      newOwner = Keypair.generate();
+    });
+
+    it("should transfer ownership when signed by the owner", async () => {
+      await program.methods
+        .transferOwnership(newOwner.publicKey)
+        .accounts({
+          owner: user2.publicKey,
+          vault: user2VaultPda,
+        })
+        .signers([user2])
+        .rpc();
+
+      const vault = await program.account.collateralVault.fetch(user2VaultPda);
+      assert.ok(vault.owner.equals(newOwner.publicKey));
+      // PDA stays derived from the original creator
+      assert.ok(vault.creator.equals(user2.publicKey));
+      console.log(" Transferred vault ownership to", newOwner.publicKey.toBase58());
+    });
+
+    it("should fail when a non-owner transfers ownership", async () => {
+      try {
+        await program.methods
+          .transferOwnership(user2.publicKey)
+          .accounts({
+            owner: user2.publicKey, // No longer the owner
+            vault: user2VaultPda,
+          })
+          .signers([user2])
+          .rpc();
+
+        assert.fail("Should have failed non-owner ownership transfer");
+      } catch (error) {
+        assert.ok(error.toString().includes("UnAuthorized"));
+        console.log(" Correctly rejected non-owner ownership transfer");
+      }
+    });
+
+    after(async () => {
+      await program.methods
+        .transferOwnership(user2.publicKey)
+        .accounts({
+          owner: newOwner.publicKey,
+          vault: user2VaultPda,
+        })
+        .signers([newOwner])
+        .rpc();
+    });
+  });
+
+  describe("11. Batch Lock", () => {
+    const BATCH_LOCK_AMOUNT = 10 * 1_000_000;
+    let user3: Keypair;
+    let user3VaultPda: PublicKey;
+    let user3VaultAuthority: PublicKey;
+
+    const batchAccounts = (vaults: PublicKey[]) =>
+      vaults.flatMap((vault) => [
+        { pubkey: vault, isWritable: true, isSigner: false },
+        {
+          pubkey: PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_authority"), vault.toBuffer()],
+            program.programId
+          )[0],
+          isWritable: false,
+          isSigner: false,
+        },
+      ]);
+
+    before(async () => {
+      user3 = Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          user3.publicKey,
+          5 * anchor.web3.LAMPORTS_PER_SOL
+        )
+      );
+
+      const user3Account = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        payer.payer,
+        usdtMint,
+        user3.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        payer.payer,
+        usdtMint,
+        user3Account.address,
+        mintAuthority,
+        INITIAL_MINT
+      );
+
+      [user3VaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), user3.publicKey.toBuffer()],
+        program.programId
+      );
+      [user3VaultAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_authority"), user3VaultPda.toBuffer()],
+        program.programId
+      );
+      const user3VaultAta = await anchor.utils.token.associatedAddress({
+        mint: usdtMint,
+        owner: user3VaultPda,
+      });
+
+      await program.methods
+        .initializeVault(new anchor.BN(0), new anchor.BN(0))
+        .accounts({ user: user3.publicKey, mint: usdtMint })
+        .signers([user3])
+        .rpc();
+      await program.methods
+        .authorityToAdd(program.programId)
+        .accounts({ admin: user3.publicKey, vault: user3VaultPda })
+        .signers([user3])
+        .rpc();
+      await program.methods
+        .authorityToAdd(lockAuthority.publicKey)
+        .accounts({ admin: user3.publicKey, vault: user3VaultPda })
+        .signers([user3])
+        .rpc();
+      await program.methods
+        .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          user: user3.publicKey,
+          vault: user3VaultPda,
+          vaultAta: user3VaultAta,
+          userTokenAccount: user3Account.address,
+        })
+        .signers([user3])
+        .rpc();
+
+      // user2 withdrew everything earlier; top it up so it can be locked
+      await program.methods
+        .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          user: user2.publicKey,
+          vault: user2VaultPda,
+          vaultAta: user2VaultAta,
+          userTokenAccount: user2TokenAccount,
+        })
+        .signers([user2])
+        .rpc();
+    });
+
+    it("should lock collateral across 3 vaults in one instruction", async () => {
+      const vaults = [user1VaultPda, user2VaultPda, user3VaultPda];
+      const before = await Promise.all(
+        vaults.map((v) => program.account.collateralVault.fetch(v))
+      );
+
+      await program.methods
+        .batchLock(
+          vaults.map(() => new anchor.BN(BATCH_LOCK_AMOUNT)),
+          before.map((vault) => vault.nonce)
+        )
+        .accounts({ authorityProgram: lockAuthority.publicKey })
+        .remainingAccounts(batchAccounts(vaults))
+        .signers([lockAuthority])
+        .rpc();
+
+      const after = await Promise.all(
+        vaults.map((v) => program.account.collateralVault.fetch(v))
+      );
+      after.forEach((vault, i) => {
+        assert.equal(
+          vault.lockedBalance.toNumber(),
+          before[i].lockedBalance.toNumber() + BATCH_LOCK_AMOUNT
+        );
+        assert.equal(
+          vault.availableBalance.toNumber(),
+          before[i].availableBalance.toNumber() - BATCH_LOCK_AMOUNT
+        );
+      });
+      console.log(" Batch locked collateral in", vaults.length, "vaults");
+    });
+
+    it("should reject a batch above the cap", async () => {
+      const vaults = Array(11).fill(user3VaultPda);
+
+      try {
+        const nonce = await vaultNonce(user3VaultPda);
+        await program.methods
+          .batchLock(
+            vaults.map(() => new anchor.BN(1)),
+            vaults.map((_, i) => nonce.addn(i))
+          )
+          .accounts({ authorityProgram: lockAuthority.publicKey })
+          .remainingAccounts(batchAccounts(vaults))
+          .signers([lockAuthority])
+          .rpc();
+
+        assert.fail("Should have rejected an oversized batch");
+      } catch (error) {
+        assert.ok(error.toString().includes("BatchTooLarge"));
+        console.log(" Correctly rejected batch of", vaults.length, "vaults");
+      }
+    });
+
+    it("should reject a batch lock by an authorized key that did not sign", async () => {
+      const vaults = [user1VaultPda, user3VaultPda];
+      const ix = await program.methods
+        .batchLock(
+          vaults.map(() => new anchor.BN(BATCH_LOCK_AMOUNT)),
+          await Promise.all(vaults.map(vaultNonce))
+        )
+        .accounts({ authorityProgram: lockAuthority.publicKey })
+        .remainingAccounts(batchAccounts(vaults))
+        .instruction();
+
+      try {
+        await sendWithoutAuthoritySignature(ix);
+        assert.fail("Should have rejected an unsigned authority");
+      } catch (error) {
+        assertNotSigner(error);
+        console.log(" Correctly rejected unsigned batch lock authority");
+      }
+    });
+
+    it("should reject a batch with a replayed nonce", async () => {
+      const vaults = [user1VaultPda, user3VaultPda];
+      const before = await Promise.all(
+        vaults.map((v) => program.account.collateralVault.fetch(v))
+      );
+      // user3's nonce was consumed by the earlier batch
+      const nonces = [before[0].nonce, before[1].nonce.subn(1)];
+
+      try {
+        await program.methods
+          .batchLock(vaults.map(() => new anchor.BN(BATCH_LOCK_AMOUNT)), nonces)
+          .accounts({ authorityProgram: lockAuthority.publicKey })
+          .remainingAccounts(batchAccounts(vaults))
+          .signers([lockAuthority])
+          .rpc();
+
+        assert.fail("Should have rejected a replayed nonce");
+      } catch (error) {
+        assert.ok(error.toString().includes("InvalidNonce"), `${error}`);
+      }
+
+      const after = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.equal(
+        after.lockedBalance.toNumber(),
+        before[0].lockedBalance.toNumber(),
+        "the whole batch should roll back"
+      );
+      console.log(" Correctly rejected replayed batch lock nonce");
+    });
+
+    after(async () => {
+      for (const vault of [user1VaultPda, user2VaultPda, user3VaultPda]) {
+        const state = await program.account.collateralVault.fetch(vault);
+        await program.methods
+          .unlockCollateral(state.lockedBalance, state.nonce)
+          .accounts({ vault, authorityProgram: lockAuthority.publicKey })
+          .signers([lockAuthority])
+          .rpc();
+      }
+    });
+  });
+
+  describe("12. Deposit Limits", () => {
+    const MIN_DEPOSIT = 5 * 1_000_000;
+    const HEADROOM = 100 * 1_000_000;
+
+    const depositToUser1 = (amount: number) =>
+      program.methods
+        .deposit(new anchor.BN(amount))
+        .accounts({
+          user: user1.publicKey,
+          vault: user1VaultPda,
+          vaultAta: user1VaultAta,
+          userTokenAccount: user1TokenAccount,
+        })
+        .signers([user1])
+        .rpc();
+
+    before(async () => {
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+      await program.methods
+        .setVaultLimits(
+          new anchor.BN(MIN_DEPOSIT),
+          new anchor.BN(vault.totalBalance.toNumber() + HEADROOM)
+        )
+        .accounts({ owner: user1.publicKey, vault: user1VaultPda })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("should reject a deposit below the minimum", async () => {
+      try {
+        await depositToUser1(MIN_DEPOSIT - 1);
+        assert.fail("Should have rejected deposit below minimum");
+      } catch (error) {
+        assert.ok(error.toString().includes("BelowMinimumDeposit"));
+        console.log(" Correctly rejected deposit below minimum");
+      }
+    });
+
+    it("should reject a deposit above the maximum balance", async () => {
+      try {
+        await depositToUser1(HEADROOM + 1);
+        assert.fail("Should have rejected deposit over maximum balance");
+      } catch (error) {
+        assert.ok(error.toString().includes("ExceedsMaxBalance"));
+        console.log(" Correctly rejected deposit over maximum balance");
+      }
+    });
+
+    it("should accept a deposit within the limits", async () => {
+      const before = await program.account.collateralVault.fetch(user1VaultPda);
+
+      await depositToUser1(HEADROOM);
+
+      const after = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.equal(
+        after.totalBalance.toNumber(),
+        before.totalBalance.toNumber() + HEADROOM
+      );
+      assert.equal(after.minDeposit.toNumber(), MIN_DEPOSIT);
+      console.log(" Accepted in-range deposit of", HEADROOM / 1_000_000, "USDT");
+    });
+
+    after(async () => {
+      await program.methods
+        .setVaultLimits(new anchor.BN(0), new anchor.BN(0))
+        .accounts({ owner: user1.publicKey, vault: user1VaultPda })
+        .signers([user1])
+        .rpc();
+    });
+  });
+
+  describe("13. Global Pause", () => {
+    const setPaused = (paused: boolean, authority: Keypair = payer.payer) =>
+      program.methods
+        .setGlobalPause(paused)
+        .accounts({ authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+    it("should reject a pause from anyone but the config authority", async () => {
+      try {
+        await setPaused(true, user1);
+        assert.fail("Non-authority should not be able to pause");
+      } catch (error) {
+        assert.ok(error.toString().includes("UnAuthorized"));
+      }
+      const config = await program.account.programConfig.fetch(programConfig);
+      assert.isFalse(config.globalPaused);
+    });
+
+    it("should reject deposits while globally paused", async () => {
+      await setPaused(true);
+      try {
+        await program.methods
+          .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+          .accounts({
+            user: user1.publicKey,
+            vault: user1VaultPda,
+            userTokenAccount: user1TokenAccount,
+            vaultAta: user1VaultAta,
+          })
+          .signers([user1])
+          .rpc();
+        assert.fail("Deposit should fail while paused");
+      } catch (error) {
+        assert.ok(error.toString().includes("ProgramPaused"));
+      } finally {
+        await setPaused(false);
+      }
+      console.log("Deposit rejected while paused");
+    });
+
+    it("should accept deposits again once resumed", async () => {
+      const before = await program.account.collateralVault.fetch(user1VaultPda);
+      await program.methods
+        .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          user: user1.publicKey,
+          vault: user1VaultPda,
+          userTokenAccount: user1TokenAccount,
+          vaultAta: user1VaultAta,
+        })
+        .signers([user1])
+        .rpc();
+
+      const after = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.equal(
+        after.totalBalance.toNumber() - before.totalBalance.toNumber(),
+        DEPOSIT_AMOUNT
+      );
+    });
+  });
+
+  describe("14. Vault Nonce", () => {
+    it("should advance the nonce on a lock carrying the current nonce", async () => {
+      const nonce = await vaultNonce(user1VaultPda);
+
+      await program.methods
+        .lockCollateral(new anchor.BN(1_000_000), nonce)
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+
+      const after = await vaultNonce(user1VaultPda);
+      assert.equal(after.toNumber(), nonce.toNumber() + 1);
+      console.log(" Nonce advanced to", after.toNumber());
+    });
+
+    it("should reject an unlock replaying a consumed nonce", async () => {
+      const stale = (await vaultNonce(user1VaultPda)).subn(1);
+
+      try {
+        await program.methods
+          .unlockCollateral(new anchor.BN(1_000_000), stale)
+          .accounts({
+            vault: user1VaultPda,
+            authorityProgram: lockAuthority.publicKey,
+          })
+          .signers([lockAuthority])
+          .rpc();
+        assert.fail("Replayed nonce should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("InvalidNonce"));
+        console.log(" Correctly rejected replayed nonce");
+      }
+    });
+
+    after(async () => {
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+      await program.methods
+        .unlockCollateral(vault.lockedBalance, vault.nonce)
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+    });
+  });
+
+  describe("15. Authorized Program Cap", () => {
+    const MAX_AUTHORIZED_PROGRAMS = 8;
+    let user4: Keypair;
+    let user4VaultPda: PublicKey;
+    let user4VaultAuthority: PublicKey;
+
+    const authorize = (programId: PublicKey) =>
+      program.methods
+        .authorityToAdd(programId)
+        .accounts({ admin: user4.publicKey, vault: user4VaultPda })
+        .signers([user4])
+        .rpc();
+
+    before(async () => {
+      user4 = Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          user4.publicKey,
+          5 * anchor.web3.LAMPORTS_PER_SOL
+        )
+      );
+
+      [user4VaultPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), user4.publicKey.toBuffer()],
+        program.programId
+      );
+      [user4VaultAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_authority"), user4VaultPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initializeVault(new anchor.BN(0), new anchor.BN(0))
+        .accounts({ user: user4.publicKey, mint: usdtMint })
+        .signers([user4])
+        .rpc();
+    });
+
+    it("should authorize up to the cap", async () => {
+      for (let i = 0; i < MAX_AUTHORIZED_PROGRAMS; i++) {
+        await authorize(Keypair.generate().publicKey);
+      }
+
+      const va = await program.account.vaultAuthority.fetch(user4VaultAuthority);
+      assert.equal(va.authorizedPrograms.length, MAX_AUTHORIZED_PROGRAMS);
+      console.log(" Authorized", va.authorizedPrograms.length, "programs");
+    });
+
+    it("should reject a program beyond the cap", async () => {
+      try {
+        await authorize(Keypair.generate().publicKey);
+        assert.fail("Should have rejected a ninth program");
+      } catch (error) {
+        assert.ok(error.toString().includes("TooManyAuthorizedPrograms"));
+        console.log(" Correctly rejected program beyond the cap");
+      }
+
+      const va = await program.account.vaultAuthority.fetch(user4VaultAuthority);
+      assert.equal(va.authorizedPrograms.length, MAX_AUTHORIZED_PROGRAMS);
+    });
+  });
+
+  describe("16. Positions", () => {
+    const POSITION_ID = new anchor.BN(1);
+    const PLAIN_LOCK = new anchor.BN(1_000_000);
+    const POSITION_AMOUNT = new anchor.BN(2_000_000);
+    let positionPda: PublicKey;
+
+    const unlock = async (amount: anchor.BN) =>
+      program.methods
+        .unlockCollateral(amount, await vaultNonce(user1VaultPda))
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+
+    before(async () => {
+      [positionPda] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("position"),
+          user1VaultPda.toBuffer(),
+          POSITION_ID.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .lockCollateral(PLAIN_LOCK, await vaultNonce(user1VaultPda))
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+    });
+
+    it("should lock collateral against an open position", async () => {
+      const before = await program.account.collateralVault.fetch(user1VaultPda);
+
+      await program.methods
+        .openPosition(POSITION_ID, POSITION_AMOUNT, before.nonce)
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+          payer: provider.wallet.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+      const position = await program.account.position.fetch(positionPda);
+      assert.equal(
+        vault.lockedBalance.toNumber(),
+        before.lockedBalance.toNumber() + POSITION_AMOUNT.toNumber()
+      );
+      assert.equal(vault.positionLocked.toNumber(), POSITION_AMOUNT.toNumber());
+      assert.equal(position.amount.toNumber(), POSITION_AMOUNT.toNumber());
+      console.log(" Opened position holding", position.amount.toNumber());
+    });
+
+    it("should reject unlocking collateral of an open position", async () => {
+      await unlock(PLAIN_LOCK);
+
+      try {
+        await unlock(POSITION_AMOUNT);
+        assert.fail("Unlock of open position collateral should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("HasOpenPositions"));
+        console.log(" Correctly rejected unlock while the position is open");
+      }
+    });
+
+    it("should allow unlocking after the position is closed", async () => {
+      await program.methods
+        .closePosition()
+        .accounts({
+          vault: user1VaultPda,
+          authorityProgram: lockAuthority.publicKey,
+          position: positionPda,
+          payer: provider.wallet.publicKey,
+        })
+        .signers([lockAuthority])
+        .rpc();
+
+      const closed = await provider.connection.getAccountInfo(positionPda);
+      assert.isNull(closed);
+
+      await unlock(POSITION_AMOUNT);
+
+      const vault = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.equal(vault.lockedBalance.toNumber(), 0);
+      assert.equal(vault.positionLocked.toNumber(), 0);
+      console.log(" Unlocked collateral of the closed position");
+    });
+  });
+
+  describe("17. Mint Allowlist", () => {
+    let otherMint: PublicKey;
+
+    const setAllowedMints = (mints: PublicKey[], authority: Keypair = payer.payer) =>
+      program.methods
+        .setAllowedMints(mints)
+        .accounts({ authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+    const initializeFor = async (mint: PublicKey) => {
+      const user = Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          user.publicKey,
+          2 * anchor.web3.LAMPORTS_PER_SOL
+        )
+      );
+      await program.methods
+        .initializeVault(new anchor.BN(0), new anchor.BN(0))
+        .accounts({ user: user.publicKey, mint })
+        .signers([user])
+        .rpc();
+    };
+
+    before(async () => {
+      otherMint = await createMint(
+        provider.connection,
+        payer.payer,
+        mintAuthority.publicKey,
+        null,
+        6
+      );
+      await setAllowedMints([usdtMint]);
+    });
+
+    it("should reject allowlist changes from anyone but the config authority", async () => {
+      try {
+        await setAllowedMints([otherMint], user1);
+        assert.fail("Non-authority should not be able to change the allowlist");
+      } catch (error) {
+        assert.ok(error.toString().includes("UnAuthorized"));
+      }
+      const config = await program.account.programConfig.fetch(programConfig);
+      assert.equal(config.allowedMints.length, 1);
+      assert.ok(config.allowedMints[0].equals(usdtMint));
+    });
+
+    it("should initialize a vault for an allowed mint", async () => {
+      await initializeFor(usdtMint);
+      console.log(" Vault created for allowlisted mint");
+    });
+
+    it("should reject a vault for a mint not on the allowlist", async () => {
+      try {
+        await initializeFor(otherMint);
+        assert.fail("Vault should not be created for an unlisted mint");
+      } catch (error) {
+        assert.ok(error.toString().includes("MintNotAllowed"));
+        console.log(" Correctly rejected unlisted mint");
+      }
+    });
+
+    after(async () => {
+      await setAllowedMints([]);
+    });
+  });
+
+  describe("18. Sponsored Deposits", () => {
+    it("should credit another user's vault from the funder's token account", async () => {
+      const before = await program.account.collateralVault.fetch(user1VaultPda);
+      const funderBefore = await getAccount(provider.connection, user2TokenAccount);
+
+      await program.methods
+        .depositFor(new anchor.BN(DEPOSIT_AMOUNT))
+        .accounts({
+          funder: user2.publicKey,
+          vault: user1VaultPda,
+          funderTokenAccount: user2TokenAccount,
+          vaultAta: user1VaultAta,
+        })
+        .signers([user2])
+        .rpc();
+
+      const after = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.equal(
+        after.totalBalance.toNumber() - before.totalBalance.toNumber(),
+        DEPOSIT_AMOUNT
+      );
+      assert.equal(
+        after.availableBalance.toNumber() - before.availableBalance.toNumber(),
+        DEPOSIT_AMOUNT
+      );
+      assert.ok(after.owner.equals(before.owner));
+      assertBalanceInvariant(after);
+
+      const funderAfter = await getAccount(provider.connection, user2TokenAccount);
+      assert.equal(
+        Number(funderBefore.amount) - Number(funderAfter.amount),
+        DEPOSIT_AMOUNT
+      );
+      console.log(" User2 funded User1's vault");
+    });
+
+    it("should reject funding from a token account the funder does not own", async () => {
+      try {
+        await program.methods
+          .depositFor(new anchor.BN(DEPOSIT_AMOUNT))
+          .accounts({
+            funder: user2.publicKey,
+            vault: user1VaultPda,
+            funderTokenAccount: user1TokenAccount,
+            vaultAta: user1VaultAta,
+          })
+          .signers([user2])
+          .rpc();
+        assert.fail("Funder should not be able to spend another user's tokens");
+      } catch (error) {
+        assert.ok(error.toString().includes("InvalidTokenAccount"));
+        console.log(" Correctly rejected a foreign funding account");
+      }
+    });
+  });
+
+  describe("19. Authority Events", () => {
+    it("should emit AuthorityAddedEvent when a program is authorized", async () => {
+      const authorized = Keypair.generate().publicKey;
+
+      let listener: number;
+      const emitted = new Promise<any>((resolve) => {
+        listener = program.addEventListener("authorityAddedEvent", (event) => {
+          if (event.programId.equals(authorized)) resolve(event);
+        });
+      });
+
+      await program.methods
+        .authorityToAdd(authorized)
+        .accounts({ admin: user1.publicKey, vault: user1VaultPda })
+        .signers([user1])
+        .rpc();
+
+      const event = await emitted;
+      await program.removeEventListener(listener);
+
+      assert.ok(event.vault.equals(user1VaultPda));
+      assert.ok(event.programId.equals(authorized));
+      assert.ok(event.timestamp.toNumber() > 0);
+      console.log(" AuthorityAddedEvent emitted for", authorized.toBase58());
+    });
+  });
+
+  describe("20. Mint Mismatch", () => {
+    let foreignTokenAccount: PublicKey;
+
+    before(async () => {
+      const foreignMint = await createMint(
+        provider.connection,
+        payer.payer,
+        mintAuthority.publicKey,
+        null,
+        6
+      );
+      foreignTokenAccount = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          payer.payer,
+          foreignMint,
+          user1.publicKey
+        )
+      ).address;
+      await mintTo(
+        provider.connection,
+        payer.payer,
+        foreignMint,
+        foreignTokenAccount,
+        mintAuthority,
+        DEPOSIT_AMOUNT
+      );
+    });
+
+    it("should reject a deposit from a token account of another mint", async () => {
+      try {
+        await program.methods
+          .deposit(new anchor.BN(DEPOSIT_AMOUNT))
+          .accounts({
+            user: user1.publicKey,
+            vault: user1VaultPda,
+            userTokenAccount: foreignTokenAccount,
+            vaultAta: user1VaultAta,
+          })
+          .signers([user1])
+          .rpc();
+        assert.fail("Deposit of a foreign mint should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("MintMismatch"), `${error}`);
+        console.log(" Correctly rejected a foreign-mint deposit");
+      }
+    });
+
+    it("should reject a withdrawal to a token account of another mint", async () => {
+      try {
+        await program.methods
+          .withdraw(new anchor.BN(1))
+          .accounts({
+            user: user1.publicKey,
+            vault: user1VaultPda,
+            userTokenAccount: foreignTokenAccount,
+            vaultAta: user1VaultAta,
+          })
+          .signers([user1])
+          .rpc();
+        assert.fail("Withdrawal to a foreign mint should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("MintMismatch"), `${error}`);
+        console.log(" Correctly rejected a foreign-mint withdrawal");
+      }
+    });
+  });
+
+  describe("21. Activity Tracking", () => {
+    it("should advance last_activity on deposit", async () => {
+      const before = await program.account.collateralVault.fetch(user1VaultPda);
+      // Cluster time has one-second resolution
+      await new Promise((resolve) => setTimeout(resolve, 1500));
+
+      await program.methods
+        .deposit(new anchor.BN(1_000_000))
+        .accounts({
+          user: user1.publicKey,
+          vault: user1VaultPda,
+          userTokenAccount: user1TokenAccount,
+          vaultAta: user1VaultAta,
+        })
+        .signers([user1])
+        .rpc();
+
+      const after = await program.account.collateralVault.fetch(user1VaultPda);
+      assert.ok(
+        after.lastActivity.toNumber() > before.lastActivity.toNumber(),
+        `last_activity ${after.lastActivity} did not advance past ${before.lastActivity}`
+      );
+      assert.ok(after.lastActivity.toNumber() >= after.createdAt.toNumber());
+      console.log(" last_activity advanced to", after.lastActivity.toNumber());
+    });
+  });
+
+  describe("22. Withdraw To Destination", () => {
+    const AMOUNT = 1_000_000;
+
+    const withdrawTo = (destination: PublicKey, confirmForeign: boolean) =>
+      program.methods
+        .withdrawTo(new anchor.BN(AMOUNT), confirmForeign)
+        .accounts({
+          user: user1.publicKey,
+          vault: user1VaultPda,
+          vaultAta: user1VaultAta,
+          destinationTokenAccount: destination,
+        })
+        .signers([user1])
+        .rpc();
+
+    it("should keep withdraw restricted to the user's own token account", async () => {
+      try {
+        await program.methods
+          .withdraw(new anchor.BN(AMOUNT))
+          .accounts({
+            user: user1.publicKey,
+            vault: user1VaultPda,
+            userTokenAccount: user2TokenAccount,
+            vaultAta: user1VaultAta,
+          })
+          .signers([user1])
+          .rpc();
+        assert.fail("Withdraw to another wallet's account should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("InvalidTokenAccount"), `${error}`);
+        console.log(" Correctly rejected withdraw to another wallet's account");
+      }
+    });
+
+    it("should withdraw to the user's own account without confirmation", async () => {
+      const before = await getAccount(provider.connection, user1TokenAccount);
+      await withdrawTo(user1TokenAccount, false);
+      const after = await getAccount(provider.connection, user1TokenAccount);
+
+      assert.equal(Number(after.amount) - Number(before.amount), AMOUNT);
+      console.log(" Withdrew to own account without confirmation");
+    });
+
+    it("should reject an unconfirmed foreign destination", async () => {
+      try {
+        await withdrawTo(user2TokenAccount, false);
+        assert.fail("Unconfirmed foreign destination should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("ForeignDestinationNotConfirmed"), `${error}`);
+        console.log(" Correctly rejected an unconfirmed foreign destination");
+      }
+    });
+
+    it("should withdraw to a confirmed foreign destination", async () => {
+      const vaultBefore = await program.account.collateralVault.fetch(user1VaultPda);
+      const before = await getAccount(provider.connection, user2TokenAccount);
+      await withdrawTo(user2TokenAccount, true);
+      const after = await getAccount(provider.connection, user2TokenAccount);
+      const vaultAfter = await program.account.collateralVault.fetch(user1VaultPda);
+
+      assert.equal(Number(after.amount) - Number(before.amount), AMOUNT);
+      assert.equal(
+        vaultBefore.availableBalance.toNumber() - vaultAfter.availableBalance.toNumber(),
+        AMOUNT
+      );
+      console.log(" Withdrew to user2's token account from user1's vault");
+    });
+  });
+
+  after(async () => {
+    console.log("\n Final Vault States:\n");
+
+    const vault1 = await program.account.collateralVault.fetch(user1VaultPda);
+    console.log("User1 Vault:");
+    console.log(
+      "  Total Balance:",
+      vault1.totalBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Available:",
+      vault1.availableBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Locked:",
+      vault1.lockedBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Total Deposited:",
+      vault1.totalDeposited.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Total Withdrawn:",
+      vault1.totalWithdrawn.toNumber() / 1_000_000,
+      "USDT"
+    );
+
+    const vault2 = await program.account.collateralVault.fetch(user2VaultPda);
+    console.log("\nUser2 Vault:");
+    console.log(
+      "  Total Balance:",
+      vault2.totalBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Available:",
+      vault2.availableBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+    console.log(
+      "  Locked:",
+      vault2.lockedBalance.toNumber() / 1_000_000,
+      "USDT"
+    );
+
+    console.log("\n All tests completed!\n");
+  });
+
+  describe("23. Realloc Legacy Vault", () => {
+    // Owner of the v4 vault preloaded from tests/fixtures/legacy_v4_vault.json
+    const legacyOwner = Keypair.fromSeed(new Uint8Array(32).fill(7));
+    const [legacyVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), legacyOwner.publicKey.toBuffer()],
+      program.programId
+    );
+    const V4_SIZE = 8 + 178;
+    const CURRENT_SIZE = 8 + 186;
+
+    const reallocVault = (owner: Keypair) =>
+      program.methods
+        .reallocVault()
+        .accounts({ owner: owner.publicKey, vault: legacyVaultPda })
+        .signers([owner])
+        .rpc();
+
+    before(async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          legacyOwner.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        )
+      );
+    });
+
+    it("should reject realloc from anyone but the owner", async () => {
+      try {
+        await reallocVault(user1);
+        assert.fail("Realloc by a non-owner should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("UnAuthorized"), `${error}`);
+        console.log(" Correctly rejected realloc by a non-owner");
+      }
+    });
+
+    it("should grow a v4 vault without touching its data", async () => {
+      const before = await provider.connection.getAccountInfo(legacyVaultPda);
+      assert.equal(before.data.length, V4_SIZE);
+
+      await reallocVault(legacyOwner);
+
+      const after = await provider.connection.getAccountInfo(legacyVaultPda);
+      assert.equal(after.data.length, CURRENT_SIZE);
+      assert.ok(after.data.subarray(0, V4_SIZE).equals(before.data));
+      assert.ok(after.lamports > before.lamports, "owner should have topped up rent");
+      console.log(" Vault grew from", V4_SIZE, "to", after.data.length, "bytes");
+    });
+
+    it("should only realloc once", async () => {
+      try {
+        await reallocVault(legacyOwner);
+        assert.fail("Second realloc should be rejected");
+      } catch (error) {
+        assert.ok(error.toString().includes("AlreadyMigrated"), `${error}`);
+        console.log(" Correctly rejected a second realloc");
+      }
+    });
+
+    it("should migrate the grown vault with its fields preserved", async () => {
+      await program.methods
+        .migrateVault()
+        .accounts({ owner: legacyOwner.publicKey, vault: legacyVaultPda })
+        .signers([legacyOwner])
+        .rpc();
+
+      const vault = await program.account.collateralVault.fetch(legacyVaultPda);
+      assert.equal(vault.version, 5);
+      assert.ok(vault.owner.equals(legacyOwner.publicKey));
+      assert.ok(vault.creator.equals(legacyOwner.publicKey));
+      assert.equal(vault.totalBalance.toNumber(), 5_000_000);
+      assert.equal(vault.lockedBalance.toNumber(), 1_000_000);
+      assert.equal(vault.availableBalance.toNumber(), 4_000_000);
+      assert.equal(vault.totalDeposited.toNumber(), 6_000_000);
+      assert.equal(vault.totalWithdrawn.toNumber(), 1_000_000);
+      assert.equal(vault.createdAt.toNumber(), 1_700_000_000);
+      assert.equal(vault.minDeposit.toNumber(), 1_000);
+      assert.equal(vault.maxBalance.toNumber(), 0);
+      assert.equal(vault.nonce.toNumber(), 3);
+      assert.equal(vault.positionLocked.toNumber(), 1_000_000);
+      console.log(" Migrated the grown vault to v5 with its fields intact");
+    });
   });
 });
\ No newline at end of file